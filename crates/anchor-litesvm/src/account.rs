@@ -1,6 +1,7 @@
-use anchor_lang::AccountDeserialize;
+use anchor_lang::{AccountDeserialize, AccountSerialize, Discriminator, Owner};
 use litesvm::LiteSVM;
 use solana_program::pubkey::Pubkey;
+use solana_sdk::account::ReadableAccount;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -13,6 +14,25 @@ pub enum AccountError {
 
     #[error("Account discriminator mismatch")]
     DiscriminatorMismatch,
+
+    #[error("Failed to serialize account: {0}")]
+    SerializationError(String),
+
+    #[error("Failed to write account {0}: {1}")]
+    WriteError(Pubkey, String),
+}
+
+/// A deserialized Anchor account bundled with its on-chain metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedAccount<T> {
+    /// The deserialized account data
+    pub data: T,
+    /// The program that owns the account
+    pub owner: Pubkey,
+    /// The account balance in lamports
+    pub lamports: u64,
+    /// The length of the raw account data in bytes
+    pub data_len: usize,
 }
 
 /// Fetches and deserializes an Anchor account from LiteSVM
@@ -66,6 +86,147 @@ where
         .map_err(|e| AccountError::DeserializationError(e.to_string()))
 }
 
+/// Fetches and deserializes an Anchor account together with its owner, lamports and data length
+///
+/// The account data is deserialized with the discriminator check, like [`get_anchor_account`].
+pub fn get_anchor_account_full<T>(
+    svm: &LiteSVM,
+    address: &Pubkey,
+) -> Result<TypedAccount<T>, AccountError>
+where
+    T: AccountDeserialize,
+{
+    let account = svm
+        .get_account(address)
+        .ok_or(AccountError::AccountNotFound(*address))?;
+
+    let mut data_slice: &[u8] = &account.data;
+    let data = T::try_deserialize(&mut data_slice)
+        .map_err(|e| AccountError::DeserializationError(e.to_string()))?;
+
+    Ok(TypedAccount {
+        data,
+        owner: account.owner,
+        lamports: account.lamports,
+        data_len: account.data.len(),
+    })
+}
+
+/// Fetches and deserializes many Anchor accounts at once, like RPC `getMultipleAccounts`
+///
+/// Results are in the same order as `addresses`, with `None` for accounts that
+/// don't exist. Accounts that exist but fail to deserialize are an error.
+pub fn get_anchor_accounts<T>(
+    svm: &LiteSVM,
+    addresses: &[Pubkey],
+) -> Result<Vec<Option<T>>, AccountError>
+where
+    T: AccountDeserialize,
+{
+    addresses
+        .iter()
+        .map(|address| match get_anchor_account(svm, address) {
+            Ok(account) => Ok(Some(account)),
+            Err(AccountError::AccountNotFound(_)) => Ok(None),
+            Err(e) => Err(e),
+        })
+        .collect()
+}
+
+/// Serializes an Anchor account and writes it directly into LiteSVM
+///
+/// The account data is written with its discriminator, owned by the account
+/// type's program and funded with the rent-exempt minimum for its size. Any
+/// existing account at the address is replaced.
+pub fn set_anchor_account<T>(
+    svm: &mut LiteSVM,
+    address: &Pubkey,
+    account: &T,
+) -> Result<(), AccountError>
+where
+    T: AccountSerialize + Owner,
+{
+    let mut data = Vec::new();
+    account
+        .try_serialize(&mut data)
+        .map_err(|e| AccountError::SerializationError(e.to_string()))?;
+
+    let lamports = svm.minimum_balance_for_rent_exemption(data.len());
+    svm.set_account(
+        *address,
+        solana_sdk::account::Account {
+            lamports,
+            data,
+            owner: T::owner(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    )
+    .map_err(|e| AccountError::WriteError(*address, format!("{:?}", e)))
+}
+
+/// A filter for [`get_program_accounts`], mirroring RPC `getProgramAccounts` filters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AccountFilter {
+    /// Match accounts whose data is exactly this many bytes
+    DataSize(usize),
+    /// Match accounts whose data contains `bytes` at `offset`
+    Memcmp { offset: usize, bytes: Vec<u8> },
+}
+
+impl AccountFilter {
+    /// Create a memcmp filter
+    ///
+    /// Offsets are into the raw account data, so the 8-byte discriminator
+    /// must be included: the first field of an Anchor account is at offset 8.
+    pub fn memcmp(offset: usize, bytes: impl Into<Vec<u8>>) -> Self {
+        Self::Memcmp { offset, bytes: bytes.into() }
+    }
+
+    /// Check whether raw account data matches this filter
+    pub fn matches(&self, data: &[u8]) -> bool {
+        match self {
+            Self::DataSize(size) => data.len() == *size,
+            Self::Memcmp { offset, bytes } => data
+                .get(*offset..offset.saturating_add(bytes.len()))
+                .is_some_and(|slice| slice == bytes.as_slice()),
+        }
+    }
+}
+
+/// Fetches every Anchor account of type `T` owned by `T`'s program
+///
+/// Accounts are matched by owner and `T`'s discriminator, then by all of the given
+/// filters. Results are sorted by address so they are deterministic.
+pub fn get_program_accounts<T>(
+    svm: &LiteSVM,
+    filters: &[AccountFilter],
+) -> Result<Vec<(Pubkey, T)>, AccountError>
+where
+    T: AccountDeserialize + Discriminator + Owner,
+{
+    let owner = T::owner();
+    let mut matches: Vec<_> = svm
+        .accounts_db()
+        .inner
+        .iter()
+        .filter(|(_, account)| {
+            *account.owner() == owner
+                && account.data().starts_with(T::DISCRIMINATOR)
+                && filters.iter().all(|filter| filter.matches(account.data()))
+        })
+        .map(|(address, account)| {
+            let mut data_slice: &[u8] = account.data();
+            T::try_deserialize(&mut data_slice)
+                .map(|data| (*address, data))
+                .map_err(|e| AccountError::DeserializationError(e.to_string()))
+        })
+        .collect::<Result<_, _>>()?;
+
+    matches.sort_by_key(|(address, _)| *address);
+    Ok(matches)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -119,6 +280,112 @@ mod tests {
         }
     }
 
+    const TEST_PROGRAM_ID: Pubkey = Pubkey::from_str_const("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+    impl anchor_lang::AccountSerialize for TestAccount {
+        fn try_serialize<W: std::io::Write>(&self, writer: &mut W) -> anchor_lang::Result<()> {
+            writer
+                .write_all(Self::DISCRIMINATOR)
+                .and_then(|_| BorshSerialize::serialize(self, writer))
+                .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotSerialize.into())
+        }
+    }
+
+    impl anchor_lang::Owner for TestAccount {
+        fn owner() -> Pubkey {
+            TEST_PROGRAM_ID
+        }
+    }
+
+    #[test]
+    fn test_set_anchor_account() {
+        let mut svm = LiteSVM::new();
+        let addr = Pubkey::new_unique();
+        let test_account = TestAccount {
+            value: 7,
+            owner: Pubkey::new_unique(),
+        };
+
+        set_anchor_account(&mut svm, &addr, &test_account).unwrap();
+
+        let full = get_anchor_account_full::<TestAccount>(&svm, &addr).unwrap();
+        assert_eq!(full.data, test_account);
+        assert_eq!(full.owner, TEST_PROGRAM_ID);
+        assert_eq!(full.data_len, 8 + 8 + 32);
+        assert_eq!(full.lamports, svm.minimum_balance_for_rent_exemption(8 + 8 + 32));
+    }
+
+    #[test]
+    fn test_get_anchor_accounts() {
+        let mut svm = LiteSVM::new();
+        let first = Pubkey::new_unique();
+        let second = Pubkey::new_unique();
+        let missing = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        set_anchor_account(&mut svm, &first, &TestAccount { value: 1, owner }).unwrap();
+        set_anchor_account(&mut svm, &second, &TestAccount { value: 2, owner }).unwrap();
+
+        let accounts = get_anchor_accounts::<TestAccount>(&svm, &[second, missing, first]).unwrap();
+        assert_eq!(
+            accounts,
+            vec![
+                Some(TestAccount { value: 2, owner }),
+                None,
+                Some(TestAccount { value: 1, owner }),
+            ]
+        );
+
+        // An existing account with the wrong layout is an error, not None
+        svm.airdrop(&missing, 1_000_000).unwrap();
+        assert!(matches!(
+            get_anchor_accounts::<TestAccount>(&svm, &[first, missing]),
+            Err(AccountError::DeserializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_program_accounts() {
+        let mut svm = LiteSVM::new();
+        let alice = Pubkey::new_unique();
+        let bob = Pubkey::new_unique();
+        let mut expected = Vec::new();
+        for (value, owner) in [(1, alice), (2, alice), (3, bob), (4, alice)] {
+            let address = Pubkey::new_unique();
+            set_anchor_account(&mut svm, &address, &TestAccount { value, owner }).unwrap();
+            if owner == alice {
+                expected.push(address);
+            }
+        }
+        expected.sort();
+
+        // Same owning program but a different discriminator
+        let mut other = svm.get_account(&expected[0]).unwrap();
+        other.data[0] = 99;
+        svm.set_account(Pubkey::new_unique(), other).unwrap();
+
+        let all = get_program_accounts::<TestAccount>(&svm, &[]).unwrap();
+        assert_eq!(all.len(), 4);
+
+        let alice_accounts = get_program_accounts::<TestAccount>(
+            &svm,
+            &[AccountFilter::DataSize(48), AccountFilter::memcmp(16, alice.to_bytes())],
+        )
+        .unwrap();
+        let addresses: Vec<_> = alice_accounts.iter().map(|(address, _)| *address).collect();
+        assert_eq!(addresses, expected);
+        assert!(alice_accounts.iter().all(|(_, account)| account.owner == alice));
+
+        assert!(get_program_accounts::<TestAccount>(&svm, &[AccountFilter::DataSize(10)])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_account_filter_memcmp_out_of_bounds() {
+        assert!(!AccountFilter::memcmp(2, vec![1, 2]).matches(&[0, 0, 1]));
+        assert!(AccountFilter::memcmp(1, vec![1, 2]).matches(&[0, 1, 2]));
+    }
+
     #[test]
     fn test_get_anchor_account_with_discriminator() {
         let mut svm = LiteSVM::new();
@@ -227,6 +494,47 @@ mod tests {
         assert!(matches!(result.unwrap_err(), AccountError::DeserializationError(_)));
     }
 
+    #[test]
+    fn test_get_anchor_account_full() {
+        let mut svm = LiteSVM::new();
+        let addr = Pubkey::new_unique();
+        let program_owner = Pubkey::new_unique();
+
+        let test_account = TestAccount {
+            value: 7,
+            owner: Pubkey::new_unique(),
+        };
+        let mut data = TestAccount::DISCRIMINATOR.to_vec();
+        BorshSerialize::serialize(&test_account, &mut data).unwrap();
+        let data_len = data.len();
+
+        svm.set_account(
+            addr,
+            solana_sdk::account::Account {
+                lamports: 2_000_000,
+                data,
+                owner: program_owner,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let full: TypedAccount<TestAccount> = get_anchor_account_full(&svm, &addr).unwrap();
+        assert_eq!(full.data, test_account);
+        assert_eq!(full.owner, program_owner);
+        assert_eq!(full.lamports, 2_000_000);
+        assert_eq!(full.data_len, data_len);
+    }
+
+    #[test]
+    fn test_get_anchor_account_full_not_found() {
+        let svm = LiteSVM::new();
+        let result: Result<TypedAccount<TestAccount>, AccountError> =
+            get_anchor_account_full(&svm, &Pubkey::new_unique());
+        assert!(matches!(result.unwrap_err(), AccountError::AccountNotFound(_)));
+    }
+
     #[test]
     fn test_get_anchor_account_not_found() {
         let svm = LiteSVM::new();
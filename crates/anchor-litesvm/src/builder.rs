@@ -3,10 +3,14 @@
 //! This module provides builders specifically designed for Anchor programs,
 //! extending the base LiteSVM builder functionality.
 
+use crate::idl::Idl;
 use crate::AnchorContext;
 use litesvm_utils::LiteSVMBuilder;
+use solana_program::epoch_schedule::EpochSchedule;
 use solana_program::pubkey::Pubkey;
+use solana_program::sysvar::SysvarSerialize;
 use solana_sdk::signature::{Keypair, Signer};
+use std::collections::HashMap;
 
 /// Builder for creating an AnchorContext with programs pre-deployed
 ///
@@ -38,6 +42,20 @@ pub struct AnchorLiteSVM {
     svm_builder: LiteSVMBuilder,
     primary_program_id: Option<Pubkey>,
     payer: Option<Keypair>,
+    locked_accounts: Vec<Pubkey>,
+    forbidden_logs: Vec<String>,
+    deployed_programs: Vec<Pubkey>,
+    program_names: HashMap<String, Pubkey>,
+    idls: Vec<Idl>,
+    account_diffs: bool,
+    history: bool,
+    account_validation: bool,
+    sysvars: Vec<(Pubkey, solana_sdk::account::Account)>,
+    keypair_seed: u64,
+    #[cfg(feature = "rpc-clone")]
+    cloned_accounts: Vec<(Pubkey, solana_sdk::account::Account)>,
+    #[cfg(feature = "rpc-clone")]
+    rpc_cache_dir: std::path::PathBuf,
 }
 
 impl AnchorLiteSVM {
@@ -47,6 +65,20 @@ impl AnchorLiteSVM {
             svm_builder: LiteSVMBuilder::new(),
             primary_program_id: None,
             payer: None,
+            locked_accounts: Vec::new(),
+            forbidden_logs: Vec::new(),
+            deployed_programs: Vec::new(),
+            program_names: HashMap::new(),
+            idls: Vec::new(),
+            account_diffs: false,
+            history: false,
+            account_validation: true,
+            sysvars: Vec::new(),
+            keypair_seed: 0,
+            #[cfg(feature = "rpc-clone")]
+            cloned_accounts: Vec::new(),
+            #[cfg(feature = "rpc-clone")]
+            rpc_cache_dir: crate::rpc_clone::default_cache_dir(),
         }
     }
 
@@ -58,6 +90,44 @@ impl AnchorLiteSVM {
         self
     }
 
+    /// Simulate write-lock conflicts on the given accounts
+    ///
+    /// Any transaction executed through the resulting context that uses one of
+    /// these accounts as writable fails with `AccountInUse` without being processed.
+    /// This is an opt-in fault mode for exercising retry and backoff logic.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .with_locked_accounts(&[pool_pda])
+    ///     .build();
+    /// ```
+    pub fn with_locked_accounts(mut self, accounts: &[Pubkey]) -> Self {
+        self.locked_accounts.extend_from_slice(accounts);
+        self
+    }
+
+    /// Fail on transactions that emit any of the given log substrings
+    ///
+    /// Any transaction executed through the resulting context whose logs contain a
+    /// forbidden substring causes a panic, even if the transaction succeeded. Useful
+    /// for enforcing "no deprecation warnings" or "no debug logs" policies.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .with_strict_logs(vec!["deprecated".to_string(), "DEBUG".to_string()])
+    ///     .build();
+    /// ```
+    pub fn with_strict_logs(mut self, forbidden: Vec<String>) -> Self {
+        self.forbidden_logs.extend(forbidden);
+        self
+    }
+
     /// Add a program to be deployed
     ///
     /// The first program added becomes the primary program for the AnchorContext.
@@ -79,9 +149,216 @@ impl AnchorLiteSVM {
         }
 
         self.svm_builder = self.svm_builder.deploy_program(program_id, program_bytes);
+        self.deployed_programs.push(program_id);
+        self
+    }
+
+    /// Add a program to be deployed and register it under a name
+    ///
+    /// Named programs can be looked up with `AnchorContext::program_named`.
+    /// As with `deploy_program`, the first program added becomes the primary program.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let ctx = AnchorLiteSVM::new()
+    ///     .deploy_named_program("escrow", escrow_id, escrow_bytes)
+    ///     .deploy_named_program("oracle", oracle_id, oracle_bytes)
+    ///     .build();
+    /// let oracle = ctx.program_named("oracle").unwrap();
+    /// ```
+    pub fn deploy_named_program(mut self, name: &str, program_id: Pubkey, program_bytes: &[u8]) -> Self {
+        self.program_names.insert(name.to_string(), program_id);
+        self.deploy_program(program_id, program_bytes)
+    }
+
+    /// Record the writable accounts changed by each transaction
+    ///
+    /// See `AnchorContext::set_account_diffs`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .with_account_diffs()
+    ///     .build();
+    /// ```
+    pub fn with_account_diffs(mut self) -> Self {
+        self.account_diffs = true;
         self
     }
 
+    /// Don't check instruction account metas against loaded IDLs before sending
+    ///
+    /// See `AnchorContext::set_account_validation`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .with_idl(idl)
+    ///     .without_account_validation()
+    ///     .build();
+    /// ```
+    pub fn without_account_validation(mut self) -> Self {
+        self.account_validation = false;
+        self
+    }
+
+    /// Keep a record of every transaction executed through the context
+    ///
+    /// See `AnchorContext::set_history`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .with_history()
+    ///     .build();
+    /// ```
+    pub fn with_history(mut self) -> Self {
+        self.history = true;
+        self
+    }
+
+    /// Load an IDL so executed instructions are labelled with their IDL names
+    ///
+    /// See `AnchorContext::add_idl`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .with_idl(load_idl("target/idl/my_program.json")?)
+    ///     .build();
+    /// ```
+    pub fn with_idl(mut self, idl: Idl) -> Self {
+        self.idls.push(idl);
+        self
+    }
+
+    /// Set the seed used to derive named keypairs
+    ///
+    /// See `AnchorContext::keypair`. Defaults to 0.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .with_keypair_seed(42)
+    ///     .build();
+    /// let alice = ctx.keypair("alice");
+    /// ```
+    pub fn with_keypair_seed(mut self, seed: u64) -> Self {
+        self.keypair_seed = seed;
+        self
+    }
+
+    /// Use a custom epoch schedule instead of the default one
+    ///
+    /// Short epochs make per-epoch logic cheap to reach with `warp_to_epoch`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .with_epoch_schedule(EpochSchedule::custom(32, 32, false))
+    ///     .build();
+    /// ctx.svm.warp_to_epoch(5);
+    /// ```
+    pub fn with_epoch_schedule(self, epoch_schedule: EpochSchedule) -> Self {
+        self.with_sysvar(&epoch_schedule)
+    }
+
+    /// Override a sysvar before any transaction runs
+    ///
+    /// Works for any serializable sysvar, such as `Rent`, `EpochSchedule`,
+    /// `SlotHashes` or `Clock`. Use `AnchorContext::set_sysvar` to change one
+    /// mid-test.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .with_sysvar(&SlotHashes::new(&[(41, Hash::new_unique())]))
+    ///     .build();
+    /// ```
+    pub fn with_sysvar<T: SysvarSerialize>(mut self, sysvar: &T) -> Self {
+        // Sized like `LiteSVM::set_sysvar` so variable-length sysvars keep their full length
+        let mut account = solana_sdk::account::Account::new(1, T::size_of(), &solana_program::sysvar::id());
+        bincode::serialize_into(&mut account.data[..], sysvar).expect("Failed to serialize sysvar");
+        self.sysvars.retain(|(address, _)| *address != T::id());
+        self.sysvars.push((T::id(), account));
+        self
+    }
+
+    /// Set the directory where RPC responses are cached
+    ///
+    /// Defaults to `target/rpc-cache`. Each RPC endpoint gets its own
+    /// subdirectory. Requires the `rpc-clone` feature.
+    #[cfg(feature = "rpc-clone")]
+    pub fn with_rpc_cache_dir(mut self, dir: impl Into<std::path::PathBuf>) -> Self {
+        self.rpc_cache_dir = dir.into();
+        self
+    }
+
+    /// Clone an account from a live cluster into the test environment
+    ///
+    /// The account is fetched once and cached to disk; later runs use the cache.
+    /// Requires the `rpc-clone` feature.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .clone_account_from("https://api.mainnet-beta.solana.com", pool)?
+    ///     .build();
+    /// ```
+    #[cfg(feature = "rpc-clone")]
+    pub fn clone_account_from(
+        mut self,
+        rpc_url: &str,
+        address: Pubkey,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let account = crate::rpc_clone::fetch_account(rpc_url, &address, &self.rpc_cache_dir)?;
+        self.cloned_accounts.push((address, account));
+        Ok(self)
+    }
+
+    /// Clone a program binary from a live cluster and deploy it at the same address
+    ///
+    /// Upgradeable programs are resolved through their ProgramData account. The
+    /// binary is cached to disk like cloned accounts. Cloned programs never become
+    /// the primary program. Requires the `rpc-clone` feature.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let ctx = AnchorLiteSVM::new()
+    ///     .deploy_program(program_id, program_bytes)
+    ///     .clone_program_from("https://api.mainnet-beta.solana.com", raydium_amm_id)?
+    ///     .build();
+    /// ```
+    #[cfg(feature = "rpc-clone")]
+    pub fn clone_program_from(
+        mut self,
+        rpc_url: &str,
+        program_id: Pubkey,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let program_bytes = crate::rpc_clone::fetch_program(rpc_url, &program_id, &self.rpc_cache_dir)?;
+        self.svm_builder = self.svm_builder.deploy_program(program_id, &program_bytes);
+        self.deployed_programs.push(program_id);
+        Ok(self)
+    }
+
     /// Build the AnchorContext with all programs deployed
     ///
     /// # Returns
@@ -90,7 +367,8 @@ impl AnchorLiteSVM {
     ///
     /// # Panics
     ///
-    /// Panics if no programs were added
+    /// Panics if no programs were added, a loaded IDL has an invalid address or a
+    /// cloned account or sysvar can't be stored
     ///
     /// # Example
     ///
@@ -102,6 +380,15 @@ impl AnchorLiteSVM {
             .expect("No programs added. Call deploy_program() at least once.");
 
         let mut svm = self.svm_builder.build();
+        for (address, account) in self.sysvars {
+            svm.set_account(address, account)
+                .unwrap_or_else(|e| panic!("Failed to set sysvar {}: {:?}", address, e));
+        }
+        #[cfg(feature = "rpc-clone")]
+        for (address, account) in self.cloned_accounts {
+            svm.set_account(address, account)
+                .unwrap_or_else(|e| panic!("Failed to set cloned account {}: {:?}", address, e));
+        }
 
         // Create or use provided payer
         let payer = self.payer.unwrap_or_else(|| {
@@ -111,7 +398,26 @@ impl AnchorLiteSVM {
             payer
         });
 
-        AnchorContext::new_with_payer(svm, program_id, payer)
+        let mut ctx = AnchorContext::new_with_payer(svm, program_id, payer);
+        ctx.locked_accounts = self.locked_accounts;
+        ctx.forbidden_logs = self.forbidden_logs;
+        ctx.program_names = self.program_names;
+        ctx.record_account_changes = self.account_diffs;
+        ctx.record_history = self.history;
+        ctx.account_validation = self.account_validation;
+        ctx.keypair_seed = self.keypair_seed;
+        for idl in self.idls {
+            ctx.add_idl(idl).expect("Failed to load IDL");
+        }
+
+        // Record the deployment slot of every program deployed by the builder
+        let slot = ctx.svm.get_sysvar::<solana_program::clock::Clock>().slot;
+        ctx.deploy_slots = self
+            .deployed_programs
+            .into_iter()
+            .map(|program_id| (program_id, slot))
+            .collect();
+        ctx
     }
 
     /// Convenience method to quickly set up a single Anchor program
@@ -1,13 +1,29 @@
-use crate::account::AccountError;
+use crate::account::{
+    get_anchor_account_full, get_anchor_accounts, get_program_accounts, set_anchor_account, AccountError, AccountFilter,
+    TypedAccount,
+};
+use crate::events::{event_payloads, EventError};
+use crate::idl::{
+    decode_instruction, instruction_name_for, validate_instruction_accounts, Idl, IdlError, IdlProgram,
+};
+use crate::instruction::ping_instruction;
 use crate::program::Program;
-use anchor_lang::AccountDeserialize;
+use anchor_lang::{AccountDeserialize, AccountSerialize, AnchorDeserialize, Discriminator, Event, Owner};
+use litesvm::types::TransactionMetadata;
 use litesvm::LiteSVM;
 use solana_program::pubkey::Pubkey;
+use solana_program::instruction::Instruction;
+use solana_program::program_pack::Pack;
+use solana_program::sysvar::SysvarSerialize;
+use sha2::{Digest, Sha256};
 use solana_sdk::{
     signature::{Keypair, Signature, Signer},
-    transaction::Transaction,
+    transaction::{Transaction, TransactionError},
 };
-use litesvm_utils::TransactionResult;
+use litesvm_utils::{check_transaction_size, transaction_fee, AccountChange, TransactionResult};
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use std::collections::HashMap;
+use std::fmt::Debug;
 
 /// Production-compatible testing context for Anchor programs.
 ///
@@ -22,6 +38,82 @@ pub struct AnchorContext {
     payer: Keypair,
     /// The program instance for instruction building
     program: Program,
+    /// Compute units consumed by all transactions executed through this context
+    total_compute_units: u64,
+    /// Accounts that simulate a write-lock conflict when used as writable
+    pub(crate) locked_accounts: Vec<Pubkey>,
+    /// Log substrings that cause any executed transaction to panic
+    pub(crate) forbidden_logs: Vec<String>,
+    /// Slots at which programs were deployed through the builder or context
+    pub(crate) deploy_slots: HashMap<Pubkey, u64>,
+    /// Programs registered under a name, for lookup with `program_named`
+    pub(crate) program_names: HashMap<String, Pubkey>,
+    /// Loaded IDLs by program address, used to label executed instructions
+    pub(crate) idls: HashMap<Pubkey, Idl>,
+    /// Custom error names by code, used to decode failed transactions
+    error_names: HashMap<Pubkey, HashMap<u32, String>>,
+    /// Whether to record the writable accounts changed by each transaction
+    pub(crate) record_account_changes: bool,
+    /// Whether to check instruction account metas against loaded IDLs before sending
+    pub(crate) account_validation: bool,
+    /// Human-readable names for addresses, substituted in transaction output
+    labels: HashMap<Pubkey, String>,
+    /// Event payloads emitted by successful transactions, while recording is enabled
+    recorded_events: Option<Vec<Vec<u8>>>,
+    /// Seed mixed into every keypair derived with `keypair`
+    pub(crate) keypair_seed: u64,
+    /// Additional fee payers registered under a name
+    payers: HashMap<String, Keypair>,
+    /// Lamports airdropped to unfunded signers before each transaction, if enabled
+    auto_fund_lamports: Option<u64>,
+    /// Callbacks run before every transaction executed through this context
+    before_tx_hooks: Vec<BeforeTxHook>,
+    /// Callbacks run with the result of every transaction executed through this context
+    after_tx_hooks: Vec<AfterTxHook>,
+    /// Whether to keep a record of every transaction executed through this context
+    pub(crate) record_history: bool,
+    /// Transactions executed while history recording was enabled
+    history: Vec<TransactionRecord>,
+    /// Result of the most recent transaction executed through this context
+    last_result: Option<TransactionResult>,
+}
+
+/// Callback registered with [`AnchorContext::on_before_tx`]
+type BeforeTxHook = Box<dyn FnMut(&AnchorContext)>;
+
+/// Callback registered with [`AnchorContext::on_after_tx`]
+type AfterTxHook = Box<dyn FnMut(&AnchorContext, &TransactionResult)>;
+
+/// A transaction executed through an [`AnchorContext`] while history recording was enabled
+///
+/// See [`AnchorContext::history`].
+#[derive(Debug, Clone)]
+pub struct TransactionRecord {
+    /// The transaction signature
+    pub signature: Signature,
+    /// The name of every top-level instruction, from loaded IDLs where possible
+    pub instruction_names: Vec<String>,
+    /// The account keys of the transaction message
+    pub account_keys: Vec<Pubkey>,
+    /// Compute units consumed by the transaction
+    pub compute_units: u64,
+    /// The full transaction result
+    pub result: TransactionResult,
+}
+
+/// A saved copy of the SVM state, created with [`AnchorContext::snapshot`]
+///
+/// Captures every account, sysvar (including the clock), the compute units
+/// consumed so far, the transaction history, the last result, recorded events
+/// and address labels.
+#[derive(Clone)]
+pub struct Snapshot {
+    svm: LiteSVM,
+    total_compute_units: u64,
+    history: Vec<TransactionRecord>,
+    last_result: Option<TransactionResult>,
+    recorded_events: Option<Vec<Vec<u8>>>,
+    labels: HashMap<Pubkey, String>,
 }
 
 impl AnchorContext {
@@ -52,6 +144,25 @@ impl AnchorContext {
             program_id,
             payer,
             program,
+            total_compute_units: 0,
+            locked_accounts: Vec::new(),
+            forbidden_logs: Vec::new(),
+            deploy_slots: HashMap::new(),
+            program_names: HashMap::new(),
+            idls: HashMap::new(),
+            error_names: HashMap::new(),
+            record_account_changes: false,
+            account_validation: true,
+            labels: HashMap::new(),
+            recorded_events: None,
+            keypair_seed: 0,
+            payers: HashMap::new(),
+            auto_fund_lamports: None,
+            before_tx_hooks: Vec::new(),
+            after_tx_hooks: Vec::new(),
+            record_history: false,
+            history: Vec::new(),
+            last_result: None,
         }
     }
 
@@ -68,6 +179,25 @@ impl AnchorContext {
             program_id,
             payer,
             program,
+            total_compute_units: 0,
+            locked_accounts: Vec::new(),
+            forbidden_logs: Vec::new(),
+            deploy_slots: HashMap::new(),
+            program_names: HashMap::new(),
+            idls: HashMap::new(),
+            error_names: HashMap::new(),
+            record_account_changes: false,
+            account_validation: true,
+            labels: HashMap::new(),
+            recorded_events: None,
+            keypair_seed: 0,
+            payers: HashMap::new(),
+            auto_fund_lamports: None,
+            before_tx_hooks: Vec::new(),
+            after_tx_hooks: Vec::new(),
+            record_history: false,
+            history: Vec::new(),
+            last_result: None,
         }
     }
 
@@ -86,11 +216,117 @@ impl AnchorContext {
         self.program
     }
 
+    /// Get a Program handle for any deployed program
+    ///
+    /// # Returns
+    ///
+    /// The Program, or None if no executable account exists at the address
+    ///
+    /// # Example
+    /// ```ignore
+    /// let ix = ctx.program_at(escrow_id)
+    ///     .unwrap()
+    ///     .accounts(...)
+    ///     .args(...)
+    ///     .instruction()?;
+    /// ```
+    pub fn program_at(&self, program_id: Pubkey) -> Option<Program> {
+        let deployed = program_id == self.program_id
+            || self.deploy_slots.contains_key(&program_id)
+            || self.svm.get_account(&program_id).is_some_and(|a| a.executable);
+        deployed.then(|| Program::new(program_id))
+    }
+
+    /// Get a Program handle for a program registered under a name
+    ///
+    /// Programs are registered with `deploy_named_program` on the builder or context.
+    ///
+    /// # Returns
+    ///
+    /// The Program, or None if no program was registered under the name
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut ctx = AnchorLiteSVM::new()
+    ///     .deploy_named_program("escrow", escrow_id, escrow_bytes)
+    ///     .deploy_named_program("oracle", oracle_id, oracle_bytes)
+    ///     .build();
+    /// let oracle = ctx.program_named("oracle").unwrap();
+    /// ```
+    pub fn program_named(&self, name: &str) -> Option<Program> {
+        self.program_names.get(name).map(|id| Program::new(*id))
+    }
+
     /// Get the payer keypair
     pub fn payer(&self) -> &Keypair {
         &self.payer
     }
 
+    /// Register an additional payer under a name
+    ///
+    /// The payer is not funded; fund it before use. Its address is also
+    /// labelled with the name. Registering a name again replaces the payer.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let treasury = ctx.create_funded_account(10_000_000_000)?;
+    /// ctx.add_payer("treasury", treasury);
+    /// let treasury = ctx.payer_named("treasury").unwrap().insecure_clone();
+    /// ctx.execute_instruction_as(&treasury, ix, &[&user])?.assert_success();
+    /// ```
+    pub fn add_payer(&mut self, name: impl Into<String>, payer: Keypair) {
+        let name = name.into();
+        self.label(&payer.pubkey(), name.clone());
+        self.payers.insert(name, payer);
+    }
+
+    /// Get a payer registered with [`add_payer`](Self::add_payer)
+    pub fn payer_named(&self, name: &str) -> Option<&Keypair> {
+        self.payers.get(name)
+    }
+
+    /// Get the current lamport balance of the context payer
+    ///
+    /// Useful for debugging setups where later transactions fail with
+    /// insufficient funds. Returns 0 if the payer account no longer exists.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anchor_litesvm::AnchorContext;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+    /// println!("Payer has {} lamports left", ctx.payer_balance());
+    /// ```
+    pub fn payer_balance(&self) -> u64 {
+        self.svm.get_balance(&self.payer.pubkey()).unwrap_or(0)
+    }
+
+    /// Assert that the context payer has at least the given number of lamports
+    ///
+    /// # Panics
+    ///
+    /// Panics with the actual balance if the payer holds fewer lamports than expected
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anchor_litesvm::AnchorContext;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+    /// ctx.assert_payer_has(1_000_000_000);
+    /// ```
+    pub fn assert_payer_has(&self, lamports: u64) {
+        let actual = self.payer_balance();
+        assert!(
+            actual >= lamports,
+            "Payer {} has insufficient balance. Expected at least: {}, Actual: {}",
+            self.payer.pubkey(),
+            lamports,
+            actual
+        );
+    }
+
     /// Execute a single instruction using LiteSVM
     ///
     /// This is a convenience method for executing instructions.
@@ -110,6 +346,8 @@ impl AnchorContext {
         instruction: solana_program::instruction::Instruction,
         signers: &[&Keypair],
     ) -> Result<TransactionResult, Box<dyn std::error::Error>> {
+        self.validate_accounts(&instruction)?;
+
         // Determine the payer - use the first signer if provided, otherwise use the context's payer
         let payer_pubkey = if !signers.is_empty() {
             signers[0].pubkey()
@@ -119,153 +357,2721 @@ impl AnchorContext {
 
         // Build and sign the transaction
         let tx = Transaction::new_signed_with_payer(
-            &[instruction.clone()],
+            std::slice::from_ref(&instruction),
             Some(&payer_pubkey),
             signers,
             self.svm.latest_blockhash(),
         );
 
-        // Execute the transaction
-        match self.svm.send_transaction(tx) {
-            Ok(result) => Ok(TransactionResult::new(
-                result,
-                Some(format!("instruction to {}", instruction.program_id)),
-            )),
-            Err(failed) => Ok(TransactionResult::new_failed(
-                format!("{:?}", failed.err),
-                failed.meta,
-                Some(format!("instruction to {}", instruction.program_id)),
-            )),
-        }
+        check_transaction_size(&tx)?;
+        let name = self.instruction_label(&instruction);
+        Ok(self.process_transaction(tx, name))
     }
 
-    /// Execute multiple instructions in a single transaction
-    pub fn execute_instructions(
+    /// Execute a single instruction with an explicit fee payer
+    ///
+    /// Unlike [`execute_instruction`](Self::execute_instruction), the fee payer
+    /// doesn't have to be the first signer. The transaction is paid for by
+    /// `payer` and additionally signed by `signers`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = ctx.execute_instruction_as(&treasury, ix, &[&user])?;
+    /// result.assert_success().assert_fee_paid(5000);
+    /// ```
+    pub fn execute_instruction_as(
         &mut self,
-        instructions: Vec<solana_program::instruction::Instruction>,
+        payer: &Keypair,
+        instruction: Instruction,
         signers: &[&Keypair],
     ) -> Result<TransactionResult, Box<dyn std::error::Error>> {
-        // Determine the payer
+        self.validate_accounts(&instruction)?;
+
+        let mut all_signers = vec![payer];
+        all_signers.extend(signers.iter().filter(|s| s.pubkey() != payer.pubkey()));
+
+        let tx = Transaction::new_signed_with_payer(
+            std::slice::from_ref(&instruction),
+            Some(&payer.pubkey()),
+            &all_signers,
+            self.svm.latest_blockhash(),
+        );
+
+        check_transaction_size(&tx)?;
+        let name = self.instruction_label(&instruction);
+        Ok(self.process_transaction(tx, name))
+    }
+
+    /// Execute a single instruction, labelling the result with the given name
+    ///
+    /// The name appears in `assert_success()` failures and `print_logs()` output,
+    /// which identifies the failing instruction in tests that execute many of them.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = ctx.execute_instruction_named("initialize", ix, &[&user])?;
+    /// result.assert_success();
+    /// ```
+    pub fn execute_instruction_named(
+        &mut self,
+        name: &str,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<TransactionResult, Box<dyn std::error::Error>> {
+        self.validate_accounts(&instruction)?;
+
         let payer_pubkey = if !signers.is_empty() {
             signers[0].pubkey()
         } else {
             self.payer.pubkey()
         };
 
-        // Build and sign the transaction
         let tx = Transaction::new_signed_with_payer(
-            &instructions,
+            &[instruction],
             Some(&payer_pubkey),
             signers,
             self.svm.latest_blockhash(),
         );
 
-        // Execute the transaction
-        match self.svm.send_transaction(tx) {
-            Ok(result) => Ok(TransactionResult::new(
-                result,
-                Some("batch transaction".to_string()),
-            )),
-            Err(failed) => Ok(TransactionResult::new_failed(
-                format!("{:?}", failed.err),
-                failed.meta,
-                Some("batch transaction".to_string()),
-            )),
-        }
+        check_transaction_size(&tx)?;
+        Ok(self.process_transaction(tx, name.to_string()))
     }
 
-    /// Send and confirm a transaction (convenience method)
-    pub fn send_and_confirm_transaction(
-        &mut self,
-        transaction: &Transaction,
-    ) -> Result<Signature, Box<dyn std::error::Error>> {
-        match self.svm.send_transaction(transaction.clone()) {
-            Ok(_) => Ok(transaction.signatures[0]),
-            Err(e) => Err(format!("Transaction failed: {:?}", e).into()),
-        }
+    /// Load an IDL so executed instructions are labelled with their IDL names
+    ///
+    /// The IDL is registered for the program at its `address`. Instructions to that
+    /// program are then named from their discriminator in transaction results, and
+    /// their account metas are checked against the IDL before execution, so a missing
+    /// signer or writable flag fails with an `IdlError::AccountMismatch` naming the account.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.add_idl(load_idl("target/idl/my_program.json")?)?;
+    /// let result = ctx.execute_instruction(initialize_ix, &[&user])?;
+    /// assert_eq!(result.instruction_name(), Some("initialize"));
+    /// ```
+    pub fn add_idl(&mut self, idl: Idl) -> Result<(), IdlError> {
+        let address = idl
+            .address
+            .parse::<Pubkey>()
+            .map_err(|_| IdlError::InvalidAddress(idl.address.clone()))?;
+        self.register_errors(&address, idl.errors.iter().map(|e| (e.code, e.name.clone())));
+        self.idls.insert(address, idl);
+        Ok(())
     }
 
-    /// Get an Anchor account from the blockchain
+    /// Register names for a program's custom error codes
     ///
-    /// This fetches and deserializes an Anchor account from the current state.
+    /// Failed transactions whose custom error code is registered for the failing
+    /// program show the name in `assert_success()`, `assert_error()` and
+    /// `print_logs()`. Names are kept per program, since every Anchor program
+    /// numbers its errors from 6000. Errors from IDLs loaded with
+    /// [`AnchorContext::add_idl`] are registered automatically.
     ///
     /// # Example
-    /// ```no_run
-    /// # use anchor_litesvm::AnchorContext;
-    /// # use litesvm::LiteSVM;
-    /// # use solana_program::pubkey::Pubkey;
-    /// # use anchor_lang::AccountDeserialize;
-    /// # let svm = LiteSVM::new();
-    /// # let program_id = Pubkey::new_unique();
-    /// # let ctx = AnchorContext::new(svm, program_id);
-    /// # struct MyAccount {}
-    /// # impl AccountDeserialize for MyAccount {
-    /// #     fn try_deserialize(buf: &mut &[u8]) -> Result<Self, anchor_lang::error::Error> {
-    /// #         Ok(MyAccount {})
-    /// #     }
-    /// #     fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self, anchor_lang::error::Error> {
-    /// #         Ok(MyAccount {})
-    /// #     }
-    /// # }
-    /// let account_pubkey = Pubkey::new_unique();
-    /// let account: MyAccount = ctx.get_account(&account_pubkey).unwrap();
+    /// ```ignore
+    /// // Errors generated by declare_program!
+    /// ctx.register_errors(&my_program::ID, [
+    ///     (my_program::errors::MyError::InsufficientFunds as u32, "MyError::InsufficientFunds"),
+    /// ]);
     /// ```
-    pub fn get_account<T>(&self, address: &Pubkey) -> Result<T, AccountError>
-    where
-        T: AccountDeserialize,
-    {
-        let account_data = self.svm
-            .get_account(address)
-            .ok_or(AccountError::AccountNotFound(*address))?;
+    pub fn register_errors<S: Into<String>>(
+        &mut self,
+        program_id: &Pubkey,
+        errors: impl IntoIterator<Item = (u32, S)>,
+    ) {
+        self.error_names
+            .entry(*program_id)
+            .or_default()
+            .extend(errors.into_iter().map(|(code, name)| (code, name.into())));
+    }
 
-        // Deserialize the account data
-        let mut data = account_data.data.as_slice();
-        T::try_deserialize(&mut data).map_err(|e| AccountError::DeserializationError(e.to_string()))
+    /// Resolve a failed result's custom error code to a name registered for the failing program
+    fn with_program_error_names(&self, result: TransactionResult, program_ids: &[Pubkey]) -> TransactionResult {
+        match failing_program(&result, program_ids).and_then(|program_id| self.error_names.get(&program_id)) {
+            Some(names) => result.with_error_names(names),
+            None => result,
+        }
     }
 
-    /// Get an Anchor account without discriminator check
+    /// Get the loaded IDL for a program, if any
+    pub fn idl(&self, program_id: &Pubkey) -> Option<&Idl> {
+        self.idls.get(program_id)
+    }
+
+    /// Get a handle for building instructions from a loaded IDL by program name
     ///
-    /// Use this for accounts that don't have the standard Anchor discriminator.
+    /// The name is the `metadata.name` of the IDL, e.g. `my_program`. This allows
+    /// testing programs whose crates can't be imported.
     ///
-    /// Note: `try_deserialize_unchecked` handles the discriminator internally,
-    /// so we pass the full account data.
-    pub fn get_account_unchecked<T>(&self, address: &Pubkey) -> Result<T, AccountError>
-    where
-        T: AccountDeserialize,
-    {
-        let account_data = self.svm
-            .get_account(address)
-            .ok_or(AccountError::AccountNotFound(*address))?;
+    /// # Example
+    /// ```ignore
+    /// ctx.add_idl(load_idl("idls/escrow.json")?)?;
+    /// let ix = ctx.idl_program("escrow")?
+    ///     .instruction("make")
+    ///     .accounts_json(json!({ "maker": maker.pubkey().to_string() }))
+    ///     .args_json(json!({ "amount": 100 }))
+    ///     .instruction()?;
+    /// ctx.execute_instruction(ix, &[&maker])?.assert_success();
+    /// ```
+    pub fn idl_program(&self, name: &str) -> Result<IdlProgram<'_>, IdlError> {
+        let idl = self
+            .idls
+            .values()
+            .find(|idl| idl.metadata.name == name)
+            .ok_or_else(|| IdlError::ProgramNotFound(name.to_string()))?;
+        IdlProgram::new(idl)
+    }
 
-        // Deserialize without discriminator check
-        // Note: try_deserialize_unchecked handles the discriminator internally
-        let mut data = account_data.data.as_slice();
-        T::try_deserialize_unchecked(&mut data)
-            .map_err(|e| AccountError::DeserializationError(e.to_string()))
+    /// Decode instruction data for a program with a loaded IDL
+    ///
+    /// Returns the instruction name and its arguments as JSON, see
+    /// [`decode_instruction`](crate::idl::decode_instruction). Useful for
+    /// asserting on the CPIs a program makes.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let cpi = &result.cpis()[0];
+    /// let (name, args) = ctx.decode_instruction(&cpi.program_id, &cpi.data)?;
+    /// assert_eq!((name.as_str(), &args["amount"]), ("transfer", &json!(100)));
+    /// ```
+    pub fn decode_instruction(&self, program_id: &Pubkey, data: &[u8]) -> Result<(String, serde_json::Value), IdlError> {
+        let idl = self
+            .idls
+            .get(program_id)
+            .ok_or_else(|| IdlError::ProgramNotFound(program_id.to_string()))?;
+        decode_instruction(idl, data)
     }
 
-    /// Create a funded account (convenience method)
-    pub fn create_funded_account(&mut self, lamports: u64) -> Result<Keypair, Box<dyn std::error::Error>> {
-        let account = Keypair::new();
-        self.svm.airdrop(&account.pubkey(), lamports)
-            .map_err(|e| format!("Airdrop failed: {:?}", e))?;
-        Ok(account)
+    /// Check an instruction's account metas against its program's loaded IDL, if any
+    fn validate_accounts(&self, instruction: &Instruction) -> Result<(), IdlError> {
+        match self.idls.get(&instruction.program_id) {
+            Some(idl) if self.account_validation => validate_instruction_accounts(idl, instruction),
+            _ => Ok(()),
+        }
     }
 
-    /// Airdrop lamports to an account (convenience method)
-    pub fn airdrop(&mut self, pubkey: &Pubkey, lamports: u64) -> Result<(), Box<dyn std::error::Error>> {
-        self.svm.airdrop(pubkey, lamports)
-            .map_err(|e| format!("Airdrop failed: {:?}", e))?;
+    /// Check the account metas of every instruction in a transaction, see `validate_accounts`
+    fn validate_transaction_accounts(&self, tx: &Transaction) -> Result<(), IdlError> {
+        let message = &tx.message;
+        for ix in &message.instructions {
+            let accounts = ix
+                .accounts
+                .iter()
+                .map(|&index| {
+                    let index = index as usize;
+                    solana_program::instruction::AccountMeta {
+                        pubkey: message.account_keys[index],
+                        is_signer: message.is_signer(index),
+                        is_writable: message.is_maybe_writable(index, None),
+                    }
+                })
+                .collect();
+            self.validate_accounts(&Instruction {
+                program_id: message.account_keys[ix.program_id_index as usize],
+                accounts,
+                data: ix.data.clone(),
+            })?;
+        }
         Ok(())
     }
 
-    /// Get the latest blockhash
-    pub fn latest_blockhash(&self) -> solana_sdk::hash::Hash {
-        self.svm.latest_blockhash()
+    /// Name an instruction from a loaded IDL, falling back to its program id
+    fn instruction_label(&self, instruction: &Instruction) -> String {
+        self.instruction_name(&instruction.program_id, &instruction.data)
     }
 
-    /// Check if an account exists
-    pub fn account_exists(&self, pubkey: &Pubkey) -> bool {
-        self.svm.get_account(pubkey).is_some()
+    /// Name an instruction from its program's loaded IDL, falling back to the program address
+    fn instruction_name(&self, program_id: &Pubkey, data: &[u8]) -> String {
+        self.idls
+            .get(program_id)
+            .and_then(|idl| instruction_name_for(idl, data))
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("instruction to {}", program_id))
+    }
+
+    /// Execute multiple instructions in a single transaction
+    pub fn execute_instructions(
+        &mut self,
+        instructions: Vec<solana_program::instruction::Instruction>,
+        signers: &[&Keypair],
+    ) -> Result<TransactionResult, Box<dyn std::error::Error>> {
+        for instruction in &instructions {
+            self.validate_accounts(instruction)?;
+        }
+
+        // Determine the payer
+        let payer_pubkey = if !signers.is_empty() {
+            signers[0].pubkey()
+        } else {
+            self.payer.pubkey()
+        };
+
+        // Build and sign the transaction
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer_pubkey),
+            signers,
+            self.svm.latest_blockhash(),
+        );
+
+        check_transaction_size(&tx)?;
+        Ok(self.process_transaction(tx, "batch transaction".to_string()))
+    }
+
+    /// Simulate an instruction without committing any state changes
+    ///
+    /// The returned result carries the logs, compute units and return data the
+    /// instruction would produce, but no accounts are modified. Useful for read-only
+    /// "view" instructions and dry runs before executing for real.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = ctx.simulate_instruction(view_ix, &[&user])?;
+    /// result.assert_success();
+    /// println!("Would use {} CU", result.compute_units());
+    /// ```
+    pub fn simulate_instruction(
+        &self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<TransactionResult, Box<dyn std::error::Error>> {
+        self.validate_accounts(&instruction)?;
+
+        let payer_pubkey = if !signers.is_empty() {
+            signers[0].pubkey()
+        } else {
+            self.payer.pubkey()
+        };
+
+        let name = format!("simulation of {}", self.instruction_label(&instruction));
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer_pubkey),
+            signers,
+            self.svm.latest_blockhash(),
+        );
+        check_transaction_size(&tx)?;
+
+        let fee = transaction_fee(&tx);
+        let account_keys = tx.message.account_keys.clone();
+        let program_ids = top_level_program_ids(&tx);
+        let result = match self.svm.simulate_transaction(tx) {
+            Ok(info) => TransactionResult::new(info.meta, Some(name)),
+            Err(failed) => {
+                TransactionResult::new_failed(format!("{:?}", failed.err), failed.meta, Some(name))
+            }
+        };
+        let result = result.with_fee(fee).with_account_keys(account_keys).with_labels(&self.labels);
+        Ok(self.with_program_error_names(result, &program_ids))
     }
-}
\ No newline at end of file
+
+    /// Execute an instruction with an explicit fee payer and assert who paid the fee
+    ///
+    /// The transaction is paid for by `payer` and additionally signed by `signers`.
+    /// After execution, the balance of `expected_fee_payer` must have dropped by exactly
+    /// the transaction fee. This catches fee-payer wiring mistakes in relayer patterns.
+    ///
+    /// The instruction itself should not move lamports out of `expected_fee_payer`,
+    /// since that would be indistinguishable from the fee.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the balance delta of `expected_fee_payer` differs from the transaction fee
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = ctx.execute_and_assert_fee_payer(ix, &relayer, &[&user], &relayer.pubkey());
+    /// result.assert_success();
+    /// ```
+    pub fn execute_and_assert_fee_payer(
+        &mut self,
+        instruction: Instruction,
+        payer: &Keypair,
+        signers: &[&Keypair],
+        expected_fee_payer: &Pubkey,
+    ) -> TransactionResult {
+        if let Err(e) = self.validate_accounts(&instruction) {
+            panic!("{}", e);
+        }
+
+        let mut all_signers = vec![payer];
+        all_signers.extend(signers.iter().filter(|s| s.pubkey() != payer.pubkey()));
+
+        let tx = Transaction::new_signed_with_payer(
+            std::slice::from_ref(&instruction),
+            Some(&payer.pubkey()),
+            &all_signers,
+            self.svm.latest_blockhash(),
+        );
+        if let Err(e) = check_transaction_size(&tx) {
+            panic!("{}", e);
+        }
+        let fee = transaction_fee(&tx);
+
+        // Fund signers first so an airdrop isn't mistaken for a refund of the fee
+        self.fund_signers(&tx);
+        let balance_before = self.svm.get_balance(expected_fee_payer).unwrap_or(0);
+        let name = self.instruction_label(&instruction);
+        let result = self.process_transaction(tx, name);
+        let balance_after = self.svm.get_balance(expected_fee_payer).unwrap_or(0);
+
+        let delta = balance_before as i128 - balance_after as i128;
+        assert_eq!(
+            delta, fee as i128,
+            "Fee was not paid by {}. Expected balance decrease: {}, Actual: {}\nError: {:?}\nLogs:\n{}",
+            expected_fee_payer,
+            fee,
+            delta,
+            result.error(),
+            result.logs().join("\n")
+        );
+        result
+    }
+
+    /// Send a transaction through LiteSVM, running the registered hooks around it
+    fn process_transaction(&mut self, tx: Transaction, instruction_name: String) -> TransactionResult {
+        self.run_hooks(|ctx| &mut ctx.before_tx_hooks, |hook, ctx| hook(ctx));
+
+        let record = self.record_history.then(|| {
+            let instruction_names = tx
+                .message
+                .instructions
+                .iter()
+                .map(|ix| {
+                    let program_id = tx.message.account_keys[ix.program_id_index as usize];
+                    self.instruction_name(&program_id, &ix.data)
+                })
+                .collect();
+            (tx.signatures[0], instruction_names, tx.message.account_keys.clone())
+        });
+        let result = self.run_transaction(tx, instruction_name);
+        if let Some((signature, instruction_names, account_keys)) = record {
+            self.history.push(TransactionRecord {
+                signature,
+                instruction_names,
+                account_keys,
+                compute_units: result.compute_units(),
+                result: result.clone(),
+            });
+        }
+        self.last_result = Some(result.clone());
+
+        self.run_hooks(|ctx| &mut ctx.after_tx_hooks, |hook, ctx| hook(ctx, &result));
+        result
+    }
+
+    /// Run the hooks stored in `slot`, putting them back even if one of them panics
+    ///
+    /// Hooks are taken out of the context while they run so they can borrow it.
+    /// A panicking hook (such as a violated invariant) must not leave the context
+    /// without its hooks for the rest of the test.
+    fn run_hooks<H>(&mut self, slot: fn(&mut Self) -> &mut Vec<H>, mut run: impl FnMut(&mut H, &Self)) {
+        let mut hooks = std::mem::take(slot(self));
+        let outcome = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            for hook in &mut hooks {
+                run(hook, self);
+            }
+        }));
+        hooks.append(slot(self));
+        *slot(self) = hooks;
+        if let Err(panic) = outcome {
+            std::panic::resume_unwind(panic);
+        }
+    }
+
+    /// Airdrop to the transaction's unfunded signers, if auto-funding is enabled
+    fn fund_signers(&mut self, tx: &Transaction) {
+        let Some(lamports) = self.auto_fund_lamports else {
+            return;
+        };
+        let signer_count = tx.message.header.num_required_signatures as usize;
+        for signer in &tx.message.account_keys[..signer_count] {
+            if self.svm.get_balance(signer).unwrap_or(0) == 0 {
+                self.svm
+                    .airdrop(signer, lamports)
+                    .unwrap_or_else(|e| panic!("Failed to fund signer {}: {:?}", signer, e));
+            }
+        }
+    }
+
+    /// Send a transaction through LiteSVM and record its outcome on the context
+    fn run_transaction(&mut self, tx: Transaction, instruction_name: String) -> TransactionResult {
+        if self.has_lock_conflict(&tx) {
+            let result = TransactionResult::new_failed(
+                format!("{:?}", TransactionError::AccountInUse),
+                TransactionMetadata::default(),
+                Some(instruction_name),
+            );
+            return if self.record_account_changes {
+                result.with_account_changes(Vec::new())
+            } else {
+                result
+            };
+        }
+
+        self.fund_signers(&tx);
+
+        let writable: Vec<Pubkey> = if self.record_account_changes {
+            tx.message
+                .account_keys
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| tx.message.is_maybe_writable(*i, None))
+                .map(|(_, key)| *key)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let before: Vec<_> = writable.iter().map(|key| self.svm.get_account(key)).collect();
+
+        let fee = transaction_fee(&tx);
+        let account_keys = tx.message.account_keys.clone();
+        let program_ids = top_level_program_ids(&tx);
+        let result = match self.svm.send_transaction(tx) {
+            Ok(result) => TransactionResult::new(result, Some(instruction_name)),
+            Err(failed) => TransactionResult::new_failed(
+                format!("{:?}", failed.err),
+                failed.meta,
+                Some(instruction_name),
+            ),
+        }
+        .with_fee(fee)
+        .with_account_keys(account_keys)
+        .with_labels(&self.labels);
+        let mut result = self.with_program_error_names(result, &program_ids);
+
+        if self.record_account_changes {
+            let changes = writable
+                .iter()
+                .zip(before)
+                .filter_map(|(key, before)| account_change(*key, before, self.svm.get_account(key)))
+                .collect();
+            result = result.with_account_changes(changes);
+        }
+
+        self.total_compute_units += result.compute_units();
+        self.record_events(&result);
+        self.check_forbidden_logs(&result);
+        result
+    }
+
+    /// Record the writable accounts changed by each transaction
+    ///
+    /// When enabled, results of transactions executed through this context expose
+    /// `account_changes()` with lamport, data-length and owner changes.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.set_account_diffs(true);
+    /// let result = ctx.execute_instruction(deposit_ix, &[&user])?;
+    /// let changed: Vec<_> = result.account_changes().iter().map(|c| c.address).collect();
+    /// assert_eq!(changed, vec![user.pubkey(), vault]);
+    /// ```
+    pub fn set_account_diffs(&mut self, enabled: bool) {
+        self.record_account_changes = enabled;
+    }
+
+    /// Airdrop lamports to every signer with a zero balance before each transaction
+    ///
+    /// Saves funding each new keypair by hand, whose failure mode is an
+    /// `AccountNotFound` error. Signers that hold any lamports are left alone.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.auto_fund_signers(1_000_000_000);
+    /// let user = Keypair::new();
+    /// ctx.execute_instruction(ix, &[&user])?.assert_success();
+    /// ```
+    pub fn auto_fund_signers(&mut self, lamports: u64) {
+        self.auto_fund_lamports = Some(lamports);
+    }
+
+    /// Stop airdropping to unfunded signers, see [`auto_fund_signers`](Self::auto_fund_signers)
+    pub fn disable_auto_fund_signers(&mut self) {
+        self.auto_fund_lamports = None;
+    }
+
+    /// Check instruction account metas against loaded IDLs before sending
+    ///
+    /// Enabled by default. Disable it to deliberately send a missing signer or
+    /// writable flag and assert that the program itself rejects it.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.set_account_validation(false);
+    /// let mut ix = withdraw_ix();
+    /// ix.accounts[0].is_signer = false;
+    /// ctx.execute_instruction(ix, &[])?.assert_error("AccountNotSigner");
+    /// ```
+    pub fn set_account_validation(&mut self, enabled: bool) {
+        self.account_validation = enabled;
+    }
+
+    /// Register a callback that runs before every transaction executed through this context
+    ///
+    /// Hooks run in registration order, including for transactions sent by
+    /// assertion helpers such as `execute_and_assert_readonly`. Simulations don't
+    /// run hooks.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.on_before_tx(|ctx| assert!(ctx.payer_balance() > 1_000_000_000, "payer running low"));
+    /// ```
+    pub fn on_before_tx(&mut self, hook: impl FnMut(&AnchorContext) + 'static) {
+        self.before_tx_hooks.push(Box::new(hook));
+    }
+
+    /// Register a callback that runs with the result of every transaction executed through this context
+    ///
+    /// # Example
+    /// ```ignore
+    /// let compute = Rc::new(RefCell::new(Vec::new()));
+    /// let recorded = compute.clone();
+    /// ctx.on_after_tx(move |_, result| recorded.borrow_mut().push(result.compute_units()));
+    /// ```
+    pub fn on_after_tx(&mut self, hook: impl FnMut(&AnchorContext, &TransactionResult) + 'static) {
+        self.after_tx_hooks.push(Box::new(hook));
+    }
+
+    /// Register an invariant that must hold after every successful transaction
+    ///
+    /// The check runs as an [`on_after_tx`](Self::on_after_tx) hook and returns
+    /// an error describing the violation. Failed transactions are skipped since
+    /// they don't change state.
+    ///
+    /// # Panics
+    ///
+    /// The transaction that broke the invariant panics, naming the invariant and
+    /// the instruction
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.add_invariant("supply_conserved", move |ctx| {
+    ///     let supply = ctx.svm.get_account(&mint).map(|a| Mint::unpack(&a.data).unwrap().supply);
+    ///     match supply {
+    ///         Some(1_000_000) => Ok(()),
+    ///         other => Err(format!("supply is {:?}", other)),
+    ///     }
+    /// });
+    /// ```
+    pub fn add_invariant(
+        &mut self,
+        name: impl Into<String>,
+        check: impl Fn(&AnchorContext) -> Result<(), String> + 'static,
+    ) {
+        let name = name.into();
+        self.on_after_tx(move |ctx, result| {
+            if !result.is_success() {
+                return;
+            }
+            if let Err(reason) = check(ctx) {
+                panic!(
+                    "Invariant `{}` violated after {}: {}\nLogs:\n{}",
+                    name,
+                    result.instruction_name().unwrap_or("transaction"),
+                    reason,
+                    result.logs().join("\n")
+                );
+            }
+        });
+    }
+
+    /// Keep a record of every transaction executed through this context
+    ///
+    /// Useful for auditing what actually ran when an assertion at the end of a
+    /// long scenario fails. Disabling recording keeps the existing history.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.set_history(true);
+    /// run_scenario(&mut ctx);
+    /// for record in ctx.history() {
+    ///     println!("{} {:?} {} CU", record.signature, record.instruction_names, record.compute_units);
+    /// }
+    /// ```
+    pub fn set_history(&mut self, enabled: bool) {
+        self.record_history = enabled;
+    }
+
+    /// Get the transactions recorded since [`set_history`](Self::set_history) was enabled
+    pub fn history(&self) -> &[TransactionRecord] {
+        &self.history
+    }
+
+    /// Discard the recorded transaction history
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Get the result of the most recent transaction executed through this context
+    ///
+    /// Available whether or not history recording is enabled.
+    pub fn last_result(&self) -> Option<&TransactionResult> {
+        self.last_result.as_ref()
+    }
+
+    /// Register a human-readable name for an address
+    ///
+    /// Results of transactions executed through this context show the label
+    /// instead of the base58 address in `print_logs`, `print_account_changes`
+    /// and assertion panic messages. Registering an address again replaces its label.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.label(&vault_pda, "vault");
+    /// ctx.label(&user.pubkey(), "user");
+    /// ctx.execute_instruction(withdraw_ix, &[&user])?.print_logs();
+    /// ```
+    pub fn label(&mut self, address: &Pubkey, name: impl Into<String>) {
+        self.labels.insert(*address, name.into());
+    }
+
+    /// Get the labels registered with [`label`](Self::label)
+    pub fn labels(&self) -> &HashMap<Pubkey, String> {
+        &self.labels
+    }
+
+    /// Derive a keypair deterministically from a name and the context's keypair seed
+    ///
+    /// The same name and seed always give the same keypair, so addresses in
+    /// logs and fixtures stay identical across runs.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let alice = ctx.keypair("alice");
+    /// assert_eq!(alice.pubkey(), ctx.keypair("alice").pubkey());
+    /// ```
+    pub fn keypair(&self, name: &str) -> Keypair {
+        let mut hasher = Sha256::new();
+        hasher.update(format!("{}:{}", self.keypair_seed, name));
+        Keypair::new_from_array(hasher.finalize().into())
+    }
+
+    /// Set the seed used by [`keypair`](Self::keypair)
+    ///
+    /// Defaults to 0. Changing it gives every name a different keypair.
+    pub fn set_keypair_seed(&mut self, seed: u64) {
+        self.keypair_seed = seed;
+    }
+
+    /// Start recording the events emitted by every transaction executed through this context
+    ///
+    /// Only successful transactions are recorded, since a failed transaction's
+    /// events never happened on chain. Events already recorded are kept; use
+    /// [`clear_recorded_events`](Self::clear_recorded_events) to start over.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.start_event_recording();
+    /// ctx.execute_instruction(deposit_ix, &[&user])?.assert_success();
+    /// ctx.execute_instruction(withdraw_ix, &[&user])?.assert_success();
+    /// assert_eq!(ctx.recorded_events::<TransferEvent>()?.len(), 2);
+    /// ```
+    pub fn start_event_recording(&mut self) {
+        self.recorded_events.get_or_insert_with(Vec::new);
+    }
+
+    /// Stop recording events and discard the ones recorded so far
+    pub fn stop_event_recording(&mut self) {
+        self.recorded_events = None;
+    }
+
+    /// Discard the events recorded so far, without stopping recording
+    pub fn clear_recorded_events(&mut self) {
+        if let Some(events) = &mut self.recorded_events {
+            events.clear();
+        }
+    }
+
+    /// Get the recorded events of a specific type, in emission order
+    ///
+    /// Returns an empty list if recording was never started.
+    pub fn recorded_events<T>(&self) -> Result<Vec<T>, EventError>
+    where
+        T: AnchorDeserialize + Discriminator + Event,
+    {
+        self.recorded_events
+            .iter()
+            .flatten()
+            .filter(|payload| payload.len() >= 8 && &payload[..8] == T::DISCRIMINATOR)
+            .map(|payload| T::deserialize(&mut &payload[8..]).map_err(|e| EventError::AnchorError(e.to_string())))
+            .collect()
+    }
+
+    /// Get the number of events of every type recorded so far
+    pub fn recorded_event_count(&self) -> usize {
+        self.recorded_events.as_ref().map_or(0, Vec::len)
+    }
+
+    /// Append the events emitted by a successful transaction when recording is enabled
+    fn record_events(&mut self, result: &TransactionResult) {
+        let Some(events) = &mut self.recorded_events else {
+            return;
+        };
+        if result.is_success() {
+            if let Ok(payloads) = event_payloads(result) {
+                events.extend(payloads.into_iter().map(|(_, payload)| payload));
+            }
+        }
+    }
+
+    /// Panic if the transaction logged anything configured as forbidden
+    fn check_forbidden_logs(&self, result: &TransactionResult) {
+        for forbidden in &self.forbidden_logs {
+            if let Some(line) = result.logs().iter().find(|log| log.contains(forbidden.as_str())) {
+                panic!(
+                    "Forbidden log '{}' found: {}\nLogs:\n{}",
+                    forbidden,
+                    line,
+                    result.logs().join("\n")
+                );
+            }
+        }
+    }
+
+    /// Check whether a transaction writes to any account configured as locked
+    fn has_lock_conflict(&self, tx: &Transaction) -> bool {
+        tx.message
+            .account_keys
+            .iter()
+            .enumerate()
+            .any(|(i, key)| {
+                tx.message.is_maybe_writable(i, None) && self.locked_accounts.contains(key)
+            })
+    }
+
+    /// Send and confirm a transaction (convenience method)
+    pub fn send_and_confirm_transaction(
+        &mut self,
+        transaction: &Transaction,
+    ) -> Result<Signature, Box<dyn std::error::Error>> {
+        self.validate_transaction_accounts(transaction)?;
+        check_transaction_size(transaction)?;
+        let result = self.process_transaction(transaction.clone(), "transaction".to_string());
+        match result.error() {
+            None => Ok(transaction.signatures[0]),
+            Some(e) => Err(format!("Transaction failed: {}", e).into()),
+        }
+    }
+
+    /// Get the total compute units consumed by every transaction executed through this context
+    ///
+    /// Failed transactions are included, since they still consume compute.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.execute_instruction(deposit_ix, &[&user])?.assert_success();
+    /// ctx.execute_instruction(withdraw_ix, &[&user])?.assert_success();
+    /// println!("Whole flow used {} CU", ctx.total_compute_units());
+    /// ```
+    pub fn total_compute_units(&self) -> u64 {
+        self.total_compute_units
+    }
+
+    /// Save the current SVM state so it can be restored later
+    ///
+    /// Useful for running many variations from the same expensive setup.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let snapshot = ctx.snapshot();
+    /// for amount in [0, u64::MAX] {
+    ///     ctx.restore(&snapshot);
+    ///     ctx.execute_instruction(deposit_ix(amount), &[&user])?.assert_failure();
+    /// }
+    /// ```
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            svm: self.svm.clone(),
+            total_compute_units: self.total_compute_units,
+            history: self.history.clone(),
+            last_result: self.last_result.clone(),
+            recorded_events: self.recorded_events.clone(),
+            labels: self.labels.clone(),
+        }
+    }
+
+    /// Restore the SVM state saved by [`AnchorContext::snapshot`]
+    ///
+    /// All accounts, sysvars and the clock are reset to the snapshot, along with
+    /// the transaction history, last result, recorded events and labels, so
+    /// nothing from the discarded branch is visible afterwards. Settings such as
+    /// hooks, registered payers and whether history or events are recorded are
+    /// kept. The same snapshot can be restored any number of times.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.svm = snapshot.svm.clone();
+        self.total_compute_units = snapshot.total_compute_units;
+        self.history = snapshot.history.clone();
+        self.last_result = snapshot.last_result.clone();
+        self.recorded_events = snapshot.recorded_events.clone();
+        self.labels = snapshot.labels.clone();
+    }
+
+    /// Assert that the total compute units consumed so far are below a budget
+    ///
+    /// # Panics
+    ///
+    /// Panics if the accumulated compute units are greater than or equal to `limit`
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.assert_total_compute_below(400_000);
+    /// ```
+    pub fn assert_total_compute_below(&self, limit: u64) {
+        assert!(
+            self.total_compute_units < limit,
+            "Total compute units exceeded budget. Limit: {}, Actual: {}",
+            limit,
+            self.total_compute_units
+        );
+    }
+
+    /// Get an Anchor account from the blockchain
+    ///
+    /// This fetches and deserializes an Anchor account from the current state.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anchor_litesvm::AnchorContext;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # use anchor_lang::AccountDeserialize;
+    /// # let svm = LiteSVM::new();
+    /// # let program_id = Pubkey::new_unique();
+    /// # let ctx = AnchorContext::new(svm, program_id);
+    /// # struct MyAccount {}
+    /// # impl AccountDeserialize for MyAccount {
+    /// #     fn try_deserialize(buf: &mut &[u8]) -> Result<Self, anchor_lang::error::Error> {
+    /// #         Ok(MyAccount {})
+    /// #     }
+    /// #     fn try_deserialize_unchecked(buf: &mut &[u8]) -> Result<Self, anchor_lang::error::Error> {
+    /// #         Ok(MyAccount {})
+    /// #     }
+    /// # }
+    /// let account_pubkey = Pubkey::new_unique();
+    /// let account: MyAccount = ctx.get_account(&account_pubkey).unwrap();
+    /// ```
+    pub fn get_account<T>(&self, address: &Pubkey) -> Result<T, AccountError>
+    where
+        T: AccountDeserialize,
+    {
+        let account_data = self.svm
+            .get_account(address)
+            .ok_or(AccountError::AccountNotFound(*address))?;
+
+        // Deserialize the account data
+        let mut data = account_data.data.as_slice();
+        T::try_deserialize(&mut data).map_err(|e| AccountError::DeserializationError(e.to_string()))
+    }
+
+    /// Get and deserialize many accounts at once, like RPC `getMultipleAccounts`
+    ///
+    /// Results are in the same order as `addresses`, with `None` for missing accounts.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let vaults: Vec<Option<Vault>> = ctx.get_accounts(&[vault_a, vault_b, vault_c])?;
+    /// assert!(vaults.iter().all(Option::is_some));
+    /// ```
+    pub fn get_accounts<T>(&self, addresses: &[Pubkey]) -> Result<Vec<Option<T>>, AccountError>
+    where
+        T: AccountDeserialize,
+    {
+        get_anchor_accounts(&self.svm, addresses)
+    }
+
+    /// Get every account of type `T` owned by its program, like RPC `getProgramAccounts`
+    ///
+    /// Accounts are matched by `T`'s discriminator and then by all of the given
+    /// filters. Memcmp offsets include the 8-byte discriminator.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // Open orders whose `owner` field (the first field) is the user
+    /// let orders = ctx.get_program_accounts::<my_program::accounts::Order>(&[
+    ///     AccountFilter::memcmp(8, user.pubkey().to_bytes()),
+    /// ])?;
+    /// assert_eq!(orders.len(), 3);
+    /// ```
+    pub fn get_program_accounts<T>(&self, filters: &[AccountFilter]) -> Result<Vec<(Pubkey, T)>, AccountError>
+    where
+        T: AccountDeserialize + Discriminator + Owner,
+    {
+        get_program_accounts(&self.svm, filters)
+    }
+
+    /// Write an Anchor account directly into the SVM
+    ///
+    /// The account is serialized with its discriminator, owned by the account type's
+    /// program and funded with the rent-exempt minimum. This puts PDAs into a given
+    /// state without replaying the instructions that would normally create them.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.set_anchor_account(&vault_pda, &my_program::accounts::Vault {
+    ///     authority: user.pubkey(),
+    ///     balance: 1_000,
+    ///     bump,
+    /// })?;
+    /// ```
+    pub fn set_anchor_account<T>(&mut self, address: &Pubkey, account: &T) -> Result<(), AccountError>
+    where
+        T: AccountSerialize + Owner,
+    {
+        set_anchor_account(&mut self.svm, address, account)
+    }
+
+    /// Get an Anchor account together with its owner, lamports and data length
+    ///
+    /// This bundles everything a test commonly asserts about an account in one call.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let vault = ctx.get_account_full::<Vault>(&vault_pda)?;
+    /// assert_eq!(vault.owner, ctx.program_id);
+    /// assert_eq!(vault.data.amount, 1_000);
+    /// assert!(vault.lamports >= ctx.svm.minimum_balance_for_rent_exemption(vault.data_len));
+    /// ```
+    pub fn get_account_full<T>(&self, address: &Pubkey) -> Result<TypedAccount<T>, AccountError>
+    where
+        T: AccountDeserialize,
+    {
+        get_anchor_account_full(&self.svm, address)
+    }
+
+    /// Get an Anchor account without discriminator check
+    ///
+    /// Use this for accounts that don't have the standard Anchor discriminator.
+    ///
+    /// Note: `try_deserialize_unchecked` handles the discriminator internally,
+    /// so we pass the full account data.
+    pub fn get_account_unchecked<T>(&self, address: &Pubkey) -> Result<T, AccountError>
+    where
+        T: AccountDeserialize,
+    {
+        let account_data = self.svm
+            .get_account(address)
+            .ok_or(AccountError::AccountNotFound(*address))?;
+
+        // Deserialize without discriminator check
+        // Note: try_deserialize_unchecked handles the discriminator internally
+        let mut data = account_data.data.as_slice();
+        T::try_deserialize_unchecked(&mut data)
+            .map_err(|e| AccountError::DeserializationError(e.to_string()))
+    }
+
+    /// Assert that an account transitions through a sequence of states
+    ///
+    /// For each step, the instruction is executed with the given signers, the
+    /// transaction is asserted to succeed, and the state extracted from the
+    /// deserialized account is compared to the expected value. Failures report
+    /// the index of the offending step.
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.assert_state_sequence::<Escrow, EscrowState>(
+    ///     escrow_pda,
+    ///     |escrow| escrow.state,
+    ///     vec![
+    ///         (fund_ix, vec![maker.insecure_clone()], EscrowState::Funded),
+    ///         (accept_ix, vec![taker.insecure_clone()], EscrowState::Accepted),
+    ///     ],
+    /// );
+    /// ```
+    pub fn assert_state_sequence<T, S>(
+        &mut self,
+        account: Pubkey,
+        extract: impl Fn(&T) -> S,
+        steps: Vec<(Instruction, Vec<Keypair>, S)>,
+    ) where
+        T: AccountDeserialize,
+        S: PartialEq + Debug,
+    {
+        for (step, (instruction, signers, expected)) in steps.into_iter().enumerate() {
+            let signer_refs: Vec<&Keypair> = signers.iter().collect();
+            let result = self
+                .execute_instruction(instruction, &signer_refs)
+                .unwrap_or_else(|e| panic!("Step {}: failed to execute instruction: {}", step, e));
+            assert!(
+                result.is_success(),
+                "Step {}: transaction failed: {}\nLogs:\n{}",
+                step,
+                result.error().map(String::as_str).unwrap_or("Unknown error"),
+                result.logs().join("\n")
+            );
+
+            let state: T = self
+                .get_account(&account)
+                .unwrap_or_else(|e| panic!("Step {}: {}", step, e));
+            let actual = extract(&state);
+            assert_eq!(
+                actual, expected,
+                "Step {}: state mismatch for account {}. Expected: {:?}, Actual: {:?}",
+                step, account, expected, actual
+            );
+        }
+    }
+
+    /// Execute an instruction and assert that it didn't change the supply of any given mint
+    ///
+    /// Each mint's supply is recorded before execution and compared afterwards. This is
+    /// the mint-side invariant for transfer-only operations: it catches programs that
+    /// mint or burn tokens when they should only move them. Works with both SPL Token
+    /// and Token-2022 mints.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a mint doesn't exist or can't be decoded, or if any mint's supply changed
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = ctx.assert_no_mint_supply_change(transfer_ix, &[&user], &[mint_a, mint_b]);
+    /// result.assert_success();
+    /// ```
+    pub fn assert_no_mint_supply_change(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+        mints: &[Pubkey],
+    ) -> TransactionResult {
+        let before: Vec<u64> = mints.iter().map(|mint| self.mint_supply(mint)).collect();
+
+        let result = self
+            .execute_instruction(instruction, signers)
+            .expect("Failed to execute instruction");
+
+        for (mint, supply_before) in mints.iter().zip(before) {
+            let supply_after = self.mint_supply(mint);
+            assert_eq!(
+                supply_before,
+                supply_after,
+                "Supply changed for mint {} by {}. Expected: {}, Actual: {}\nLogs:\n{}",
+                mint,
+                supply_after as i128 - supply_before as i128,
+                supply_before,
+                supply_after,
+                result.logs().join("\n")
+            );
+        }
+        result
+    }
+
+    /// Execute an instruction and assert the change in an account's rent-exempt minimum
+    ///
+    /// The rent-exempt minimum is computed from the account's data length before and
+    /// after execution, so the delta reflects reallocations: positive when the account
+    /// grew and needs more lamports, negative when it shrank. A missing account counts
+    /// as requiring no rent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rent delta differs from `expected_rent_delta`
+    ///
+    /// # Example
+    /// ```ignore
+    /// let extra = ctx.svm.minimum_balance_for_rent_exemption(200)
+    ///     - ctx.svm.minimum_balance_for_rent_exemption(100);
+    /// ctx.execute_and_assert_rent_delta(resize_ix, &[&user], profile_pda, extra as i128)
+    ///     .assert_success();
+    /// ```
+    pub fn execute_and_assert_rent_delta(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+        account: Pubkey,
+        expected_rent_delta: i128,
+    ) -> TransactionResult {
+        let rent_before = self.rent_exempt_minimum(&account);
+        let result = self
+            .execute_instruction(instruction, signers)
+            .expect("Failed to execute instruction");
+        let rent_after = self.rent_exempt_minimum(&account);
+
+        let actual = rent_after as i128 - rent_before as i128;
+        assert_eq!(
+            actual, expected_rent_delta,
+            "Rent delta mismatch for account {}. Expected: {}, Actual: {}\nLogs:\n{}",
+            account,
+            expected_rent_delta,
+            actual,
+            result.logs().join("\n")
+        );
+        result
+    }
+
+    /// Rent-exempt minimum for an account's current data length, or 0 if it doesn't exist
+    fn rent_exempt_minimum(&self, account: &Pubkey) -> u64 {
+        self.svm
+            .get_account(account)
+            .map_or(0, |a| self.svm.minimum_balance_for_rent_exemption(a.data.len()))
+    }
+
+    /// Execute an instruction and assert that it didn't mutate any of the given accounts
+    ///
+    /// The data of each account is recorded before execution and compared afterwards.
+    /// This verifies that accounts the test treats as read-only are never written,
+    /// whether or not they were marked writable in the instruction.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the first mutated account if any account's data changed
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.execute_and_assert_readonly(swap_ix, &[&user], &[config_pda, oracle])
+    ///     .assert_success();
+    /// ```
+    pub fn execute_and_assert_readonly(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+        readonly: &[Pubkey],
+    ) -> TransactionResult {
+        let before: Vec<Option<Vec<u8>>> = readonly
+            .iter()
+            .map(|pubkey| self.svm.get_account(pubkey).map(|a| a.data))
+            .collect();
+
+        let result = self
+            .execute_instruction(instruction, signers)
+            .expect("Failed to execute instruction");
+
+        for (pubkey, data_before) in readonly.iter().zip(before) {
+            let data_after = self.svm.get_account(pubkey).map(|a| a.data);
+            if data_before != data_after {
+                let len = |data: &Option<Vec<u8>>| data.as_ref().map_or(0, Vec::len);
+                panic!(
+                    "Read-only account {} was mutated. Data length before: {}, after: {}\nLogs:\n{}",
+                    pubkey,
+                    len(&data_before),
+                    len(&data_after),
+                    result.logs().join("\n")
+                );
+            }
+        }
+        result
+    }
+
+    /// Assert the exact payer balance an instruction needs to succeed
+    ///
+    /// The context payer's balance is set to `min - 1` and the instruction is expected
+    /// to fail, then set to `min` and expected to succeed. The payer signs and pays for
+    /// both transactions, so `min` includes the transaction fee. The payer's original
+    /// balance is restored afterwards.
+    ///
+    /// `build_ix` is called once per attempt so that each transaction can use fresh accounts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the instruction succeeds below `min` or fails at `min`
+    ///
+    /// # Example
+    /// ```ignore
+    /// let rent = ctx.svm.minimum_balance_for_rent_exemption(8 + Config::INIT_SPACE);
+    /// ctx.assert_requires_min_payer_balance(|| build_init_config_ix(), rent + 5_000);
+    /// ```
+    pub fn assert_requires_min_payer_balance(
+        &mut self,
+        build_ix: impl Fn() -> Instruction,
+        min: u64,
+    ) {
+        assert!(min > 0, "Minimum payer balance must be greater than zero");
+        let payer = self.payer.insecure_clone();
+        let original = self.payer_balance();
+
+        self.set_payer_balance(min - 1);
+        let below = self
+            .execute_instruction(build_ix(), &[&payer])
+            .expect("Failed to execute instruction");
+        assert!(
+            !below.is_success(),
+            "Instruction succeeded with payer balance {}, below the expected minimum {}\nLogs:\n{}",
+            min - 1,
+            min,
+            below.logs().join("\n")
+        );
+
+        // A fresh blockhash keeps identical instructions from being rejected as duplicates
+        self.svm.expire_blockhash();
+        self.set_payer_balance(min);
+        let at_min = self
+            .execute_instruction(build_ix(), &[&payer])
+            .expect("Failed to execute instruction");
+        assert!(
+            at_min.is_success(),
+            "Instruction failed with payer balance {}: {}\nLogs:\n{}",
+            min,
+            at_min.error().map(String::as_str).unwrap_or("Unknown error"),
+            at_min.logs().join("\n")
+        );
+
+        self.set_payer_balance(original);
+    }
+
+    /// Overwrite the payer's lamport balance, keeping the rest of the account intact
+    fn set_payer_balance(&mut self, lamports: u64) {
+        let pubkey = self.payer.pubkey();
+        let mut account = self.svm.get_account(&pubkey).unwrap_or_default();
+        account.lamports = lamports;
+        self.svm
+            .set_account(pubkey, account)
+            .expect("Failed to set payer balance");
+    }
+
+    /// Read the current supply of a token mint
+    fn mint_supply(&self, mint: &Pubkey) -> u64 {
+        let account = self
+            .svm
+            .get_account(mint)
+            .unwrap_or_else(|| panic!("Mint {} does not exist", mint));
+        // Token-2022 mints share the base layout and append extensions after it
+        let data = account.data.get(..spl_token::state::Mint::LEN).unwrap_or(&[]);
+        spl_token::state::Mint::unpack_from_slice(data)
+            .unwrap_or_else(|e| panic!("Failed to decode mint {}: {:?}", mint, e))
+            .supply
+    }
+
+    /// Assert that an instruction rejects an account owned by the wrong program
+    ///
+    /// Fabricates a rent-exempt account owned by a random program id, passes its
+    /// address to `build_ix` and executes the resulting instruction. The transaction
+    /// must fail with Anchor's `ConstraintOwner` or `AccountOwnedByWrongProgram` error.
+    ///
+    /// The fabricated account holds 1024 zero bytes. Programs that check the
+    /// account's data length or discriminator before its owner will fail with a
+    /// different error, so this suits `Account<T>` and `#[account(owner = ...)]`
+    /// checks, which Anchor runs on the owner first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction succeeds or fails with a different error
+    ///
+    /// # Example
+    /// ```ignore
+    /// let program = ctx.program();
+    /// ctx.assert_rejects_wrong_owner(
+    ///     |vault| {
+    ///         program
+    ///             .accounts(my_program::accounts::Withdraw { vault, user: user.pubkey() })
+    ///             .args(my_program::instruction::Withdraw { amount: 100 })
+    ///             .instruction()
+    ///             .unwrap()
+    ///     },
+    ///     &[&user],
+    /// );
+    /// ```
+    pub fn assert_rejects_wrong_owner(
+        &mut self,
+        build_ix: impl Fn(Pubkey) -> Instruction,
+        signers: &[&Keypair],
+    ) -> TransactionResult {
+        let address = Pubkey::new_unique();
+        let wrong_owner = Pubkey::new_unique();
+        let data_len = 1024;
+        self.svm
+            .set_account(
+                address,
+                solana_sdk::account::Account {
+                    lamports: self.svm.minimum_balance_for_rent_exemption(data_len),
+                    data: vec![0; data_len],
+                    owner: wrong_owner,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .expect("Failed to fabricate wrong-owner account");
+
+        let result = self
+            .execute_instruction(build_ix(address), signers)
+            .expect("Failed to execute instruction");
+        assert!(
+            is_owner_constraint_failure(&result),
+            "Expected account {} owned by {} to be rejected with an owner constraint error, got: {}\nLogs:\n{}",
+            address,
+            wrong_owner,
+            result.error().map(String::as_str).unwrap_or("success"),
+            result.logs().join("\n")
+        );
+        result
+    }
+
+    /// Assert that the context's program is deployed and reachable
+    ///
+    /// Sends a [`ping_instruction`] to the program
+    /// and expects it to be rejected by the program itself (e.g. with a dispatch or
+    /// deserialization error), as opposed to failing because the program doesn't exist.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the program is not deployed or not executable
+    ///
+    /// # Example
+    /// ```ignore
+    /// let mut ctx = AnchorLiteSVM::build_with_program(program_id, program_bytes);
+    /// ctx.assert_program_reachable();
+    /// ```
+    pub fn assert_program_reachable(&mut self) {
+        let ix = ping_instruction(&self.program_id);
+        let payer = self.payer.insecure_clone();
+        let result = self
+            .execute_instruction(ix, &[&payer])
+            .expect("Failed to send ping instruction");
+
+        let unreachable = result.error().is_some_and(|e| {
+            e.contains("ProgramAccountNotFound") || e.contains("InvalidProgramForExecution")
+        });
+        assert!(
+            !unreachable,
+            "Program {} is not reachable: {}\nLogs:\n{}",
+            self.program_id,
+            result.error().map(String::as_str).unwrap_or_default(),
+            result.logs().join("\n")
+        );
+    }
+
+    /// Deploy a program and record the slot it was deployed at
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.svm.warp_to_slot(100);
+    /// ctx.deploy_program(other_program_id, other_program_bytes)?;
+    /// assert_eq!(ctx.program_deploy_slot(&other_program_id), Some(100));
+    /// ```
+    pub fn deploy_program(
+        &mut self,
+        program_id: Pubkey,
+        program_bytes: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.svm
+            .add_program(program_id, program_bytes)
+            .map_err(|e| format!("Failed to deploy program {}: {:?}", program_id, e))?;
+        let slot = self.svm.get_sysvar::<solana_program::clock::Clock>().slot;
+        self.deploy_slots.insert(program_id, slot);
+        Ok(())
+    }
+
+    /// Deploy a program and register it under a name
+    ///
+    /// The program can then be looked up with [`AnchorContext::program_named`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// ctx.deploy_named_program("oracle", oracle_id, oracle_bytes)?;
+    /// let oracle = ctx.program_named("oracle").unwrap();
+    /// ```
+    pub fn deploy_named_program(
+        &mut self,
+        name: &str,
+        program_id: Pubkey,
+        program_bytes: &[u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.deploy_program(program_id, program_bytes)?;
+        self.program_names.insert(name.to_string(), program_id);
+        Ok(())
+    }
+
+    /// Get the slot at which a program was deployed
+    ///
+    /// Programs deployed through the builder or [`AnchorContext::deploy_program`] report
+    /// the slot recorded at deployment. Otherwise, for programs owned by the upgradeable
+    /// loader, the `slot` field of their ProgramData account is returned.
+    ///
+    /// # Returns
+    ///
+    /// The deployment slot, or None if it is unknown
+    ///
+    /// # Example
+    /// ```ignore
+    /// assert_eq!(ctx.program_deploy_slot(&ctx.program_id), Some(0));
+    /// ```
+    pub fn program_deploy_slot(&self, program_id: &Pubkey) -> Option<u64> {
+        if let Some(slot) = self.deploy_slots.get(program_id) {
+            return Some(*slot);
+        }
+
+        let program = self.svm.get_account(program_id)?;
+        let programdata_address = match bincode::deserialize(&program.data) {
+            Ok(UpgradeableLoaderState::Program { programdata_address }) => programdata_address,
+            _ => return None,
+        };
+        let programdata = self.svm.get_account(&programdata_address)?;
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata()
+            .min(programdata.data.len());
+        match bincode::deserialize(&programdata.data[..metadata_len]) {
+            Ok(UpgradeableLoaderState::ProgramData { slot, .. }) => Some(slot),
+            _ => None,
+        }
+    }
+
+    /// Create a funded account (convenience method)
+    pub fn create_funded_account(&mut self, lamports: u64) -> Result<Keypair, Box<dyn std::error::Error>> {
+        let account = Keypair::new();
+        self.svm.airdrop(&account.pubkey(), lamports)
+            .map_err(|e| format!("Airdrop failed: {:?}", e))?;
+        Ok(account)
+    }
+
+    /// Create a funded account derived from a name with [`keypair`](Self::keypair)
+    ///
+    /// The address is also labelled with the name.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let alice = ctx.create_funded_account_named("alice", 1_000_000_000)?;
+    /// ```
+    pub fn create_funded_account_named(
+        &mut self,
+        name: &str,
+        lamports: u64,
+    ) -> Result<Keypair, Box<dyn std::error::Error>> {
+        let account = self.keypair(name);
+        self.airdrop(&account.pubkey(), lamports)?;
+        self.label(&account.pubkey(), name);
+        Ok(account)
+    }
+
+    /// Airdrop lamports to an account (convenience method)
+    pub fn airdrop(&mut self, pubkey: &Pubkey, lamports: u64) -> Result<(), Box<dyn std::error::Error>> {
+        self.svm.airdrop(pubkey, lamports)
+            .map_err(|e| format!("Airdrop failed: {:?}", e))?;
+        Ok(())
+    }
+
+    /// Override a sysvar mid-test
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut rent = Rent::default();
+    /// rent.lamports_per_byte_year *= 2;
+    /// ctx.set_sysvar(&rent);
+    /// ```
+    pub fn set_sysvar<T: SysvarSerialize>(&mut self, sysvar: &T) {
+        self.svm.set_sysvar(sysvar);
+    }
+
+    /// Get the latest blockhash
+    pub fn latest_blockhash(&self) -> solana_sdk::hash::Hash {
+        self.svm.latest_blockhash()
+    }
+
+    /// Expire the current blockhash, so transactions signed against it fail with `BlockhashNotFound`
+    pub fn expire_blockhash(&mut self) {
+        self.svm.expire_blockhash();
+    }
+
+    /// Check if an account exists
+    pub fn account_exists(&self, pubkey: &Pubkey) -> bool {
+        self.svm.get_account(pubkey).is_some()
+    }
+}
+
+/// Get the program invoked by each top-level instruction of a transaction
+fn top_level_program_ids(tx: &Transaction) -> Vec<Pubkey> {
+    tx.message
+        .instructions
+        .iter()
+        .map(|ix| tx.message.account_keys[ix.program_id_index as usize])
+        .collect()
+}
+
+/// Find the program that raised the custom error a transaction failed with
+///
+/// The first `Program X failed: custom program error` log names the program that
+/// raised the error, even inside a CPI. Without it, the top-level instruction at
+/// the `InstructionError` index is used.
+fn failing_program(result: &TransactionResult, program_ids: &[Pubkey]) -> Option<Pubkey> {
+    let from_logs = result.logs().iter().find_map(|log| {
+        let (program, _) = log.strip_prefix("Program ")?.split_once(" failed: custom program error")?;
+        program.parse().ok()
+    });
+    from_logs.or_else(|| {
+        let (index, _) = result.error()?.strip_prefix("InstructionError(")?.split_once(',')?;
+        program_ids.get(index.parse::<usize>().ok()?).copied()
+    })
+}
+
+/// Check whether a transaction failed with one of Anchor's account owner errors
+fn is_owner_constraint_failure(result: &TransactionResult) -> bool {
+    let owner_errors = [
+        anchor_lang::error::ErrorCode::ConstraintOwner as u32,
+        anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram as u32,
+    ];
+    !result.is_success()
+        && result
+            .anchor_error_code()
+            .is_some_and(|code| owner_errors.contains(&code))
+}
+
+/// Compare an account before and after a transaction, returning None if it didn't change
+fn account_change(
+    address: Pubkey,
+    before: Option<solana_sdk::account::Account>,
+    after: Option<solana_sdk::account::Account>,
+) -> Option<AccountChange> {
+    let (lamports_before, data_before, owner_before) = before
+        .map(|a| (a.lamports, a.data, a.owner))
+        .unwrap_or_default();
+    let (lamports_after, data_after, owner_after) = after
+        .map(|a| (a.lamports, a.data, a.owner))
+        .unwrap_or_default();
+
+    let data_changed = data_before != data_after;
+    if lamports_before == lamports_after && !data_changed && owner_before == owner_after {
+        return None;
+    }
+
+    Some(AccountChange {
+        address,
+        lamports_before,
+        lamports_after,
+        data_len_before: data_before.len(),
+        data_len_after: data_after.len(),
+        owner_before,
+        owner_after,
+        data_changed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use litesvm_utils::TestHelpers;
+
+    /// Token account balance, decoded through `AccountDeserialize`
+    struct TokenAmount(u64);
+
+    impl AccountDeserialize for TokenAmount {
+        fn try_deserialize_unchecked(buf: &mut &[u8]) -> anchor_lang::Result<Self> {
+            let account = spl_token::state::Account::unpack(buf)
+                .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+            Ok(TokenAmount(account.amount))
+        }
+    }
+
+    /// Create a context with a mint and a token account owned by the payer
+    fn token_setup() -> (AnchorContext, Keypair, Pubkey) {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let mint = ctx.svm.create_token_mint(&payer, 9).unwrap();
+        let ata = ctx.svm.create_associated_token_account(&mint.pubkey(), &payer).unwrap();
+        (ctx, mint, ata)
+    }
+
+    fn mint_to_ix(mint: &Pubkey, account: &Pubkey, authority: &Pubkey, amount: u64) -> Instruction {
+        spl_token::instruction::mint_to(&spl_token::id(), mint, account, authority, &[], amount).unwrap()
+    }
+
+    #[test]
+    fn test_payer_balance() {
+        let ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        assert_eq!(ctx.payer_balance(), 10_000_000_000);
+        ctx.assert_payer_has(10_000_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "insufficient balance")]
+    fn test_assert_payer_has_fails() {
+        let ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        ctx.assert_payer_has(20_000_000_000);
+    }
+
+    #[test]
+    fn test_assert_state_sequence() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+
+        ctx.assert_state_sequence::<TokenAmount, u64>(
+            ata,
+            |account| account.0,
+            vec![
+                (mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 100), vec![payer.insecure_clone()], 100),
+                (mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 50), vec![payer.insecure_clone()], 150),
+            ],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Step 1: state mismatch")]
+    fn test_assert_state_sequence_reports_step() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+
+        ctx.assert_state_sequence::<TokenAmount, u64>(
+            ata,
+            |account| account.0,
+            vec![
+                (mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 100), vec![payer.insecure_clone()], 100),
+                (mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 50), vec![payer.insecure_clone()], 100),
+            ],
+        );
+    }
+
+    #[test]
+    fn test_total_compute_units() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        assert_eq!(ctx.total_compute_units(), 0);
+
+        let first = ctx
+            .execute_instruction(mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10), &[&payer])
+            .unwrap();
+        let second = ctx
+            .execute_instruction(mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 20), &[&payer])
+            .unwrap();
+
+        let expected = first.compute_units() + second.compute_units();
+        assert!(expected > 0);
+        assert_eq!(ctx.total_compute_units(), expected);
+        ctx.assert_total_compute_below(expected + 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Total compute units exceeded budget")]
+    fn test_assert_total_compute_below_fails() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        ctx.execute_instruction(mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10), &[&payer])
+            .unwrap();
+        ctx.assert_total_compute_below(1);
+    }
+
+    #[test]
+    fn test_execute_instruction_records_cpis() {
+        let (mut ctx, mint, _) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+
+        let ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            &mint.pubkey(),
+            &spl_token::id(),
+        );
+        let result = ctx.execute_instruction(ix, &[&payer]).unwrap();
+        result.assert_success().assert_cpi_to(&spl_token::id());
+        assert!(result.cpi_count_to(&spl_token::id()) > 0);
+    }
+
+    #[test]
+    fn test_event_recording() {
+        use anchor_lang::prelude::*;
+        use base64::{engine::general_purpose, Engine as _};
+
+        #[event]
+        struct Deposited {
+            amount: u64,
+        }
+
+        let with_events = |amounts: &[u64], failed: bool| {
+            let meta = TransactionMetadata {
+                logs: amounts
+                    .iter()
+                    .map(|amount| {
+                        format!("Program data: {}", general_purpose::STANDARD.encode(Deposited { amount: *amount }.data()))
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            if failed {
+                TransactionResult::new_failed("Custom(1)".to_string(), meta, None)
+            } else {
+                TransactionResult::new(meta, None)
+            }
+        };
+
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        ctx.record_events(&with_events(&[1], false));
+        assert_eq!(ctx.recorded_event_count(), 0);
+
+        ctx.start_event_recording();
+        ctx.record_events(&with_events(&[2, 3], false));
+        ctx.record_events(&with_events(&[4], true));
+        ctx.record_events(&with_events(&[5], false));
+        let amounts: Vec<u64> = ctx.recorded_events::<Deposited>().unwrap().iter().map(|e| e.amount).collect();
+        assert_eq!(amounts, vec![2, 3, 5]);
+
+        // Transactions without events don't add any
+        let payer = ctx.payer().insecure_clone();
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000);
+        ctx.execute_instruction(ix, &[&payer]).unwrap().assert_success();
+        assert_eq!(ctx.recorded_event_count(), 3);
+
+        ctx.clear_recorded_events();
+        assert!(ctx.recorded_events::<Deposited>().unwrap().is_empty());
+        ctx.record_events(&with_events(&[6], false));
+        assert_eq!(ctx.recorded_event_count(), 1);
+
+        ctx.stop_event_recording();
+        ctx.record_events(&with_events(&[7], false));
+        assert_eq!(ctx.recorded_event_count(), 0);
+    }
+
+    #[test]
+    fn test_labels_in_assertion_output() {
+        let (mut ctx, mint, _) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        ctx.label(&spl_token::id(), "token program");
+        assert_eq!(ctx.labels().get(&spl_token::id()).map(String::as_str), Some("token program"));
+
+        let ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            &mint.pubkey(),
+            &spl_token::id(),
+        );
+        let result = ctx.execute_instruction(ix, &[&payer]).unwrap();
+        let panic = std::panic::catch_unwind(|| {
+            result.assert_cpi_to(&Pubkey::new_unique());
+        });
+        let message = *panic.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("\"token program\""), "{}", message);
+        assert!(!message.contains(&spl_token::id().to_string()), "{}", message);
+    }
+
+    #[test]
+    fn test_simulate_instruction_does_not_commit() {
+        let ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let recipient = Pubkey::new_unique();
+        let balance_before = ctx.payer_balance();
+
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &recipient, 1_000_000);
+        let result = ctx.simulate_instruction(ix, &[&payer]).unwrap();
+        result.assert_success();
+        assert!(result.compute_units() > 0);
+        assert!(!result.logs().is_empty());
+
+        assert_eq!(ctx.payer_balance(), balance_before);
+        assert!(ctx.svm.get_account(&recipient).is_none());
+        assert_eq!(ctx.total_compute_units(), 0);
+
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &recipient, u64::MAX);
+        ctx.simulate_instruction(ix, &[&payer]).unwrap().assert_failure();
+    }
+
+    #[test]
+    fn test_execute_and_assert_fee_payer() {
+        let (mut ctx, mint, ata) = token_setup();
+        let authority = ctx.payer().insecure_clone();
+        let relayer = ctx.create_funded_account(1_000_000_000).unwrap();
+
+        let ix = mint_to_ix(&mint.pubkey(), &ata, &authority.pubkey(), 10);
+        ctx.execute_and_assert_fee_payer(ix, &relayer, &[&authority], &relayer.pubkey())
+            .assert_success();
+    }
+
+    #[test]
+    fn test_execute_instruction_as() {
+        let (mut ctx, mint, ata) = token_setup();
+        let authority = ctx.payer().insecure_clone();
+        let treasury = ctx.create_funded_account(1_000_000_000).unwrap();
+        ctx.add_payer("treasury", treasury);
+        assert!(ctx.payer_named("other").is_none());
+
+        let treasury = ctx.payer_named("treasury").unwrap().insecure_clone();
+        assert_eq!(ctx.labels().get(&treasury.pubkey()).map(String::as_str), Some("treasury"));
+
+        let authority_before = ctx.payer_balance();
+        let ix = mint_to_ix(&mint.pubkey(), &ata, &authority.pubkey(), 10);
+        let result = ctx.execute_instruction_as(&treasury, ix, &[&authority]).unwrap();
+        result.assert_success();
+        assert_eq!(result.fee_payer(), Some(&treasury.pubkey()));
+        assert_eq!(ctx.payer_balance(), authority_before);
+        assert_eq!(
+            ctx.svm.get_balance(&treasury.pubkey()),
+            Some(1_000_000_000 - result.fee_paid().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_execute_and_assert_fee_payer_with_auto_fund() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        ctx.auto_fund_signers(1_000_000_000);
+        let relayer = Keypair::new();
+        let user = ctx.create_funded_account(1_000_000_000).unwrap();
+
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&user.pubkey(), &Pubkey::new_unique(), 1_000);
+        ctx.execute_and_assert_fee_payer(ix, &relayer, &[&user], &relayer.pubkey())
+            .assert_success();
+    }
+
+    #[test]
+    #[should_panic(expected = "Fee was not paid by")]
+    fn test_execute_and_assert_fee_payer_wrong_payer() {
+        let (mut ctx, mint, ata) = token_setup();
+        let authority = ctx.payer().insecure_clone();
+        let relayer = ctx.create_funded_account(1_000_000_000).unwrap();
+
+        let ix = mint_to_ix(&mint.pubkey(), &ata, &authority.pubkey(), 10);
+        ctx.execute_and_assert_fee_payer(ix, &relayer, &[&authority], &authority.pubkey());
+    }
+
+    #[test]
+    fn test_assert_no_mint_supply_change() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        ctx.svm.mint_to(&mint.pubkey(), &ata, &payer, 100).unwrap();
+
+        let recipient = Keypair::new();
+        let recipient_ata = ctx
+            .svm
+            .create_ata_for(&payer, &recipient.pubkey(), &mint.pubkey())
+            .unwrap();
+        let ix = spl_token::instruction::transfer(&spl_token::id(), &ata, &recipient_ata, &payer.pubkey(), &[], 40)
+            .unwrap();
+
+        ctx.assert_no_mint_supply_change(ix, &[&payer], &[mint.pubkey()])
+            .assert_success();
+    }
+
+    #[test]
+    #[should_panic(expected = "by 10")]
+    fn test_assert_no_mint_supply_change_detects_mint() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+
+        let ix = mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10);
+        ctx.assert_no_mint_supply_change(ix, &[&payer], &[mint.pubkey()]);
+    }
+
+    #[test]
+    fn test_execute_and_assert_rent_delta() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let account = Keypair::new();
+        let rent = ctx.svm.minimum_balance_for_rent_exemption(100);
+
+        let ix = anchor_lang::solana_program::system_instruction::create_account(
+            &payer.pubkey(),
+            &account.pubkey(),
+            rent,
+            100,
+            &Pubkey::new_unique(),
+        );
+        ctx.execute_and_assert_rent_delta(ix, &[&payer, &account], account.pubkey(), rent as i128)
+            .assert_success();
+    }
+
+    #[test]
+    #[should_panic(expected = "Rent delta mismatch")]
+    fn test_execute_and_assert_rent_delta_fails() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+
+        // Minting doesn't resize the token account
+        let ix = mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10);
+        ctx.execute_and_assert_rent_delta(ix, &[&payer], ata, 1);
+    }
+
+    #[test]
+    fn test_is_owner_constraint_failure() {
+        let failed = |code: u32| {
+            TransactionResult::new_failed(
+                format!("InstructionError(0, Custom({}))", code),
+                TransactionMetadata::default(),
+                None,
+            )
+        };
+        assert!(is_owner_constraint_failure(&failed(2004)));
+        assert!(is_owner_constraint_failure(&failed(3007)));
+        assert!(!is_owner_constraint_failure(&failed(6000)));
+    }
+
+    // Rejects an account not owned by the program the way Anchor's `Account<T>` does
+    solana_program_runtime::declare_process_instruction!(OwnerCheckedProgram, 150, |invoke_context| {
+        let instruction_context = invoke_context.transaction_context.get_current_instruction_context()?;
+        let program_id = *instruction_context.get_program_key()?;
+        let owner = *instruction_context.try_borrow_instruction_account(0)?.get_owner();
+        if owner != program_id {
+            if let Some(logger) = invoke_context.get_log_collector() {
+                logger.borrow_mut().log(
+                    "AnchorError caused by account: vault. Error Code: AccountOwnedByWrongProgram. Error Number: 3007.",
+                );
+            }
+            return Err(solana_sdk::instruction::InstructionError::Custom(
+                anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram as u32,
+            ));
+        }
+        Ok(())
+    });
+
+    #[test]
+    fn test_assert_rejects_wrong_owner() {
+        let program_id = Pubkey::new_unique();
+        let mut svm = LiteSVM::new();
+        svm.add_builtin(program_id, OwnerCheckedProgram::vm);
+        // Builtins only run from accounts owned by the native loader
+        svm.set_account(
+            program_id,
+            solana_sdk::account::Account {
+                lamports: 1,
+                data: b"owner_checked".to_vec(),
+                owner: solana_sdk_ids::native_loader::id(),
+                executable: true,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+        let mut ctx = AnchorContext::new(svm, program_id);
+        let payer = ctx.payer().insecure_clone();
+        let vault_ix = |vault| Instruction::new_with_bytes(program_id, &[], vec![solana_program::instruction::AccountMeta::new(vault, false)]);
+
+        // An account owned by the program is accepted
+        let vault = Pubkey::new_unique();
+        ctx.svm
+            .set_account(
+                vault,
+                solana_sdk::account::Account {
+                    lamports: 1_000_000_000,
+                    data: vec![0; 8],
+                    owner: program_id,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .unwrap();
+        ctx.execute_instruction(vault_ix(vault), &[&payer]).unwrap().assert_success();
+
+        let result = ctx.assert_rejects_wrong_owner(vault_ix, &[&payer]);
+        assert_eq!(result.anchor_error_code(), Some(3007));
+    }
+
+    #[test]
+    #[should_panic(expected = "to be rejected with an owner constraint error")]
+    fn test_assert_rejects_wrong_owner_fails_on_other_error() {
+        let (mut ctx, mint, _) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+
+        // SPL Token isn't an Anchor program, so it reports a different error
+        ctx.assert_rejects_wrong_owner(
+            |account| mint_to_ix(&mint.pubkey(), &account, &payer.pubkey(), 10),
+            &[&payer],
+        );
+    }
+
+    #[test]
+    fn test_execute_and_assert_readonly() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+
+        let ix = mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10);
+        ctx.execute_and_assert_readonly(ix, &[&payer], &[spl_token::id(), payer.pubkey()])
+            .assert_success();
+    }
+
+    #[test]
+    #[should_panic(expected = "was mutated")]
+    fn test_execute_and_assert_readonly_detects_write() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+
+        let ix = mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10);
+        ctx.execute_and_assert_readonly(ix, &[&payer], &[spl_token::id(), ata]);
+    }
+
+    #[test]
+    fn test_assert_requires_min_payer_balance() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().pubkey();
+        let recipient = Pubkey::new_unique();
+
+        let transfer = || anchor_lang::solana_program::system_instruction::transfer(&payer, &recipient, 1_000_000);
+        ctx.assert_requires_min_payer_balance(transfer, 1_000_000 + 5_000);
+        assert_eq!(ctx.payer_balance(), 10_000_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "below the expected minimum")]
+    fn test_assert_requires_min_payer_balance_too_high() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().pubkey();
+        let recipient = Pubkey::new_unique();
+
+        let transfer = || anchor_lang::solana_program::system_instruction::transfer(&payer, &recipient, 1_000_000);
+        ctx.assert_requires_min_payer_balance(transfer, 2_000_000);
+    }
+
+    #[test]
+    fn test_assert_program_reachable() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), spl_token::id());
+        ctx.assert_program_reachable();
+    }
+
+    #[test]
+    #[should_panic(expected = "is not reachable")]
+    fn test_assert_program_reachable_not_deployed() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        ctx.assert_program_reachable();
+    }
+
+    #[test]
+    #[should_panic(expected = "Forbidden log 'MintTo'")]
+    fn test_forbidden_logs_panics_on_success() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        ctx.forbidden_logs = vec!["MintTo".to_string()];
+
+        let _ = ctx.execute_instruction(mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10), &[&payer]);
+    }
+
+    #[test]
+    fn test_forbidden_logs_allows_clean_transactions() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        ctx.forbidden_logs = vec!["deprecated".to_string()];
+
+        ctx.execute_instruction(mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10), &[&payer])
+            .unwrap()
+            .assert_success();
+    }
+
+    #[test]
+    fn test_program_deploy_slot() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let program_id = Pubkey::new_unique();
+        assert_eq!(ctx.program_deploy_slot(&program_id), None);
+
+        // Redeploy the bundled SPL Token program under a new address
+        let program_bytes = ctx.svm.get_account(&spl_token::id()).unwrap().data;
+        ctx.svm.warp_to_slot(42);
+        ctx.deploy_program(program_id, &program_bytes).unwrap();
+        assert_eq!(ctx.program_deploy_slot(&program_id), Some(42));
+    }
+
+    #[test]
+    fn test_execute_instruction_named() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000);
+
+        let result = ctx.execute_instruction_named("fund", ix.clone(), &[&payer]).unwrap();
+        result.assert_success();
+        assert_eq!(result.instruction_name(), Some("fund"));
+
+        let result = ctx.execute_instruction(ix, &[&payer]).unwrap();
+        assert_eq!(
+            result.instruction_name(),
+            Some(format!("instruction to {}", anchor_lang::system_program::ID).as_str())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Transaction failed (fund)")]
+    fn test_execute_instruction_named_failure_mentions_name() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), u64::MAX);
+
+        ctx.execute_instruction_named("fund", ix, &[&payer]).unwrap().assert_success();
+    }
+
+    #[test]
+    fn test_instruction_name_from_idl() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let idl: Idl = serde_json::from_value(serde_json::json!({
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "system", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [{
+                "name": "transfer",
+                "discriminator": [2, 0, 0, 0],
+                "accounts": [],
+                "args": [{ "name": "lamports", "type": "u64" }]
+            }]
+        }))
+        .unwrap();
+        ctx.add_idl(idl).unwrap();
+        assert!(ctx.idl(&anchor_lang::system_program::ID).is_some());
+
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000);
+        let (name, args) = ctx.decode_instruction(&ix.program_id, &ix.data).unwrap();
+        assert_eq!((name.as_str(), &args), ("transfer", &serde_json::json!({ "lamports": 1_000 })));
+        assert!(matches!(
+            ctx.decode_instruction(&Pubkey::new_unique(), &ix.data),
+            Err(IdlError::ProgramNotFound(_))
+        ));
+        let result = ctx.execute_instruction(ix, &[&payer]).unwrap();
+        assert_eq!(result.instruction_name(), Some("transfer"));
+
+        let ix = ctx
+            .idl_program("system")
+            .unwrap()
+            .instruction("transfer")
+            .args_json(serde_json::json!({ "lamports": 5 }))
+            .instruction()
+            .unwrap();
+        assert_eq!(ix.data, [2, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0]);
+        assert!(matches!(ctx.idl_program("escrow"), Err(IdlError::ProgramNotFound(_))));
+
+        // Account metas are checked against the IDL before execution
+        let mut idl = ctx.idl(&anchor_lang::system_program::ID).unwrap().clone();
+        idl.instructions[0].accounts = serde_json::from_value(serde_json::json!([
+            { "name": "from", "writable": true, "signer": true },
+            { "name": "to", "writable": true }
+        ]))
+        .unwrap();
+        ctx.add_idl(idl).unwrap();
+        let mut ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000);
+        ix.accounts[1].is_writable = false;
+        let error = ctx.execute_instruction(ix.clone(), &[&payer]).unwrap_err();
+        assert_eq!(error.to_string(), "Instruction `transfer`: account #1 `to` should be writable");
+        let error = ctx.simulate_instruction(ix.clone(), &[&payer]).unwrap_err();
+        assert_eq!(error.to_string(), "Instruction `transfer`: account #1 `to` should be writable");
+        let tx = Transaction::new_signed_with_payer(
+            std::slice::from_ref(&ix),
+            Some(&payer.pubkey()),
+            &[&payer],
+            ctx.latest_blockhash(),
+        );
+        let error = ctx.send_and_confirm_transaction(&tx).unwrap_err();
+        assert_eq!(error.to_string(), "Instruction `transfer`: account #1 `to` should be writable");
+
+        // With validation disabled, the instruction reaches the program
+        ctx.set_account_validation(false);
+        ctx.execute_instruction(ix, &[&payer]).unwrap().assert_failure();
+        ctx.set_account_validation(true);
+
+        let mut idl = ctx.idl(&anchor_lang::system_program::ID).unwrap().clone();
+        idl.address = "not an address".to_string();
+        assert!(matches!(ctx.add_idl(idl), Err(IdlError::InvalidAddress(_))));
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let recipient = Pubkey::new_unique();
+        let snapshot = ctx.snapshot();
+
+        for _ in 0..2 {
+            let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &recipient, 1_000_000);
+            ctx.execute_instruction(ix, &[&payer]).unwrap().assert_success();
+            ctx.svm.warp_to_slot(500);
+            assert_eq!(ctx.svm.get_balance(&recipient), Some(1_000_000));
+            assert!(ctx.total_compute_units() > 0);
+
+            ctx.restore(&snapshot);
+            assert!(ctx.svm.get_account(&recipient).is_none());
+            assert_eq!(ctx.svm.get_sysvar::<solana_program::clock::Clock>().slot, 0);
+            assert_eq!(ctx.total_compute_units(), 0);
+        }
+    }
+
+    #[test]
+    fn test_restore_rewinds_history_and_events() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let transfer = || {
+            anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000)
+        };
+        ctx.set_history(true);
+        ctx.start_event_recording();
+        ctx.execute_instruction(transfer(), &[&payer]).unwrap().assert_success();
+        let kept = ctx.last_result().unwrap().inner().signature;
+        let snapshot = ctx.snapshot();
+
+        let recipient = Pubkey::new_unique();
+        ctx.label(&recipient, "recipient");
+        ctx.execute_instruction(transfer(), &[&payer]).unwrap().assert_success();
+        // Stands in for an event emitted by the discarded transaction
+        ctx.recorded_events.as_mut().unwrap().push(vec![0; 8]);
+        assert_eq!(ctx.history().len(), 2);
+
+        ctx.restore(&snapshot);
+        assert_eq!(ctx.history().len(), 1);
+        assert_eq!(ctx.last_result().unwrap().inner().signature, kept);
+        assert_eq!(ctx.recorded_events, Some(vec![]));
+        assert!(ctx.labels.is_empty());
+    }
+
+    #[test]
+    fn test_error_names_from_idl() {
+        let (mut ctx, _mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        let idl: Idl = serde_json::from_value(serde_json::json!({
+            "address": spl_token::id().to_string(),
+            "metadata": { "name": "token", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [],
+            "errors": [{ "code": 4, "name": "OwnerMismatch", "msg": "Owner does not match" }]
+        }))
+        .unwrap();
+        ctx.add_idl(idl).unwrap();
+
+        // Closing a token account with the wrong owner fails with TokenError::OwnerMismatch (4)
+        let wrong_owner = ctx.create_funded_account(1_000_000_000).unwrap();
+        let ix = spl_token::instruction::close_account(
+            &spl_token::id(), &ata, &payer.pubkey(), &wrong_owner.pubkey(), &[],
+        )
+        .unwrap();
+        let result = ctx.execute_instruction(ix, &[&wrong_owner]).unwrap();
+        assert_eq!(result.error_name(), Some("OwnerMismatch"));
+        result.assert_error("OwnerMismatch");
+
+        ctx.register_errors(&spl_token::id(), [(4, "TokenError::OwnerMismatch")]);
+        let ix = spl_token::instruction::close_account(
+            &spl_token::id(), &ata, &payer.pubkey(), &wrong_owner.pubkey(), &[],
+        )
+        .unwrap();
+        ctx.svm.expire_blockhash();
+        let result = ctx.execute_instruction(ix, &[&wrong_owner]).unwrap();
+        assert_eq!(result.error_name(), Some("TokenError::OwnerMismatch"));
+    }
+
+    #[test]
+    fn test_account_validation_toggle() {
+        let program_bytes = LiteSVM::new().get_account(&spl_token::id()).unwrap().data;
+        let ctx = crate::AnchorLiteSVM::new()
+            .deploy_program(Pubkey::new_unique(), &program_bytes)
+            .without_account_validation()
+            .build();
+        assert!(!ctx.account_validation);
+        assert!(AnchorContext::new(LiteSVM::new(), Pubkey::new_unique()).account_validation);
+    }
+
+    #[test]
+    #[should_panic(expected = "Instruction `transfer`: account #0 `from` should be a signer")]
+    fn test_execute_and_assert_fee_payer_validates_accounts() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        ctx.add_idl(
+            serde_json::from_value(serde_json::json!({
+                "address": "11111111111111111111111111111111",
+                "metadata": { "name": "system", "version": "0.1.0", "spec": "0.1.0" },
+                "instructions": [{
+                    "name": "transfer",
+                    "discriminator": [2, 0, 0, 0],
+                    "accounts": [{ "name": "from", "writable": true, "signer": true }, { "name": "to", "writable": true }],
+                    "args": [{ "name": "lamports", "type": "u64" }]
+                }]
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+
+        let source = Pubkey::new_unique();
+        let mut ix = anchor_lang::solana_program::system_instruction::transfer(&source, &Pubkey::new_unique(), 1);
+        ix.accounts[0].is_signer = false;
+        ctx.execute_and_assert_fee_payer(ix, &payer, &[], &payer.pubkey());
+    }
+
+    #[test]
+    fn test_transaction_size_checked_on_every_path() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let oversized = Instruction::new_with_bytes(Pubkey::new_unique(), &[0; 1_300], vec![]);
+
+        let error = ctx.simulate_instruction(oversized.clone(), &[&payer]).unwrap_err();
+        assert!(error.to_string().starts_with("Transaction too large"));
+
+        let tx = Transaction::new_signed_with_payer(
+            std::slice::from_ref(&oversized),
+            Some(&payer.pubkey()),
+            &[&payer],
+            ctx.latest_blockhash(),
+        );
+        let error = ctx.send_and_confirm_transaction(&tx).unwrap_err();
+        assert!(error.to_string().starts_with("Transaction too large"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Transaction too large")]
+    fn test_execute_and_assert_fee_payer_checks_size() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let oversized = Instruction::new_with_bytes(Pubkey::new_unique(), &[0; 1_300], vec![]);
+        ctx.execute_and_assert_fee_payer(oversized, &payer, &[], &payer.pubkey());
+    }
+
+    #[test]
+    fn test_error_names_per_program() {
+        let (mut ctx, _mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        let error_idl = |address: Pubkey, name: &str| -> Idl {
+            serde_json::from_value(serde_json::json!({
+                "address": address.to_string(),
+                "metadata": { "name": name, "version": "0.1.0", "spec": "0.1.0" },
+                "instructions": [],
+                "errors": [{ "code": 4, "name": format!("{}Error", name), "msg": "" }]
+            }))
+            .unwrap()
+        };
+        // Both programs use code 4; the one loaded last must not shadow the other
+        ctx.add_idl(error_idl(spl_token::id(), "Token")).unwrap();
+        ctx.add_idl(error_idl(Pubkey::new_unique(), "Other")).unwrap();
+
+        let wrong_owner = ctx.create_funded_account(1_000_000_000).unwrap();
+        let ix = spl_token::instruction::close_account(
+            &spl_token::id(), &ata, &payer.pubkey(), &wrong_owner.pubkey(), &[],
+        )
+        .unwrap();
+        let result = ctx.execute_instruction(ix, &[&wrong_owner]).unwrap();
+        assert_eq!(result.error_name(), Some("TokenError"));
+    }
+
+    #[test]
+    fn test_failing_program() {
+        let program_a = Pubkey::new_unique();
+        let program_b = Pubkey::new_unique();
+        let failed = |logs: Vec<String>| {
+            TransactionResult::new_failed(
+                "InstructionError(1, Custom(6000))".to_string(),
+                TransactionMetadata { logs, ..Default::default() },
+                None,
+            )
+        };
+
+        // The callee that raised the error is named before its caller
+        let cpi = failed(vec![
+            format!("Program {} invoke [1]", program_a),
+            format!("Program {} invoke [2]", program_b),
+            format!("Program {} failed: custom program error: 0x1770", program_b),
+            format!("Program {} failed: custom program error: 0x1770", program_a),
+        ]);
+        assert_eq!(failing_program(&cpi, &[program_b, program_a]), Some(program_b));
+
+        // Without logs, the instruction index picks the top-level program
+        assert_eq!(failing_program(&failed(Vec::new()), &[program_b, program_a]), Some(program_a));
+        assert_eq!(failing_program(&failed(Vec::new()), &[program_b]), None);
+
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        ctx.register_errors(&program_a, [(6000, "A::Locked")]);
+        ctx.register_errors(&program_b, [(6000, "B::Expired")]);
+        let result = ctx.with_program_error_names(cpi, &[program_b, program_a]);
+        assert_eq!(result.error_name(), Some("B::Expired"));
+        let result = ctx.with_program_error_names(failed(Vec::new()), &[program_b, program_a]);
+        assert_eq!(result.error_name(), Some("A::Locked"));
+    }
+
+    #[test]
+    fn test_account_diffs() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        let ix = mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 100);
+
+        ctx.set_account_diffs(true);
+        let result = ctx.execute_instruction(ix, &[&payer]).unwrap();
+        result.assert_success();
+
+        let mut changed: Vec<_> = result.account_changes().iter().map(|c| c.address).collect();
+        changed.sort();
+        let mut expected = vec![payer.pubkey(), mint.pubkey(), ata];
+        expected.sort();
+        assert_eq!(changed, expected);
+
+        let payer_change = result.account_change(&payer.pubkey()).unwrap();
+        assert_eq!(payer_change.lamport_delta(), -5000);
+        assert!(!payer_change.data_changed);
+        let ata_change = result.account_change(&ata).unwrap();
+        assert!(ata_change.data_changed);
+        assert_eq!(ata_change.data_len_delta(), 0);
+        assert!(!ata_change.owner_changed());
+
+        // A newly created account reports an owner change and data growth
+        let new_account = Keypair::new();
+        let rent = ctx.svm.minimum_balance_for_rent_exemption(165);
+        let ix = anchor_lang::solana_program::system_instruction::create_account(
+            &payer.pubkey(), &new_account.pubkey(), rent, 165, &spl_token::id(),
+        );
+        let result = ctx.execute_instruction(ix, &[&payer, &new_account]).unwrap();
+        let change = result.account_change(&new_account.pubkey()).unwrap();
+        assert_eq!(change.lamport_delta(), rent as i128);
+        assert_eq!(change.data_len_delta(), 165);
+        assert!(change.owner_changed());
+    }
+
+    #[test]
+    fn test_program_registry() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let program_id = Pubkey::new_unique();
+        let program_bytes = ctx.svm.get_account(&spl_token::id()).unwrap().data;
+
+        assert!(ctx.program_at(program_id).is_none());
+        assert!(ctx.program_named("token").is_none());
+
+        ctx.deploy_named_program("token", program_id, &program_bytes).unwrap();
+        assert_eq!(ctx.program_named("token").unwrap().id(), program_id);
+        assert_eq!(ctx.program_at(program_id).unwrap().id(), program_id);
+        assert_eq!(ctx.program_at(spl_token::id()).unwrap().id(), spl_token::id());
+        assert_eq!(ctx.program_at(ctx.program_id).unwrap().id(), ctx.program_id);
+    }
+
+    #[test]
+    fn test_program_registry_from_builder() {
+        let escrow = Pubkey::new_unique();
+        let oracle = Pubkey::new_unique();
+        let program_bytes = LiteSVM::new().get_account(&spl_token::id()).unwrap().data;
+
+        let ctx = crate::AnchorLiteSVM::new()
+            .deploy_named_program("escrow", escrow, &program_bytes)
+            .deploy_named_program("oracle", oracle, &program_bytes)
+            .build();
+        assert_eq!(ctx.program_id, escrow);
+        assert_eq!(ctx.program_named("oracle").unwrap().id(), oracle);
+        assert_eq!(ctx.program_named("escrow").unwrap().id(), escrow);
+        assert_eq!(ctx.program_deploy_slot(&oracle), Some(0));
+    }
+
+    #[test]
+    fn test_keypair() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let alice = ctx.keypair("alice");
+        assert_eq!(alice.pubkey(), ctx.keypair("alice").pubkey());
+        assert_ne!(alice.pubkey(), ctx.keypair("bob").pubkey());
+
+        let other = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        assert_eq!(other.keypair("alice").pubkey(), alice.pubkey());
+
+        ctx.set_keypair_seed(7);
+        assert_ne!(ctx.keypair("alice").pubkey(), alice.pubkey());
+
+        let bob = ctx.create_funded_account_named("bob", 1_000_000).unwrap();
+        assert_eq!(bob.pubkey(), ctx.keypair("bob").pubkey());
+        assert_eq!(ctx.svm.get_balance(&bob.pubkey()), Some(1_000_000));
+        assert_eq!(ctx.labels().get(&bob.pubkey()).map(String::as_str), Some("bob"));
+    }
+
+    #[test]
+    fn test_auto_fund_signers() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let user = Keypair::new();
+        let transfer = |from: &Keypair| {
+            anchor_lang::solana_program::system_instruction::transfer(&from.pubkey(), &Pubkey::new_unique(), 1_000_000)
+        };
+
+        ctx.execute_instruction(transfer(&user), &[&user]).unwrap().assert_failure();
+
+        ctx.auto_fund_signers(1_000_000_000);
+        let result = ctx.execute_instruction(transfer(&user), &[&user]).unwrap();
+        result.assert_success();
+        let balance = ctx.svm.get_balance(&user.pubkey()).unwrap();
+        assert_eq!(balance, 1_000_000_000 - 1_000_000 - result.fee_paid().unwrap());
+
+        // Funded signers aren't topped up
+        ctx.execute_instruction(transfer(&user), &[&user]).unwrap().assert_success();
+        assert!(ctx.svm.get_balance(&user.pubkey()).unwrap() < balance);
+
+        ctx.disable_auto_fund_signers();
+        let other = Keypair::new();
+        ctx.execute_instruction(transfer(&other), &[&other]).unwrap().assert_failure();
+    }
+
+    #[test]
+    fn test_transaction_hooks() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let calls = Rc::new(RefCell::new(Vec::new()));
+
+        let before = calls.clone();
+        ctx.on_before_tx(move |ctx| before.borrow_mut().push(format!("before {}", ctx.payer_balance())));
+        let after = calls.clone();
+        ctx.on_after_tx(move |_, result| after.borrow_mut().push(format!("after {}", result.is_success())));
+
+        let balance = ctx.payer_balance();
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000);
+        ctx.execute_instruction(ix, &[&payer]).unwrap();
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), u64::MAX);
+        ctx.execute_instruction(ix, &[&payer]).unwrap();
+
+        assert_eq!(
+            *calls.borrow(),
+            [
+                format!("before {}", balance),
+                "after true".to_string(),
+                format!("before {}", balance - 1_000_000 - 5_000),
+                "after false".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_invariant() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let payer_pubkey = payer.pubkey();
+        ctx.add_invariant("payer_above_9_sol", move |ctx| match ctx.svm.get_balance(&payer_pubkey) {
+            Some(balance) if balance >= 9_000_000_000 => Ok(()),
+            balance => Err(format!("payer has {:?}", balance)),
+        });
+
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000);
+        ctx.execute_instruction(ix, &[&payer]).unwrap().assert_success();
+
+        // Failed transactions aren't checked
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), u64::MAX);
+        ctx.execute_instruction(ix, &[&payer]).unwrap().assert_failure();
+    }
+
+    #[test]
+    #[should_panic(expected = "Invariant `payer_above_9_sol` violated after drain: payer has")]
+    fn test_add_invariant_violated() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let payer_pubkey = payer.pubkey();
+        ctx.add_invariant("payer_above_9_sol", move |ctx| match ctx.svm.get_balance(&payer_pubkey) {
+            Some(balance) if balance >= 9_000_000_000 => Ok(()),
+            balance => Err(format!("payer has {:?}", balance)),
+        });
+
+        let ix = anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 2_000_000_000);
+        let _ = ctx.execute_instruction_named("drain", ix, &[&payer]);
+    }
+
+    #[test]
+    fn test_hooks_survive_panic() {
+        use std::cell::Cell;
+        use std::panic::{catch_unwind, AssertUnwindSafe};
+        use std::rc::Rc;
+
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let before_calls = Rc::new(Cell::new(0));
+        let checks = Rc::new(Cell::new(0));
+
+        let before = before_calls.clone();
+        ctx.on_before_tx(move |_| {
+            before.set(before.get() + 1);
+            assert!(before.get() != 1, "first before hook call");
+        });
+        let checked = checks.clone();
+        ctx.add_invariant("fails_once", move |_| {
+            checked.set(checked.get() + 1);
+            if checked.get() == 1 { Err("first check".to_string()) } else { Ok(()) }
+        });
+        let transfer = || {
+            anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000)
+        };
+
+        // A panicking before hook stops the transaction but keeps every hook
+        let panic = catch_unwind(AssertUnwindSafe(|| ctx.execute_instruction(transfer(), &[&payer])));
+        assert!(panic.is_err());
+        assert_eq!(checks.get(), 0);
+
+        // So does a violated invariant
+        let panic = catch_unwind(AssertUnwindSafe(|| ctx.execute_instruction(transfer(), &[&payer])));
+        assert!(panic.is_err());
+        assert_eq!((before_calls.get(), checks.get()), (2, 1));
+
+        ctx.execute_instruction(transfer(), &[&payer]).unwrap().assert_success();
+        assert_eq!((before_calls.get(), checks.get()), (3, 2));
+    }
+
+    #[test]
+    fn test_history() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let payer = ctx.payer().insecure_clone();
+        let transfer = |lamports| {
+            anchor_lang::solana_program::system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), lamports)
+        };
+        assert!(ctx.last_result().is_none());
+
+        // Not recorded until enabled, but the last result is always kept
+        ctx.execute_instruction(transfer(1_000), &[&payer]).unwrap();
+        assert!(ctx.history().is_empty());
+        assert!(ctx.last_result().unwrap().is_success());
+
+        ctx.set_history(true);
+        let result = ctx.execute_instructions(vec![transfer(1_000), transfer(2_000)], &[&payer]).unwrap();
+        ctx.execute_instruction(transfer(u64::MAX), &[&payer]).unwrap();
+        assert_eq!(ctx.history().len(), 2);
+
+        let record = &ctx.history()[0];
+        let system = format!("instruction to {}", anchor_lang::system_program::ID);
+        assert_eq!(record.instruction_names, vec![system.clone(), system]);
+        assert_eq!(record.signature, result.inner().signature);
+        assert_eq!(record.compute_units, result.compute_units());
+        assert_eq!(record.account_keys[0], payer.pubkey());
+        assert!(record.result.is_success());
+        assert!(!ctx.history()[1].result.is_success());
+        assert!(!ctx.last_result().unwrap().is_success());
+
+        ctx.clear_history();
+        assert!(ctx.history().is_empty());
+    }
+
+    #[test]
+    fn test_sysvar_overrides() {
+        use solana_program::rent::Rent;
+        use solana_program::slot_hashes::SlotHashes;
+
+        let program_bytes = LiteSVM::new().get_account(&spl_token::id()).unwrap().data;
+        let rent = Rent {
+            lamports_per_byte_year: Rent::default().lamports_per_byte_year * 2,
+            ..Rent::default()
+        };
+        let hash = solana_sdk::hash::Hash::new_unique();
+        let mut ctx = crate::AnchorLiteSVM::new()
+            .deploy_program(Pubkey::new_unique(), &program_bytes)
+            .with_sysvar(&rent)
+            .with_sysvar(&SlotHashes::new(&[(41, hash)]))
+            .build();
+        assert_eq!(ctx.svm.get_sysvar::<Rent>(), rent);
+        assert_eq!(ctx.svm.minimum_balance_for_rent_exemption(0), rent.minimum_balance(0));
+        assert_eq!(ctx.svm.get_sysvar::<SlotHashes>().get(&41), Some(&hash));
+
+        ctx.set_sysvar(&Rent::default());
+        assert_eq!(ctx.svm.minimum_balance_for_rent_exemption(0), Rent::default().minimum_balance(0));
+    }
+
+    #[test]
+    fn test_program_deploy_slot_from_builder() {
+        let program_id = Pubkey::new_unique();
+        let program_bytes = LiteSVM::new().get_account(&spl_token::id()).unwrap().data;
+
+        let ctx = crate::AnchorLiteSVM::build_with_program(program_id, &program_bytes);
+        assert_eq!(ctx.program_deploy_slot(&program_id), Some(0));
+    }
+
+    #[test]
+    fn test_program_deploy_slot_from_programdata() {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), Pubkey::new_unique());
+        let program_id = Pubkey::new_unique();
+        let programdata_address = solana_loader_v3_interface::get_program_data_address(&program_id);
+
+        // Programs are stored as non-executable here so LiteSVM doesn't try to load them
+        let owner = Pubkey::from_str_const("BPFLoaderUpgradeab1e11111111111111111111111");
+        for (address, state) in [
+            (program_id, UpgradeableLoaderState::Program { programdata_address }),
+            (
+                programdata_address,
+                UpgradeableLoaderState::ProgramData { slot: 7, upgrade_authority_address: None },
+            ),
+        ] {
+            ctx.svm
+                .set_account(
+                    address,
+                    solana_sdk::account::Account {
+                        lamports: 1_000_000,
+                        data: bincode::serialize(&state).unwrap(),
+                        owner,
+                        executable: false,
+                        rent_epoch: 0,
+                    },
+                )
+                .unwrap();
+        }
+
+        assert_eq!(ctx.program_deploy_slot(&program_id), Some(7));
+    }
+
+    #[test]
+    fn test_locked_accounts_conflict() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        ctx.locked_accounts = vec![ata];
+
+        let result = ctx
+            .execute_instruction(mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10), &[&payer])
+            .unwrap();
+        result.assert_error("AccountInUse");
+
+        // Read-only usage of a locked account is not a conflict
+        ctx.locked_accounts = vec![spl_token::id()];
+        ctx.execute_instruction(mint_to_ix(&mint.pubkey(), &ata, &payer.pubkey(), 10), &[&payer])
+            .unwrap()
+            .assert_success();
+    }
+}
@@ -0,0 +1,269 @@
+//! Typed Anchor error assertions
+//!
+//! Asserting on error codes or names by hand breaks silently when an
+//! `#[error_code]` enum is reordered. These helpers take the error variant
+//! itself and compare its code against the failed transaction. The error
+//! Anchor logs, including the offending account or source location, can also
+//! be parsed into [`AnchorErrorDetails`].
+
+use anchor_lang::error::Error;
+use litesvm_utils::TransactionResult;
+
+/// An Anchor error parsed from the program logs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AnchorErrorDetails {
+    /// The error code, e.g. 2006 or 6000
+    pub code: u32,
+    /// The error name, e.g. `ConstraintSeeds`
+    pub name: String,
+    /// The error message
+    pub message: String,
+    /// The account that caused the error, for account constraint violations
+    pub account: Option<String>,
+    /// The source location that raised the error, as `file:line`
+    pub file_line: Option<String>,
+}
+
+impl AnchorErrorDetails {
+    /// Parse a log line written by Anchor's `AnchorError::log`
+    ///
+    /// Returns None if the line isn't an Anchor error log.
+    pub fn from_log(log: &str) -> Option<Self> {
+        let rest = log.strip_prefix("Program log: ").unwrap_or(log);
+        let rest = rest.strip_prefix("AnchorError ")?;
+        let (origin, rest) = rest.split_once(". Error Code: ")?;
+        let (name, rest) = rest.split_once(". Error Number: ")?;
+        let (code, message) = rest.split_once(". Error Message: ")?;
+
+        let account = origin.strip_prefix("caused by account: ").map(str::to_string);
+        let file_line = origin.strip_prefix("thrown in ").map(str::to_string);
+        Some(Self {
+            code: code.parse().ok()?,
+            name: name.to_string(),
+            message: message.strip_suffix('.').unwrap_or(message).to_string(),
+            account,
+            file_line,
+        })
+    }
+}
+
+/// Extension trait for TransactionResult to assert on typed Anchor errors
+pub trait AnchorErrorHelpers {
+    /// Get the Anchor error logged by the program, if any
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let details = result.anchor_error_details().unwrap();
+    /// assert_eq!(details.name, "ConstraintSeeds");
+    /// assert_eq!(details.account.as_deref(), Some("vault"));
+    /// ```
+    fn anchor_error_details(&self) -> Option<AnchorErrorDetails>;
+
+    /// Assert that the transaction failed with an Anchor error caused by a specific account
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction succeeded, no Anchor error was logged, or the
+    /// error was caused by a different account
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result
+    ///     .assert_error_is(ErrorCode::ConstraintSeeds)
+    ///     .assert_error_on_account("vault");
+    /// ```
+    fn assert_error_on_account(&self, account: &str) -> &Self;
+
+    /// Assert that the transaction failed with a specific Anchor error
+    ///
+    /// Accepts any variant of a program's `#[error_code]` enum, or of Anchor's
+    /// built-in `ErrorCode`, and compares its error code against the one the
+    /// transaction failed with.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction succeeded or failed with a different error
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_error_is(MyError::InsufficientFunds);
+    /// result.assert_error_is(anchor_lang::error::ErrorCode::ConstraintSeeds);
+    /// ```
+    fn assert_error_is<E: Into<Error>>(&self, error: E) -> &Self;
+}
+
+impl AnchorErrorHelpers for TransactionResult {
+    fn anchor_error_details(&self) -> Option<AnchorErrorDetails> {
+        self.logs().iter().find_map(|log| AnchorErrorDetails::from_log(log))
+    }
+
+    fn assert_error_on_account(&self, account: &str) -> &Self {
+        self.assert_failure();
+        let details = self.anchor_error_details();
+        assert_eq!(
+            details.as_ref().and_then(|details| details.account.as_deref()),
+            Some(account),
+            "Error account mismatch. Expected: {}, Actual: {}\nLogs:\n{}",
+            account,
+            match &details {
+                Some(AnchorErrorDetails { account: Some(actual), name, .. }) => format!("{} ({})", actual, name),
+                Some(details) => format!("no account ({})", details.name),
+                None => "no Anchor error logged".to_string(),
+            },
+            self.logs().join("\n")
+        );
+        self
+    }
+
+    fn assert_error_is<E: Into<Error>>(&self, error: E) -> &Self {
+        let (expected_code, expected_name) = match error.into() {
+            Error::AnchorError(error) => (error.error_code_number, error.error_name),
+            Error::ProgramError(error) => {
+                let name = format!("{:?}", error.program_error);
+                match error.program_error {
+                    anchor_lang::prelude::ProgramError::Custom(code) => (code, name),
+                    _ => {
+                        // Built-in program errors have no custom code; match the error name instead
+                        self.assert_error(&name);
+                        return self;
+                    }
+                }
+            }
+        };
+
+        self.assert_failure();
+        let actual = self.anchor_error_code();
+        assert_eq!(
+            actual,
+            Some(expected_code),
+            "Anchor error mismatch. Expected: {} ({}), Actual: {}\nLogs:\n{}",
+            expected_name,
+            expected_code,
+            self.error_name()
+                .map(|name| format!("{} ({})", name, actual.unwrap_or_default()))
+                .or_else(|| self.error().cloned())
+                .unwrap_or_default(),
+            self.logs().join("\n")
+        );
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::prelude::*;
+    use litesvm::types::TransactionMetadata;
+
+    #[error_code]
+    pub enum VaultError {
+        #[msg("Vault is locked")]
+        Locked,
+        #[msg("Insufficient funds")]
+        InsufficientFunds,
+    }
+
+    fn failed_with(error: &str) -> TransactionResult {
+        TransactionResult::new_failed(error.to_string(), TransactionMetadata::default(), None)
+    }
+
+    #[test]
+    fn test_assert_error_is() {
+        failed_with("InstructionError(0, Custom(6001))").assert_error_is(VaultError::InsufficientFunds);
+        failed_with("InstructionError(0, Custom(2006))")
+            .assert_error_is(anchor_lang::error::ErrorCode::ConstraintSeeds);
+        failed_with("InstructionError(0, InvalidArgument)").assert_error_is(ProgramError::InvalidArgument);
+    }
+
+    /// Log an Anchor error the way `AnchorError::log` does
+    fn failed_with_log(error: anchor_lang::error::AnchorError) -> TransactionResult {
+        let origin = match &error.error_origin {
+            None => "occurred".to_string(),
+            Some(ErrorOrigin::Source(source)) => format!("thrown in {}:{}", source.filename, source.line),
+            Some(ErrorOrigin::AccountName(account)) => format!("caused by account: {}", account),
+        };
+        TransactionResult::new_failed(
+            format!("InstructionError(0, Custom({}))", error.error_code_number),
+            TransactionMetadata {
+                logs: vec![
+                    "Program log: Instruction: Withdraw".to_string(),
+                    format!(
+                        "Program log: AnchorError {}. Error Code: {}. Error Number: {}. Error Message: {}.",
+                        origin, error.error_name, error.error_code_number, error.error_msg
+                    ),
+                ],
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    fn anchor_error(error: impl Into<Error>) -> anchor_lang::error::AnchorError {
+        match error.into() {
+            Error::AnchorError(error) => *error,
+            Error::ProgramError(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_anchor_error_details() {
+        let error = anchor_error(anchor_lang::error::ErrorCode::ConstraintSeeds).with_account_name("vault");
+        let result = failed_with_log(error);
+        let details = result.anchor_error_details().unwrap();
+        assert_eq!(
+            details,
+            AnchorErrorDetails {
+                code: 2006,
+                name: "ConstraintSeeds".to_string(),
+                message: "A seeds constraint was violated".to_string(),
+                account: Some("vault".to_string()),
+                file_line: None,
+            }
+        );
+        result
+            .assert_error_is(anchor_lang::error::ErrorCode::ConstraintSeeds)
+            .assert_error_on_account("vault");
+
+        let error = anchor_error(VaultError::Locked).with_source(anchor_lang::error::Source {
+            filename: "programs/vault/src/lib.rs",
+            line: 42,
+        });
+        let details = failed_with_log(error).anchor_error_details().unwrap();
+        assert_eq!((details.code, details.name.as_str()), (6000, "Locked"));
+        assert_eq!(details.message, "Vault is locked");
+        assert_eq!(details.file_line.as_deref(), Some("programs/vault/src/lib.rs:42"));
+        assert_eq!(details.account, None);
+
+        assert_eq!(failed_with("InstructionError(0, Custom(6000))").anchor_error_details(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error account mismatch. Expected: vault, Actual: config (ConstraintSeeds)")]
+    fn test_assert_error_on_account_mismatch() {
+        let error = anchor_error(anchor_lang::error::ErrorCode::ConstraintSeeds).with_account_name("config");
+        failed_with_log(error).assert_error_on_account("vault");
+    }
+
+    #[test]
+    #[should_panic(expected = "Anchor error mismatch. Expected: Locked (6000)")]
+    fn test_assert_error_is_mismatch() {
+        failed_with("InstructionError(0, Custom(6001))").assert_error_is(VaultError::Locked);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected transaction to fail")]
+    fn test_assert_error_is_success() {
+        TransactionResult::new(TransactionMetadata::default(), None).assert_error_is(VaultError::Locked);
+    }
+}
@@ -2,11 +2,18 @@
 //!
 //! This module provides helpers for working with Anchor events in tests.
 //! Anchor programs can emit events using the `emit!` macro, and these events
-//! are logged during transaction execution.
+//! are logged during transaction execution. Events emitted with `emit_cpi!`
+//! are recorded as a self-CPI instead, and are read from the transaction's
+//! inner instructions.
 
+use anchor_lang::event::EVENT_IX_TAG_LE;
 use anchor_lang::{AnchorDeserialize, Discriminator, Event};
 use base64::{engine::general_purpose, Engine as _};
 use litesvm_utils::TransactionResult;
+use solana_program::pubkey::Pubkey;
+
+/// Seed of the PDA Anchor signs `emit_cpi!` self-CPIs with
+const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
 
 /// Event parsing error types
 #[derive(Debug, thiserror::Error)]
@@ -27,10 +34,38 @@ pub enum EventError {
     AnchorError(String),
 }
 
+/// A tuple of event types in the order they are expected to be emitted
+///
+/// Implemented for tuples of up to six event types, for use with
+/// [`EventHelpers::assert_events_in_order`].
+pub trait EventSequence {
+    /// Discriminators and type names of the events, in order
+    fn events() -> Vec<(&'static [u8], &'static str)>;
+}
+
+macro_rules! impl_event_sequence {
+    ($($event:ident),+) => {
+        impl<$($event: Discriminator + Event),+> EventSequence for ($($event,)+) {
+            fn events() -> Vec<(&'static [u8], &'static str)> {
+                vec![$(($event::DISCRIMINATOR, std::any::type_name::<$event>())),+]
+            }
+        }
+    };
+}
+
+impl_event_sequence!(A);
+impl_event_sequence!(A, B);
+impl_event_sequence!(A, B, C);
+impl_event_sequence!(A, B, C, D);
+impl_event_sequence!(A, B, C, D, E);
+impl_event_sequence!(A, B, C, D, E, F);
+
 /// Extension trait for TransactionResult to add event parsing capabilities
 pub trait EventHelpers {
     /// Parse all events of a specific type from transaction logs
     ///
+    /// Events emitted with `emit_cpi!` are included, in emission order.
+    ///
     /// # Example
     ///
     /// ```ignore
@@ -50,6 +85,27 @@ pub trait EventHelpers {
     where
         T: AnchorDeserialize + Discriminator + Event;
 
+    /// Parse all events of a specific type, paired with their position in the logs
+    ///
+    /// For events logged with `emit!`, the index is the position of the event's
+    /// `Program data:` line among all transaction logs. For `emit_cpi!` events it
+    /// is the position of the self-CPI's `invoke` line. This allows assertions
+    /// about how events interleave with other log lines or events.
+    ///
+    /// Returns an error if an `emit_cpi!` event has no matching `invoke` line,
+    /// e.g. because the logs were truncated, since its position is then unknown.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let deposits: Vec<(usize, DepositEvent)> = result.parse_events_indexed().unwrap();
+    /// let withdrawals: Vec<(usize, WithdrawEvent)> = result.parse_events_indexed().unwrap();
+    /// assert!(deposits[0].0 < withdrawals[0].0);
+    /// ```
+    fn parse_events_indexed<T>(&self) -> Result<Vec<(usize, T)>, EventError>
+    where
+        T: AnchorDeserialize + Discriminator + Event;
+
     /// Parse the first event of a specific type from transaction logs
     ///
     /// # Example
@@ -97,38 +153,181 @@ pub trait EventHelpers {
     fn has_event<T>(&self) -> bool
     where
         T: AnchorDeserialize + Discriminator + Event;
+
+    /// Assert that at least one emitted event of the specified type carries a pubkey
+    ///
+    /// The pubkey is read from each event with `extract`, which makes this a
+    /// lightweight check for actor fields like `from`, `to` or `authority`.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_event_has_pubkey::<TransferEvent>(|e| e.to, &recipient.pubkey());
+    /// ```
+    fn assert_event_has_pubkey<T>(&self, extract: impl Fn(&T) -> Pubkey, expected: &Pubkey)
+    where
+        T: AnchorDeserialize + Discriminator + Event;
+
+    /// Assert that at least one emitted event of the specified type matches a predicate
+    ///
+    /// On failure, every emitted event of that type is printed, so the mismatch
+    /// is visible without deserializing the events by hand.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_event::<TransferEvent>(|e| e.amount == 100 && e.to == dest);
+    /// ```
+    fn assert_event<T>(&self, predicate: impl Fn(&T) -> bool)
+    where
+        T: AnchorDeserialize + Discriminator + Event + std::fmt::Debug;
+
+    /// Check if events of the given types were emitted in that relative order
+    ///
+    /// Other events may be emitted in between.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// assert!(result.events_in_order::<(DepositEvent, FeeEvent)>());
+    /// ```
+    fn events_in_order<S: EventSequence>(&self) -> bool;
+
+    /// Assert that events of the given types were emitted in that relative order
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_event_count::<TransferEvent>(1);
+    /// result.assert_events_in_order::<(DepositEvent, FeeEvent, TransferEvent)>();
+    /// ```
+    fn assert_events_in_order<S: EventSequence>(&self);
+}
+
+/// Every event payload (discriminator followed by Borsh data) emitted by the
+/// transaction, paired with its log index and in emission order
+///
+/// Logged events are `Program data:` lines. `emit_cpi!` events are self-CPIs:
+/// inner instructions whose data starts with Anchor's event instruction tag,
+/// whose callee is the invoking program, and whose first account is that
+/// program's event authority PDA. They are indexed by the `invoke` log line of
+/// that CPI, and an error is returned if that line is missing. Tagged CPIs into
+/// other programs are ignored.
+///
+/// Verifying a self-CPI needs the transaction's account keys, which results
+/// returned by [`AnchorContext`](crate::AnchorContext) always carry.
+pub(crate) fn event_payloads(result: &TransactionResult) -> Result<Vec<(usize, Vec<u8>)>, EventError> {
+    let mut payloads = Vec::new();
+    for (index, log) in result.logs().iter().enumerate() {
+        if let Some(event_data) = log.strip_prefix("Program data: ") {
+            let decoded = general_purpose::STANDARD
+                .decode(event_data)
+                .map_err(EventError::Base64Error)?;
+            payloads.push((index, decoded));
+        }
+    }
+
+    let cpi_logs = cpi_invocations(result.logs());
+    for (instruction_index, inner) in result.inner_instructions().iter().enumerate() {
+        for (cpi_index, ix) in inner.iter().enumerate() {
+            let Some(event_data) = ix.instruction.data.strip_prefix(EVENT_IX_TAG_LE) else {
+                continue;
+            };
+            let keys = result.account_keys().ok_or_else(|| {
+                EventError::ParseError("account keys are needed to verify emit_cpi! events".to_string())
+            })?;
+            let key = |index: u8| keys.get(index as usize).copied();
+            let Some(&(index, invoker)) = cpi_logs
+                .get(instruction_index)
+                .and_then(|invocations| invocations.get(cpi_index))
+            else {
+                return Err(EventError::ParseError(format!(
+                    "no invoke log line for inner instruction {} of instruction {}, so its emit_cpi! event can't be ordered",
+                    cpi_index, instruction_index
+                )));
+            };
+
+            let callee = key(ix.instruction.program_id_index);
+            let authority = ix.instruction.accounts.first().and_then(|account| key(*account));
+            let event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &invoker).0;
+            if callee == Some(invoker) && authority == Some(event_authority) {
+                payloads.push((index, event_data.to_vec()));
+            }
+        }
+    }
+
+    payloads.sort_by_key(|(index, _)| *index);
+    Ok(payloads)
+}
+
+/// Log index of the `invoke` line of each CPI and the program that made it,
+/// grouped by top-level instruction
+fn cpi_invocations(logs: &[String]) -> Vec<Vec<(usize, Pubkey)>> {
+    let mut invocations: Vec<Vec<(usize, Pubkey)>> = Vec::new();
+    let mut stack: Vec<Pubkey> = Vec::new();
+    for (index, log) in logs.iter().enumerate() {
+        let Some((program_id, depth)) = log
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(" invoke ["))
+            .and_then(|(program_id, depth)| {
+                Some((program_id.parse::<Pubkey>().ok()?, depth.strip_suffix(']')?.parse::<usize>().ok()?))
+            })
+        else {
+            continue;
+        };
+        stack.truncate(depth.saturating_sub(1));
+        if depth == 1 {
+            invocations.push(Vec::new());
+        } else if let Some(current) = invocations.last_mut() {
+            current.push((index, stack.last().copied().unwrap_or_default()));
+        }
+        stack.push(program_id);
+    }
+    invocations
+}
+
+/// Discriminators of every event emitted by the transaction, in emission order
+fn emitted_discriminators(result: &TransactionResult) -> Vec<Vec<u8>> {
+    event_payloads(result)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|(_, payload)| payload.len() >= 8)
+        .map(|(_, payload)| payload[..8].to_vec())
+        .collect()
 }
 
 impl EventHelpers for TransactionResult {
     fn parse_events<T>(&self) -> Result<Vec<T>, EventError>
+    where
+        T: AnchorDeserialize + Discriminator + Event,
+    {
+        Ok(self
+            .parse_events_indexed()?
+            .into_iter()
+            .map(|(_, event)| event)
+            .collect())
+    }
+
+    fn parse_events_indexed<T>(&self) -> Result<Vec<(usize, T)>, EventError>
     where
         T: AnchorDeserialize + Discriminator + Event,
     {
         let mut events = Vec::new();
 
-        // Anchor events are logged with the format: "Program data: <base64_encoded_data>"
         // The discriminator for events is the first 8 bytes
-        for log in self.logs() {
-            if let Some(event_data) = log.strip_prefix("Program data: ") {
-                // Decode base64
-                let decoded = general_purpose::STANDARD
-                    .decode(event_data)
-                    .map_err(EventError::Base64Error)?;
-
-                // Check if this matches the event discriminator
-                if decoded.len() < 8 {
-                    continue;
-                }
+        for (index, decoded) in event_payloads(self)? {
+            if decoded.len() < 8 {
+                continue;
+            }
 
-                let discriminator = &decoded[0..8];
-                if discriminator == T::DISCRIMINATOR {
-                    // Deserialize the event (skip discriminator)
-                    let mut event_data_slice = &decoded[8..];
-                    match T::deserialize(&mut event_data_slice) {
-                        Ok(event) => events.push(event),
-                        Err(e) => {
-                            return Err(EventError::AnchorError(e.to_string()));
-                        }
+            let discriminator = &decoded[0..8];
+            if discriminator == T::DISCRIMINATOR {
+                // Deserialize the event (skip discriminator)
+                let mut event_data_slice = &decoded[8..];
+                match T::deserialize(&mut event_data_slice) {
+                    Ok(event) => events.push((index, event)),
+                    Err(e) => {
+                        return Err(EventError::AnchorError(e.to_string()));
                     }
                 }
             }
@@ -206,6 +405,81 @@ impl EventHelpers for TransactionResult {
             .map(|events| !events.is_empty())
             .unwrap_or(false)
     }
+
+    fn assert_event_has_pubkey<T>(&self, extract: impl Fn(&T) -> Pubkey, expected: &Pubkey)
+    where
+        T: AnchorDeserialize + Discriminator + Event,
+    {
+        let events = self.parse_events::<T>().unwrap_or_else(|e| {
+            panic!(
+                "Failed to parse events of type '{}': {}\nLogs:\n{}",
+                std::any::type_name::<T>(),
+                e,
+                self.logs().join("\n")
+            )
+        });
+
+        let pubkeys: Vec<Pubkey> = events.iter().map(extract).collect();
+        assert!(
+            pubkeys.contains(expected),
+            "Expected an event of type '{}' with pubkey {}, but found: {:?}\nLogs:\n{}",
+            std::any::type_name::<T>(),
+            expected,
+            pubkeys,
+            self.logs().join("\n")
+        );
+    }
+
+    fn assert_event<T>(&self, predicate: impl Fn(&T) -> bool)
+    where
+        T: AnchorDeserialize + Discriminator + Event + std::fmt::Debug,
+    {
+        let events = self.parse_events::<T>().unwrap_or_else(|e| {
+            panic!(
+                "Failed to parse events of type '{}': {}\nLogs:\n{}",
+                std::any::type_name::<T>(),
+                e,
+                self.logs().join("\n")
+            )
+        });
+
+        assert!(
+            events.iter().any(predicate),
+            "No event of type '{}' matched the predicate. Emitted events of that type:\n{:#?}\nLogs:\n{}",
+            std::any::type_name::<T>(),
+            events,
+            self.logs().join("\n")
+        );
+    }
+
+    fn events_in_order<S: EventSequence>(&self) -> bool {
+        let mut emitted = emitted_discriminators(self).into_iter();
+        S::events()
+            .iter()
+            .all(|(discriminator, _)| emitted.any(|emitted| emitted == *discriminator))
+    }
+
+    fn assert_events_in_order<S: EventSequence>(&self) {
+        if self.events_in_order::<S>() {
+            return;
+        }
+        let expected = S::events();
+        let emitted: Vec<&str> = emitted_discriminators(self)
+            .iter()
+            .map(|emitted| {
+                expected
+                    .iter()
+                    .find(|(discriminator, _)| emitted == discriminator)
+                    .map_or("<other event>", |(_, name)| name)
+            })
+            .collect();
+        panic!(
+            "Expected events in order {:?}, but emitted: {:?}\nLogs:\n{}",
+            expected.iter().map(|(_, name)| name).collect::<Vec<_>>(),
+            emitted,
+            self.logs().join("\n")
+        );
+    }
 }
 
 /// Helper function to manually parse event data from a base64-encoded string
@@ -245,6 +519,38 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use anchor_lang::prelude::*;
+    use litesvm::types::TransactionMetadata;
+    use solana_sdk::inner_instruction::InnerInstruction;
+    use solana_sdk::message::compiled_instruction::CompiledInstruction;
+
+    #[event]
+    #[derive(Debug)]
+    struct TestEvent {
+        authority: Pubkey,
+        amount: u64,
+    }
+
+    #[event]
+    struct OtherEvent {
+        code: u8,
+    }
+
+    /// Encode an event the way Anchor's `emit!` logs it
+    fn event_log(event: &TestEvent) -> String {
+        format!("Program data: {}", general_purpose::STANDARD.encode(event.data()))
+    }
+
+    /// Build a successful transaction result with the given log lines
+    fn result_with_logs(logs: Vec<String>) -> TransactionResult {
+        TransactionResult::new(
+            TransactionMetadata {
+                logs,
+                ..Default::default()
+            },
+            None,
+        )
+    }
 
     #[test]
     fn test_event_error_display() {
@@ -254,4 +560,196 @@ mod tests {
         let err = EventError::ParseError("test error".to_string());
         assert_eq!(err.to_string(), "Failed to parse event data: test error");
     }
+
+    #[test]
+    fn test_parse_events() {
+        let event = TestEvent { authority: Pubkey::new_unique(), amount: 42 };
+        let result = result_with_logs(vec!["Program log: hello".to_string(), event_log(&event)]);
+
+        let events: Vec<TestEvent> = result.parse_events().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].amount, 42);
+        assert_eq!(events[0].authority, event.authority);
+    }
+
+    #[test]
+    fn test_parse_events_indexed() {
+        let first = TestEvent { authority: Pubkey::new_unique(), amount: 1 };
+        let second = TestEvent { authority: Pubkey::new_unique(), amount: 2 };
+        let result = result_with_logs(vec![
+            event_log(&first),
+            "Program log: between".to_string(),
+            event_log(&second),
+        ]);
+
+        let events: Vec<(usize, TestEvent)> = result.parse_events_indexed().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].0, events[0].1.amount), (0, 1));
+        assert_eq!((events[1].0, events[1].1.amount), (2, 2));
+    }
+
+    #[test]
+    fn test_assert_event_has_pubkey() {
+        let first = TestEvent { authority: Pubkey::new_unique(), amount: 1 };
+        let second = TestEvent { authority: Pubkey::new_unique(), amount: 2 };
+        let result = result_with_logs(vec![event_log(&first), event_log(&second)]);
+
+        result.assert_event_has_pubkey::<TestEvent>(|e| e.authority, &second.authority);
+    }
+
+    #[test]
+    #[should_panic(expected = "with pubkey")]
+    fn test_assert_event_has_pubkey_fails() {
+        let event = TestEvent { authority: Pubkey::new_unique(), amount: 1 };
+        let result = result_with_logs(vec![event_log(&event)]);
+
+        result.assert_event_has_pubkey::<TestEvent>(|e| e.authority, &Pubkey::new_unique());
+    }
+
+    #[test]
+    fn test_assert_event() {
+        let first = TestEvent { authority: Pubkey::new_unique(), amount: 1 };
+        let second = TestEvent { authority: Pubkey::new_unique(), amount: 100 };
+        let result = result_with_logs(vec![event_log(&first), event_log(&second)]);
+
+        result.assert_event::<TestEvent>(|e| e.amount == 100 && e.authority == second.authority);
+    }
+
+    #[test]
+    #[should_panic(expected = "amount: 7")]
+    fn test_assert_event_dumps_events() {
+        let event = TestEvent { authority: Pubkey::new_unique(), amount: 7 };
+        let result = result_with_logs(vec![event_log(&event)]);
+
+        result.assert_event::<TestEvent>(|e| e.amount == 100);
+    }
+
+    #[test]
+    fn test_events_in_order() {
+        let event = TestEvent { authority: Pubkey::new_unique(), amount: 1 };
+        let other = format!("Program data: {}", general_purpose::STANDARD.encode(OtherEvent { code: 1 }.data()));
+        let result = result_with_logs(vec![
+            event_log(&event),
+            "Program log: between".to_string(),
+            other.clone(),
+            event_log(&event),
+        ]);
+
+        assert!(result.events_in_order::<(TestEvent, OtherEvent)>());
+        assert!(result.events_in_order::<(OtherEvent, TestEvent)>());
+        assert!(result.events_in_order::<(TestEvent, OtherEvent, TestEvent)>());
+        assert!(!result.events_in_order::<(OtherEvent, OtherEvent)>());
+        result.assert_events_in_order::<(TestEvent, TestEvent)>();
+
+        let result = result_with_logs(vec![other, event_log(&event)]);
+        assert!(!result.events_in_order::<(TestEvent, OtherEvent)>());
+    }
+
+    #[test]
+    #[should_panic(expected = "but emitted")]
+    fn test_assert_events_in_order_fails() {
+        let event = TestEvent { authority: Pubkey::new_unique(), amount: 1 };
+        let result = result_with_logs(vec![event_log(&event)]);
+
+        result.assert_events_in_order::<(OtherEvent, TestEvent)>();
+    }
+
+    /// A CPI at stack height 2 into the account key at `program_index`
+    fn inner_instruction(program_index: u8, data: Vec<u8>, accounts: Vec<u8>) -> InnerInstruction {
+        InnerInstruction {
+            instruction: CompiledInstruction::new_from_raw_parts(program_index, data, accounts),
+            stack_height: 2,
+        }
+    }
+
+    /// Instruction data of an `emit_cpi!` self-CPI carrying `event`
+    fn event_cpi_data(event: &TestEvent) -> Vec<u8> {
+        [EVENT_IX_TAG_LE, event.data().as_slice()].concat()
+    }
+
+    #[test]
+    fn test_parse_emit_cpi_events() {
+        let program = Pubkey::new_unique();
+        let event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &program).0;
+        let logged = TestEvent { authority: Pubkey::new_unique(), amount: 1 };
+        let emitted = TestEvent { authority: Pubkey::new_unique(), amount: 2 };
+        let self_cpi = inner_instruction(1, event_cpi_data(&emitted), vec![2]);
+        let other_cpi = inner_instruction(3, vec![2, 0, 0, 0], vec![]);
+        let result = TransactionResult::new(
+            TransactionMetadata {
+                logs: vec![
+                    format!("Program {} invoke [1]", program),
+                    format!("Program {} invoke [2]", solana_sdk_ids::system_program::id()),
+                    format!("Program {} success", solana_sdk_ids::system_program::id()),
+                    format!("Program {} invoke [2]", program),
+                    format!("Program {} success", program),
+                    event_log(&logged),
+                    format!("Program {} success", program),
+                ],
+                inner_instructions: vec![vec![other_cpi, self_cpi]],
+                ..Default::default()
+            },
+            None,
+        )
+        .with_account_keys(vec![Pubkey::new_unique(), program, event_authority, solana_sdk_ids::system_program::id()]);
+
+        let events: Vec<(usize, TestEvent)> = result.parse_events_indexed().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!((events[0].0, events[0].1.amount), (3, 2));
+        assert_eq!((events[1].0, events[1].1.amount), (5, 1));
+        assert_eq!(events[0].1.authority, emitted.authority);
+        result.assert_event_count::<TestEvent>(2);
+    }
+
+    #[test]
+    fn test_tagged_cpi_to_other_program_is_not_an_event() {
+        let program = Pubkey::new_unique();
+        let other_program = Pubkey::new_unique();
+        let event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &program).0;
+        let event = TestEvent { authority: Pubkey::new_unique(), amount: 1 };
+        let result = TransactionResult::new(
+            TransactionMetadata {
+                logs: vec![
+                    format!("Program {} invoke [1]", program),
+                    // Another program whose data happens to start with the event tag
+                    format!("Program {} invoke [2]", other_program),
+                    format!("Program {} success", other_program),
+                    // A self-CPI without the event authority
+                    format!("Program {} invoke [2]", program),
+                    format!("Program {} success", program),
+                    format!("Program {} success", program),
+                ],
+                inner_instructions: vec![vec![
+                    inner_instruction(3, event_cpi_data(&event), vec![2]),
+                    inner_instruction(1, event_cpi_data(&event), vec![0]),
+                ]],
+                ..Default::default()
+            },
+            None,
+        )
+        .with_account_keys(vec![Pubkey::new_unique(), program, event_authority, other_program]);
+
+        result.assert_event_count::<TestEvent>(0);
+        assert!(!result.has_event::<TestEvent>());
+        assert!(!result.events_in_order::<(TestEvent,)>());
+    }
+
+    #[test]
+    fn test_emit_cpi_event_without_invoke_log() {
+        let program = Pubkey::new_unique();
+        let event_authority = Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &program).0;
+        let event = TestEvent { authority: Pubkey::new_unique(), amount: 1 };
+        let result = TransactionResult::new(
+            TransactionMetadata {
+                logs: vec![format!("Program {} invoke [1]", program), "Log truncated".to_string()],
+                inner_instructions: vec![vec![inner_instruction(1, event_cpi_data(&event), vec![2])]],
+                ..Default::default()
+            },
+            None,
+        )
+        .with_account_keys(vec![Pubkey::new_unique(), program, event_authority]);
+
+        let err = result.parse_events_indexed::<TestEvent>().unwrap_err();
+        assert!(err.to_string().contains("no invoke log line"), "{}", err);
+    }
 }
@@ -0,0 +1,1182 @@
+//! Anchor IDL support
+//!
+//! This module loads Anchor IDL JSON files and uses them to resolve instruction
+//! accounts, deriving PDAs from the seed definitions declared in the IDL. It can
+//! also build instructions from JSON accounts and arguments, for programs whose
+//! crates can't be imported into the test, and decode instruction data back
+//! into JSON arguments.
+
+use anchor_lang::AnchorSerialize;
+pub use anchor_lang_idl_spec::Idl;
+use anchor_lang_idl_spec::{
+    IdlArrayLen, IdlDefinedFields, IdlInstruction, IdlInstructionAccount,
+    IdlInstructionAccountItem, IdlSeed, IdlSerialization, IdlType, IdlTypeDefTy,
+};
+use serde_json::Value;
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_program::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum IdlError {
+    #[error("Failed to read IDL file: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Failed to parse IDL: {0}")]
+    ParseError(String),
+
+    #[error("Instruction not found in IDL: {0}")]
+    InstructionNotFound(String),
+
+    #[error("Missing account `{0}`")]
+    MissingAccount(String),
+
+    #[error("Missing argument `{0}`")]
+    MissingArg(String),
+
+    #[error("Invalid value for `{name}`: {reason}")]
+    InvalidArg { name: String, reason: String },
+
+    #[error("Invalid account `{name}`: {reason}")]
+    InvalidAccount { name: String, reason: String },
+
+    #[error("Program not found in loaded IDLs: {0}")]
+    ProgramNotFound(String),
+
+    #[error("Unsupported seed for account `{account}`: {reason}")]
+    UnsupportedSeed { account: String, reason: String },
+
+    #[error("Invalid address `{0}` in IDL")]
+    InvalidAddress(String),
+
+    #[error("Invalid instruction data for `{name}`: {reason}")]
+    InvalidData { name: String, reason: String },
+
+    #[error("Instruction `{instruction}`: account #{index} `{name}` {reason}")]
+    AccountMismatch {
+        instruction: String,
+        index: usize,
+        name: String,
+        reason: String,
+    },
+}
+
+/// Parse an Anchor IDL from its JSON representation
+pub fn parse_idl(json: &str) -> Result<Idl, IdlError> {
+    serde_json::from_str(json).map_err(|e| IdlError::ParseError(e.to_string()))
+}
+
+/// Load an Anchor IDL from a JSON file, e.g. `target/idl/my_program.json`
+pub fn load_idl(path: &str) -> Result<Idl, IdlError> {
+    parse_idl(&std::fs::read_to_string(path)?)
+}
+
+/// Account and argument values used to resolve an instruction's accounts from an IDL
+///
+/// Accounts with a fixed address or PDA seeds in the IDL don't need to be provided;
+/// only the accounts and arguments the seeds depend on do.
+///
+/// # Example
+/// ```ignore
+/// let inputs = IdlAccountInputs::new()
+///     .account("user", user.pubkey())
+///     .arg("id", 42u64);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct IdlAccountInputs {
+    accounts: HashMap<String, Pubkey>,
+    args: HashMap<String, Vec<u8>>,
+}
+
+impl IdlAccountInputs {
+    /// Create an empty set of inputs
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Provide the address of an account by its IDL name
+    pub fn account(mut self, name: &str, pubkey: Pubkey) -> Self {
+        self.accounts.insert(name.to_string(), pubkey);
+        self
+    }
+
+    /// Provide the value of an instruction argument used by PDA seeds
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value can't be serialized
+    pub fn arg<T: AnchorSerialize>(mut self, name: &str, value: T) -> Self {
+        let bytes = borsh::to_vec(&value)
+            .unwrap_or_else(|e| panic!("Failed to serialize argument `{}`: {}", name, e));
+        self.args.insert(name.to_string(), bytes);
+        self
+    }
+}
+
+/// Find an instruction in the IDL by name
+pub(crate) fn find_instruction<'a>(
+    idl: &'a Idl,
+    name: &str,
+) -> Result<&'a IdlInstruction, IdlError> {
+    idl.instructions
+        .iter()
+        .find(|ix| ix.name == name)
+        .ok_or_else(|| IdlError::InstructionNotFound(name.to_string()))
+}
+
+/// Find the name of the IDL instruction whose discriminator prefixes the instruction data
+pub fn instruction_name_for<'a>(idl: &'a Idl, data: &[u8]) -> Option<&'a str> {
+    idl.instructions
+        .iter()
+        .find(|ix| !ix.discriminator.is_empty() && data.starts_with(&ix.discriminator))
+        .map(|ix| ix.name.as_str())
+}
+
+/// Check an instruction's account metas against its IDL definition
+///
+/// Catches missing accounts, signer and writable flags the IDL requires, and
+/// accounts with a fixed IDL address set to a different one. Account indexes in
+/// errors are 0-based. Extra accounts are allowed as remaining accounts, optional
+/// accounts passed as the program id are treated as `None`, and instructions
+/// that aren't in the IDL are not checked.
+///
+/// # Example
+/// ```ignore
+/// let err = validate_instruction_accounts(&idl, &ix).unwrap_err();
+/// assert_eq!(err.to_string(), "Instruction `withdraw`: account #3 `vault` should be writable");
+/// ```
+pub fn validate_instruction_accounts(idl: &Idl, instruction: &Instruction) -> Result<(), IdlError> {
+    let Some(ix) = idl
+        .instructions
+        .iter()
+        .find(|ix| !ix.discriminator.is_empty() && instruction.data.starts_with(&ix.discriminator))
+    else {
+        return Ok(());
+    };
+
+    let mut expected = Vec::new();
+    flatten_accounts(&ix.accounts, &mut expected);
+    let mismatch = |index: usize, account: &IdlInstructionAccount, reason: String| IdlError::AccountMismatch {
+        instruction: ix.name.clone(),
+        index,
+        name: account.name.clone(),
+        reason,
+    };
+
+    for (index, account) in expected.iter().enumerate() {
+        let Some(meta) = instruction.accounts.get(index) else {
+            return Err(mismatch(
+                index,
+                account,
+                format!("is missing (expected {} accounts, got {})", expected.len(), instruction.accounts.len()),
+            ));
+        };
+        if account.optional && meta.pubkey == instruction.program_id {
+            continue;
+        }
+        if let Some(address) = account.address.as_deref().and_then(|a| Pubkey::from_str(a).ok()) {
+            if meta.pubkey != address {
+                return Err(mismatch(index, account, format!("should be {}, got {}", address, meta.pubkey)));
+            }
+        }
+        if account.writable && !meta.is_writable {
+            return Err(mismatch(index, account, "should be writable".to_string()));
+        }
+        if account.signer && !meta.is_signer {
+            return Err(mismatch(index, account, "should be a signer".to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Decode instruction data into the IDL instruction name and its arguments
+///
+/// The 8-byte discriminator is mapped back to the instruction, and the
+/// arguments are Borsh-decoded into a JSON object keyed by argument name, in the
+/// same format accepted by [`IdlInstructionBuilder::args_json`]. 128-bit
+/// integers are decoded as strings, byte vectors as arrays of numbers, public
+/// keys as base58 strings and enums as the variant name or an object with the
+/// variant name as its only key.
+///
+/// # Example
+/// ```ignore
+/// let cpi = &result.cpis()[0];
+/// let (name, args) = decode_instruction(&dex_idl, &cpi.data)?;
+/// assert_eq!(name, "swap");
+/// assert_eq!(args["amount_in"], 1_000);
+/// ```
+pub fn decode_instruction(idl: &Idl, data: &[u8]) -> Result<(String, Value), IdlError> {
+    let ix = idl
+        .instructions
+        .iter()
+        .find(|ix| !ix.discriminator.is_empty() && data.starts_with(&ix.discriminator))
+        .ok_or_else(|| {
+            IdlError::InstructionNotFound(format!("discriminator {:?}", &data[..data.len().min(8)]))
+        })?;
+
+    let mut input = &data[ix.discriminator.len()..];
+    let mut args = serde_json::Map::new();
+    for arg in &ix.args {
+        let value = decode_value(idl, &arg.ty, &mut input).map_err(|reason| IdlError::InvalidData {
+            name: format!("{}.{}", ix.name, arg.name),
+            reason,
+        })?;
+        args.insert(arg.name.clone(), value);
+    }
+    if !input.is_empty() {
+        return Err(IdlError::InvalidData {
+            name: ix.name.clone(),
+            reason: format!("{} trailing bytes", input.len()),
+        });
+    }
+    Ok((ix.name.clone(), Value::Object(args)))
+}
+
+/// Take the next `len` bytes from the input
+fn take<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], String> {
+    if input.len() < len {
+        return Err(format!("expected {} more bytes, found {}", len, input.len()));
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Ok(bytes)
+}
+
+/// Borsh-decode a value of the given IDL type into JSON
+fn decode_value(idl: &Idl, ty: &IdlType, input: &mut &[u8]) -> Result<Value, String> {
+    macro_rules! decode_int {
+        ($t:ty) => {{
+            let bytes = take(input, std::mem::size_of::<$t>())?;
+            <$t>::from_le_bytes(bytes.try_into().unwrap())
+        }};
+    }
+
+    Ok(match ty {
+        IdlType::Bool => match take(input, 1)?[0] {
+            0 => Value::Bool(false),
+            1 => Value::Bool(true),
+            other => return Err(format!("invalid bool {}", other)),
+        },
+        IdlType::U8 => decode_int!(u8).into(),
+        IdlType::I8 => decode_int!(i8).into(),
+        IdlType::U16 => decode_int!(u16).into(),
+        IdlType::I16 => decode_int!(i16).into(),
+        IdlType::U32 => decode_int!(u32).into(),
+        IdlType::I32 => decode_int!(i32).into(),
+        IdlType::U64 => decode_int!(u64).into(),
+        IdlType::I64 => decode_int!(i64).into(),
+        IdlType::U128 => decode_int!(u128).to_string().into(),
+        IdlType::I128 => decode_int!(i128).to_string().into(),
+        IdlType::F32 => (decode_int!(f32) as f64).into(),
+        IdlType::F64 => decode_int!(f64).into(),
+        IdlType::String => {
+            let len = decode_int!(u32) as usize;
+            String::from_utf8(take(input, len)?.to_vec())
+                .map_err(|e| format!("invalid UTF-8 string: {}", e))?
+                .into()
+        }
+        IdlType::Bytes => {
+            let len = decode_int!(u32) as usize;
+            take(input, len)?.to_vec().into()
+        }
+        IdlType::Pubkey => {
+            let bytes: [u8; 32] = take(input, 32)?.try_into().unwrap();
+            Pubkey::new_from_array(bytes).to_string().into()
+        }
+        IdlType::Option(inner) => match take(input, 1)?[0] {
+            0 => Value::Null,
+            1 => decode_value(idl, inner, input)?,
+            other => return Err(format!("invalid option tag {}", other)),
+        },
+        IdlType::Vec(inner) => {
+            let len = decode_int!(u32) as usize;
+            (0..len)
+                .map(|_| decode_value(idl, inner, input))
+                .collect::<Result<Vec<_>, _>>()?
+                .into()
+        }
+        IdlType::Array(inner, IdlArrayLen::Value(len)) => (0..*len)
+            .map(|_| decode_value(idl, inner, input))
+            .collect::<Result<Vec<_>, _>>()?
+            .into(),
+        IdlType::Defined { name, generics } if generics.is_empty() => {
+            let def = idl
+                .types
+                .iter()
+                .find(|t| &t.name == name)
+                .ok_or_else(|| format!("type `{}` not found in IDL", name))?;
+            if def.serialization != IdlSerialization::Borsh {
+                return Err(format!("type `{}` is not Borsh serialized", name));
+            }
+            match &def.ty {
+                IdlTypeDefTy::Struct { fields } => decode_fields(idl, fields.as_ref(), input)?,
+                IdlTypeDefTy::Enum { variants } => {
+                    let index = take(input, 1)?[0] as usize;
+                    let variant = variants
+                        .get(index)
+                        .ok_or_else(|| format!("invalid variant {} of `{}`", index, name))?;
+                    match &variant.fields {
+                        None => Value::String(variant.name.clone()),
+                        fields => {
+                            let fields = decode_fields(idl, fields.as_ref(), input)?;
+                            serde_json::json!({ variant.name.clone(): fields })
+                        }
+                    }
+                }
+                IdlTypeDefTy::Type { alias } => decode_value(idl, alias, input)?,
+            }
+        }
+        other => return Err(format!("unsupported IDL type {:?}", other)),
+    })
+}
+
+/// Borsh-decode the fields of a struct or enum variant into JSON
+fn decode_fields(
+    idl: &Idl,
+    fields: Option<&IdlDefinedFields>,
+    input: &mut &[u8],
+) -> Result<Value, String> {
+    match fields {
+        None => Ok(Value::Null),
+        Some(IdlDefinedFields::Named(fields)) => {
+            let mut map = serde_json::Map::new();
+            for field in fields {
+                map.insert(field.name.clone(), decode_value(idl, &field.ty, input)?);
+            }
+            Ok(Value::Object(map))
+        }
+        Some(IdlDefinedFields::Tuple(types)) => Ok(types
+            .iter()
+            .map(|ty| decode_value(idl, ty, input))
+            .collect::<Result<Vec<_>, _>>()?
+            .into()),
+    }
+}
+
+/// A program described by an IDL, for building instructions without its crate
+///
+/// Usually obtained with `AnchorContext::idl_program`.
+///
+/// # Example
+/// ```ignore
+/// let ix = ctx.idl_program("escrow")?
+///     .instruction("make")
+///     .accounts_json(json!({ "maker": maker.pubkey().to_string(), "mint_a": mint_a.to_string() }))
+///     .args_json(json!({ "seed": 1, "amount": 100 }))
+///     .instruction()?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct IdlProgram<'a> {
+    idl: &'a Idl,
+    program_id: Pubkey,
+}
+
+impl<'a> IdlProgram<'a> {
+    /// Create a handle for an IDL, using the program address declared in it
+    pub fn new(idl: &'a Idl) -> Result<Self, IdlError> {
+        let program_id = Pubkey::from_str(&idl.address)
+            .map_err(|_| IdlError::InvalidAddress(idl.address.clone()))?;
+        Ok(Self { idl, program_id })
+    }
+
+    /// Get the program ID
+    pub fn id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    /// Get the underlying IDL
+    pub fn idl(&self) -> &'a Idl {
+        self.idl
+    }
+
+    /// Decode instruction data into the IDL instruction name and its arguments
+    ///
+    /// See [`decode_instruction`].
+    pub fn decode_instruction(&self, data: &[u8]) -> Result<(String, Value), IdlError> {
+        decode_instruction(self.idl, data)
+    }
+
+    /// Start building an instruction by its IDL name
+    pub fn instruction(&self, name: &str) -> IdlInstructionBuilder<'a> {
+        IdlInstructionBuilder {
+            program: *self,
+            name: name.to_string(),
+            accounts: Value::Null,
+            args: Value::Null,
+            remaining_accounts: Vec::new(),
+        }
+    }
+}
+
+/// Builder for an instruction described by an IDL
+///
+/// Accounts and arguments are given as JSON objects keyed by their IDL names.
+/// Errors are reported when the instruction is built.
+pub struct IdlInstructionBuilder<'a> {
+    program: IdlProgram<'a>,
+    name: String,
+    accounts: Value,
+    args: Value,
+    remaining_accounts: Vec<AccountMeta>,
+}
+
+impl IdlInstructionBuilder<'_> {
+    /// Set the accounts as a JSON object of IDL account names to base58 addresses
+    ///
+    /// Accounts with a fixed address or PDA seeds in the IDL may be omitted.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .accounts_json(json!({ "maker": maker.pubkey().to_string() }))
+    /// ```
+    pub fn accounts_json(mut self, accounts: Value) -> Self {
+        self.accounts = accounts;
+        self
+    }
+
+    /// Set the arguments as a JSON object of IDL argument names to values
+    ///
+    /// Integers may be given as JSON numbers or strings, public keys as base58
+    /// strings, options as `null` or the value, and enums as the variant name or
+    /// an object with the variant name as its only key.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .args_json(json!({ "amount": 100, "receive": "340282366920938463463374607431768211455" }))
+    /// ```
+    pub fn args_json(mut self, args: Value) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Add accounts after the ones declared in the IDL
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.remaining_accounts.extend(accounts);
+        self
+    }
+
+    /// Build the instruction
+    pub fn instruction(self) -> Result<Instruction, IdlError> {
+        let idl = self.program.idl;
+        let ix = find_instruction(idl, &self.name)?;
+
+        let mut inputs = IdlAccountInputs::new();
+        if let Some(accounts) = self.accounts.as_object() {
+            for (name, value) in accounts {
+                let pubkey = value
+                    .as_str()
+                    .and_then(|s| Pubkey::from_str(s).ok())
+                    .ok_or_else(|| IdlError::InvalidAccount {
+                        name: name.clone(),
+                        reason: "expected a base58 address".to_string(),
+                    })?;
+                inputs = inputs.account(name, pubkey);
+            }
+        } else if !self.accounts.is_null() {
+            return Err(IdlError::InvalidAccount {
+                name: self.name,
+                reason: "accounts must be a JSON object".to_string(),
+            });
+        }
+
+        let mut data = ix.discriminator.clone();
+        for arg in &ix.args {
+            let value = self
+                .args
+                .get(&arg.name)
+                .ok_or_else(|| IdlError::MissingArg(arg.name.clone()))?;
+            let mut bytes = Vec::new();
+            encode_value(idl, &arg.ty, value, &arg.name, &mut bytes)?;
+            data.extend_from_slice(&bytes);
+            inputs.args.insert(arg.name.clone(), bytes);
+        }
+
+        let mut accounts = resolve_accounts(idl, &self.program.program_id, &self.name, &inputs)?;
+        accounts.extend(self.remaining_accounts);
+        Ok(Instruction {
+            program_id: self.program.program_id,
+            accounts,
+            data,
+        })
+    }
+}
+
+/// Borsh-encode a JSON value as the given IDL type
+pub(crate) fn encode_value(
+    idl: &Idl,
+    ty: &IdlType,
+    value: &Value,
+    path: &str,
+    out: &mut Vec<u8>,
+) -> Result<(), IdlError> {
+    let invalid = |reason: String| IdlError::InvalidArg {
+        name: path.to_string(),
+        reason,
+    };
+    let int = |value: &Value| -> Option<i128> {
+        value
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| value.as_u64().map(i128::from))
+            .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+    };
+    macro_rules! encode_int {
+        ($t:ty) => {{
+            let v = int(value)
+                .and_then(|v| <$t>::try_from(v).ok())
+                .ok_or_else(|| invalid(format!("expected {}, got {}", stringify!($t), value)))?;
+            out.extend_from_slice(&v.to_le_bytes());
+        }};
+    }
+
+    match ty {
+        IdlType::Bool => {
+            let v = value
+                .as_bool()
+                .ok_or_else(|| invalid(format!("expected bool, got {}", value)))?;
+            out.push(v as u8);
+        }
+        IdlType::U8 => encode_int!(u8),
+        IdlType::I8 => encode_int!(i8),
+        IdlType::U16 => encode_int!(u16),
+        IdlType::I16 => encode_int!(i16),
+        IdlType::U32 => encode_int!(u32),
+        IdlType::I32 => encode_int!(i32),
+        IdlType::U64 => encode_int!(u64),
+        IdlType::I64 => encode_int!(i64),
+        IdlType::I128 => encode_int!(i128),
+        IdlType::U128 => {
+            let v = value
+                .as_u64()
+                .map(u128::from)
+                .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+                .ok_or_else(|| invalid(format!("expected u128, got {}", value)))?;
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+        IdlType::F32 | IdlType::F64 => {
+            let v = value
+                .as_f64()
+                .ok_or_else(|| invalid(format!("expected a number, got {}", value)))?;
+            if matches!(ty, IdlType::F32) {
+                out.extend_from_slice(&(v as f32).to_le_bytes());
+            } else {
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        IdlType::String => {
+            let v = value
+                .as_str()
+                .ok_or_else(|| invalid(format!("expected string, got {}", value)))?;
+            out.extend_from_slice(&(v.len() as u32).to_le_bytes());
+            out.extend_from_slice(v.as_bytes());
+        }
+        IdlType::Bytes => {
+            let items = value
+                .as_array()
+                .ok_or_else(|| invalid(format!("expected an array of bytes, got {}", value)))?;
+            out.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            for item in items {
+                encode_value(idl, &IdlType::U8, item, path, out)?;
+            }
+        }
+        IdlType::Pubkey => {
+            let v = value
+                .as_str()
+                .and_then(|s| Pubkey::from_str(s).ok())
+                .ok_or_else(|| invalid(format!("expected a base58 address, got {}", value)))?;
+            out.extend_from_slice(v.as_ref());
+        }
+        IdlType::Option(inner) => {
+            if value.is_null() {
+                out.push(0);
+            } else {
+                out.push(1);
+                encode_value(idl, inner, value, path, out)?;
+            }
+        }
+        IdlType::Vec(inner) => {
+            let items = value
+                .as_array()
+                .ok_or_else(|| invalid(format!("expected an array, got {}", value)))?;
+            out.extend_from_slice(&(items.len() as u32).to_le_bytes());
+            for (i, item) in items.iter().enumerate() {
+                encode_value(idl, inner, item, &format!("{}[{}]", path, i), out)?;
+            }
+        }
+        IdlType::Array(inner, IdlArrayLen::Value(len)) => {
+            let items = value
+                .as_array()
+                .filter(|items| items.len() == *len)
+                .ok_or_else(|| invalid(format!("expected an array of {} items, got {}", len, value)))?;
+            for (i, item) in items.iter().enumerate() {
+                encode_value(idl, inner, item, &format!("{}[{}]", path, i), out)?;
+            }
+        }
+        IdlType::Defined { name, generics } if generics.is_empty() => {
+            let def = idl
+                .types
+                .iter()
+                .find(|t| &t.name == name)
+                .ok_or_else(|| invalid(format!("type `{}` not found in IDL", name)))?;
+            if def.serialization != IdlSerialization::Borsh {
+                return Err(invalid(format!("type `{}` is not Borsh serialized", name)));
+            }
+            match &def.ty {
+                IdlTypeDefTy::Struct { fields } => {
+                    encode_fields(idl, fields.as_ref(), value, path, out)?;
+                }
+                IdlTypeDefTy::Enum { variants } => {
+                    let (variant, fields) = match value {
+                        Value::String(variant) => (variant.as_str(), &Value::Null),
+                        Value::Object(map) if map.len() == 1 => {
+                            let (variant, fields) = map.iter().next().unwrap();
+                            (variant.as_str(), fields)
+                        }
+                        _ => return Err(invalid(format!("expected an enum variant, got {}", value))),
+                    };
+                    let index = variants
+                        .iter()
+                        .position(|v| v.name == variant)
+                        .ok_or_else(|| invalid(format!("unknown variant `{}` of `{}`", variant, name)))?;
+                    out.push(index as u8);
+                    encode_fields(idl, variants[index].fields.as_ref(), fields, path, out)?;
+                }
+                IdlTypeDefTy::Type { alias } => encode_value(idl, alias, value, path, out)?,
+            }
+        }
+        other => return Err(invalid(format!("unsupported IDL type {:?}", other))),
+    }
+    Ok(())
+}
+
+/// Borsh-encode the fields of a struct or enum variant
+fn encode_fields(
+    idl: &Idl,
+    fields: Option<&IdlDefinedFields>,
+    value: &Value,
+    path: &str,
+    out: &mut Vec<u8>,
+) -> Result<(), IdlError> {
+    match fields {
+        None => Ok(()),
+        Some(IdlDefinedFields::Named(fields)) => {
+            for field in fields {
+                let field_path = format!("{}.{}", path, field.name);
+                let field_value = value
+                    .get(&field.name)
+                    .ok_or_else(|| IdlError::MissingArg(field_path.clone()))?;
+                encode_value(idl, &field.ty, field_value, &field_path, out)?;
+            }
+            Ok(())
+        }
+        Some(IdlDefinedFields::Tuple(types)) => {
+            let items = value
+                .as_array()
+                .filter(|items| items.len() == types.len())
+                .ok_or_else(|| IdlError::InvalidArg {
+                    name: path.to_string(),
+                    reason: format!("expected an array of {} items, got {}", types.len(), value),
+                })?;
+            for (i, (ty, item)) in types.iter().zip(items).enumerate() {
+                encode_value(idl, ty, item, &format!("{}.{}", path, i), out)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Flatten nested account groups into the order the program expects them
+fn flatten_accounts<'a>(
+    items: &'a [IdlInstructionAccountItem],
+    out: &mut Vec<&'a IdlInstructionAccount>,
+) {
+    for item in items {
+        match item {
+            IdlInstructionAccountItem::Single(account) => out.push(account),
+            IdlInstructionAccountItem::Composite(group) => flatten_accounts(&group.accounts, out),
+        }
+    }
+}
+
+/// Resolve the account metas of an IDL instruction
+///
+/// Provided accounts are used as-is. Accounts with a fixed `address` in the IDL use
+/// that address, and accounts with `pda` seeds are derived from constants, other
+/// accounts and instruction arguments. Optional accounts that can't be resolved are
+/// passed as the program id, which Anchor treats as `None`.
+///
+/// # Example
+/// ```ignore
+/// let idl = load_idl("target/idl/my_program.json")?;
+/// let metas = resolve_accounts(
+///     &idl,
+///     &program_id,
+///     "initialize",
+///     &IdlAccountInputs::new().account("user", user.pubkey()),
+/// )?;
+/// ```
+pub fn resolve_accounts(
+    idl: &Idl,
+    program_id: &Pubkey,
+    instruction: &str,
+    inputs: &IdlAccountInputs,
+) -> Result<Vec<AccountMeta>, IdlError> {
+    let ix = find_instruction(idl, instruction)?;
+    let mut accounts = Vec::new();
+    flatten_accounts(&ix.accounts, &mut accounts);
+
+    let mut resolver = Resolver {
+        ix,
+        accounts: &accounts,
+        program_id,
+        inputs,
+        resolved: inputs.accounts.clone(),
+    };
+
+    accounts
+        .iter()
+        .map(|account| {
+            let pubkey = match resolver.resolve(&account.name, 0) {
+                Ok(pubkey) => pubkey,
+                Err(IdlError::MissingAccount(_)) if account.optional => {
+                    return Ok(AccountMeta::new_readonly(*program_id, false));
+                }
+                Err(e) => return Err(e),
+            };
+            Ok(if account.writable {
+                AccountMeta::new(pubkey, account.signer)
+            } else {
+                AccountMeta::new_readonly(pubkey, account.signer)
+            })
+        })
+        .collect()
+}
+
+/// Maximum depth of PDAs whose seeds depend on other PDAs
+const MAX_RESOLVE_DEPTH: usize = 16;
+
+struct Resolver<'a> {
+    ix: &'a IdlInstruction,
+    accounts: &'a [&'a IdlInstructionAccount],
+    program_id: &'a Pubkey,
+    inputs: &'a IdlAccountInputs,
+    resolved: HashMap<String, Pubkey>,
+}
+
+impl Resolver<'_> {
+    fn resolve(&mut self, name: &str, depth: usize) -> Result<Pubkey, IdlError> {
+        if let Some(pubkey) = self.resolved.get(name) {
+            return Ok(*pubkey);
+        }
+
+        let account = *self
+            .accounts
+            .iter()
+            .find(|a| a.name == name)
+            .ok_or_else(|| IdlError::MissingAccount(name.to_string()))?;
+        if depth > MAX_RESOLVE_DEPTH {
+            return Err(IdlError::UnsupportedSeed {
+                account: name.to_string(),
+                reason: "PDA seeds are nested too deeply".to_string(),
+            });
+        }
+
+        let pubkey = if let Some(address) = &account.address {
+            Pubkey::from_str(address).map_err(|_| IdlError::InvalidAddress(address.clone()))?
+        } else if let Some(pda) = &account.pda {
+            let seeds = pda
+                .seeds
+                .iter()
+                .map(|seed| self.seed_bytes(name, seed, depth))
+                .collect::<Result<Vec<_>, _>>()?;
+            let program_id = match &pda.program {
+                Some(seed) => {
+                    let bytes = self.seed_bytes(name, seed, depth)?;
+                    Pubkey::try_from(bytes.as_slice()).map_err(|_| IdlError::UnsupportedSeed {
+                        account: name.to_string(),
+                        reason: "PDA program seed is not a 32-byte address".to_string(),
+                    })?
+                }
+                None => *self.program_id,
+            };
+            let seed_refs: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+            Pubkey::find_program_address(&seed_refs, &program_id).0
+        } else {
+            return Err(IdlError::MissingAccount(name.to_string()));
+        };
+
+        self.resolved.insert(name.to_string(), pubkey);
+        Ok(pubkey)
+    }
+
+    fn seed_bytes(&mut self, account: &str, seed: &IdlSeed, depth: usize) -> Result<Vec<u8>, IdlError> {
+        match seed {
+            IdlSeed::Const(seed) => Ok(seed.value.clone()),
+            IdlSeed::Account(seed) => {
+                if seed.path.contains('.') {
+                    return Err(IdlError::UnsupportedSeed {
+                        account: account.to_string(),
+                        reason: format!("seed from account data field `{}`", seed.path),
+                    });
+                }
+                Ok(self.resolve(&seed.path, depth + 1)?.to_bytes().to_vec())
+            }
+            IdlSeed::Arg(seed) => {
+                if seed.path.contains('.') {
+                    return Err(IdlError::UnsupportedSeed {
+                        account: account.to_string(),
+                        reason: format!("seed from argument field `{}`", seed.path),
+                    });
+                }
+                let bytes = self
+                    .inputs
+                    .args
+                    .get(&seed.path)
+                    .ok_or_else(|| IdlError::MissingArg(seed.path.clone()))?;
+
+                // String and byte seeds use the raw bytes, without Borsh's length prefix
+                let ty = self.ix.args.iter().find(|a| a.name == seed.path).map(|a| &a.ty);
+                match ty {
+                    Some(IdlType::String) | Some(IdlType::Bytes) => {
+                        Ok(bytes.get(4..).unwrap_or_default().to_vec())
+                    }
+                    _ => Ok(bytes.clone()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+
+    fn test_idl() -> Idl {
+        serde_json::from_value(json!({
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "test_program", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [{
+                "name": "initialize",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [
+                    { "name": "user", "writable": true, "signer": true },
+                    {
+                        "name": "vault",
+                        "writable": true,
+                        "pda": { "seeds": [
+                            { "kind": "const", "value": [118, 97, 117, 108, 116] },
+                            { "kind": "account", "path": "user" }
+                        ]}
+                    },
+                    {
+                        "name": "config",
+                        "pda": { "seeds": [
+                            { "kind": "arg", "path": "name" },
+                            { "kind": "arg", "path": "id" },
+                            { "kind": "account", "path": "vault" }
+                        ]}
+                    },
+                    { "name": "referrer", "optional": true },
+                    { "name": "system_program", "address": SYSTEM_PROGRAM }
+                ],
+                "args": [
+                    { "name": "name", "type": "string" },
+                    { "name": "id", "type": "u64" }
+                ]
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_accounts_derives_pdas() {
+        let idl = test_idl();
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+
+        let metas = resolve_accounts(
+            &idl,
+            &program_id,
+            "initialize",
+            &IdlAccountInputs::new()
+                .account("user", user)
+                .arg("name", "alice".to_string())
+                .arg("id", 7u64),
+        )
+        .unwrap();
+
+        let (vault, _) = Pubkey::find_program_address(&[b"vault", user.as_ref()], &program_id);
+        let (config, _) = Pubkey::find_program_address(
+            &[b"alice", &7u64.to_le_bytes(), vault.as_ref()],
+            &program_id,
+        );
+
+        assert_eq!(metas.len(), 5);
+        assert_eq!(metas[0], AccountMeta::new(user, true));
+        assert_eq!(metas[1], AccountMeta::new(vault, false));
+        assert_eq!(metas[2], AccountMeta::new_readonly(config, false));
+        assert_eq!(metas[3], AccountMeta::new_readonly(program_id, false));
+        assert_eq!(metas[4].pubkey, Pubkey::from_str(SYSTEM_PROGRAM).unwrap());
+    }
+
+    #[test]
+    fn test_resolve_accounts_provided_pda_overrides_seeds() {
+        let idl = test_idl();
+        let vault = Pubkey::new_unique();
+
+        let metas = resolve_accounts(
+            &idl,
+            &Pubkey::new_unique(),
+            "initialize",
+            &IdlAccountInputs::new()
+                .account("user", Pubkey::new_unique())
+                .account("vault", vault)
+                .arg("name", "bob".to_string())
+                .arg("id", 1u64),
+        )
+        .unwrap();
+        assert_eq!(metas[1].pubkey, vault);
+    }
+
+    #[test]
+    fn test_resolve_accounts_missing_inputs() {
+        let idl = test_idl();
+        let program_id = Pubkey::new_unique();
+
+        let err = resolve_accounts(&idl, &program_id, "initialize", &IdlAccountInputs::new())
+            .unwrap_err();
+        assert!(matches!(err, IdlError::MissingAccount(name) if name == "user"));
+
+        let err = resolve_accounts(
+            &idl,
+            &program_id,
+            "initialize",
+            &IdlAccountInputs::new().account("user", Pubkey::new_unique()),
+        )
+        .unwrap_err();
+        assert!(matches!(err, IdlError::MissingArg(name) if name == "name"));
+
+        let err = resolve_accounts(&idl, &program_id, "close", &IdlAccountInputs::new()).unwrap_err();
+        assert!(matches!(err, IdlError::InstructionNotFound(_)));
+    }
+
+    #[test]
+    fn test_instruction_name_for() {
+        let idl = test_idl();
+        assert_eq!(instruction_name_for(&idl, &[1, 2, 3, 4, 5, 6, 7, 8, 42]), Some("initialize"));
+        assert_eq!(instruction_name_for(&idl, &[1, 2, 3]), None);
+        assert_eq!(instruction_name_for(&idl, &[]), None);
+    }
+
+    fn dynamic_idl() -> Idl {
+        serde_json::from_value(json!({
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "escrow", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [{
+                "name": "make",
+                "discriminator": [9, 9, 9, 9, 9, 9, 9, 9],
+                "accounts": [
+                    { "name": "maker", "writable": true, "signer": true },
+                    { "name": "escrow", "writable": true, "pda": { "seeds": [
+                        { "kind": "account", "path": "maker" },
+                        { "kind": "arg", "path": "seed" }
+                    ]}}
+                ],
+                "args": [
+                    { "name": "seed", "type": "u64" },
+                    { "name": "terms", "type": { "defined": { "name": "Terms" } } },
+                    { "name": "side", "type": { "defined": { "name": "Side" } } },
+                    { "name": "memo", "type": { "option": "string" } }
+                ]
+            }],
+            "types": [
+                { "name": "Terms", "type": { "kind": "struct", "fields": [
+                    { "name": "amount", "type": "u128" },
+                    { "name": "taker", "type": "pubkey" },
+                    { "name": "ratios", "type": { "array": ["u16", 2] } }
+                ]}},
+                { "name": "Side", "type": { "kind": "enum", "variants": [
+                    { "name": "Bid" },
+                    { "name": "Ask", "fields": ["u8"] }
+                ]}}
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_instruction_accounts() {
+        let idl = test_idl();
+        let program_id = Pubkey::from_str(SYSTEM_PROGRAM).unwrap();
+        let user = Pubkey::new_unique();
+        let accounts = vec![
+            AccountMeta::new(user, true),
+            AccountMeta::new(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(Pubkey::new_unique(), false),
+            AccountMeta::new_readonly(program_id, false),
+            AccountMeta::new_readonly(program_id, false),
+        ];
+        let ix = |accounts: Vec<AccountMeta>| Instruction {
+            program_id,
+            accounts,
+            data: vec![1, 2, 3, 4, 5, 6, 7, 8],
+        };
+        let error = |accounts: Vec<AccountMeta>| {
+            validate_instruction_accounts(&idl, &ix(accounts)).unwrap_err().to_string()
+        };
+
+        validate_instruction_accounts(&idl, &ix(accounts.clone())).unwrap();
+
+        // Remaining accounts are allowed
+        let mut extra = accounts.clone();
+        extra.push(AccountMeta::new(Pubkey::new_unique(), false));
+        validate_instruction_accounts(&idl, &ix(extra)).unwrap();
+
+        let mut readonly = accounts.clone();
+        readonly[1].is_writable = false;
+        assert_eq!(error(readonly), "Instruction `initialize`: account #1 `vault` should be writable");
+
+        let mut unsigned = accounts.clone();
+        unsigned[0].is_signer = false;
+        assert_eq!(error(unsigned), "Instruction `initialize`: account #0 `user` should be a signer");
+
+        let mut wrong_address = accounts.clone();
+        wrong_address[4].pubkey = user;
+        assert_eq!(
+            error(wrong_address),
+            format!("Instruction `initialize`: account #4 `system_program` should be {}, got {}", SYSTEM_PROGRAM, user)
+        );
+
+        assert_eq!(
+            error(accounts[..3].to_vec()),
+            "Instruction `initialize`: account #3 `referrer` is missing (expected 5 accounts, got 3)"
+        );
+
+        // Unknown instructions aren't checked
+        let mut unknown = ix(Vec::new());
+        unknown.data = vec![0; 8];
+        validate_instruction_accounts(&idl, &unknown).unwrap();
+    }
+
+    #[test]
+    fn test_idl_instruction_builder() {
+        let idl = dynamic_idl();
+        let program = IdlProgram::new(&idl).unwrap();
+        let maker = Pubkey::new_unique();
+        let taker = Pubkey::new_unique();
+
+        let ix = program
+            .instruction("make")
+            .accounts_json(json!({ "maker": maker.to_string() }))
+            .args_json(json!({
+                "seed": 7,
+                "terms": { "amount": "340282366920938463463374607431768211455", "taker": taker.to_string(), "ratios": [1, 2] },
+                "side": { "Ask": [3] },
+                "memo": "hi"
+            }))
+            .instruction()
+            .unwrap();
+
+        let mut expected = vec![9; 8];
+        expected.extend_from_slice(&7u64.to_le_bytes());
+        expected.extend_from_slice(&u128::MAX.to_le_bytes());
+        expected.extend_from_slice(taker.as_ref());
+        expected.extend_from_slice(&[1, 0, 2, 0]);
+        expected.extend_from_slice(&[1, 3]);
+        expected.extend_from_slice(&[1, 2, 0, 0, 0, b'h', b'i']);
+        assert_eq!(ix.data, expected);
+
+        let (escrow, _) = Pubkey::find_program_address(&[maker.as_ref(), &7u64.to_le_bytes()], &program.id());
+        assert_eq!(ix.accounts, vec![AccountMeta::new(maker, true), AccountMeta::new(escrow, false)]);
+    }
+
+    #[test]
+    fn test_decode_instruction_roundtrip() {
+        let idl = dynamic_idl();
+        let program = IdlProgram::new(&idl).unwrap();
+        let args = json!({
+            "seed": 7,
+            "terms": { "amount": "340282366920938463463374607431768211455", "taker": Pubkey::new_unique().to_string(), "ratios": [1, 2] },
+            "side": { "Ask": [3] },
+            "memo": "hi"
+        });
+
+        let ix = program
+            .instruction("make")
+            .accounts_json(json!({ "maker": Pubkey::new_unique().to_string() }))
+            .args_json(args.clone())
+            .instruction()
+            .unwrap();
+        let (name, decoded) = program.decode_instruction(&ix.data).unwrap();
+        assert_eq!(name, "make");
+        assert_eq!(decoded, args);
+
+        let ix = program
+            .instruction("make")
+            .accounts_json(json!({ "maker": Pubkey::new_unique().to_string() }))
+            .args_json(json!({ "seed": 1, "terms": args["terms"], "side": "Bid", "memo": null }))
+            .instruction()
+            .unwrap();
+        let (_, decoded) = decode_instruction(&idl, &ix.data).unwrap();
+        assert_eq!(decoded["side"], "Bid");
+        assert!(decoded["memo"].is_null());
+    }
+
+    #[test]
+    fn test_decode_instruction_errors() {
+        let idl = dynamic_idl();
+
+        let err = decode_instruction(&idl, &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(matches!(err, Err(IdlError::InstructionNotFound(_))));
+
+        let mut data = vec![9; 8];
+        data.extend_from_slice(&7u64.to_le_bytes());
+        let err = decode_instruction(&idl, &data);
+        assert!(matches!(err, Err(IdlError::InvalidData { name, .. }) if name == "make.terms"));
+
+        let mut data = test_idl().instructions[0].discriminator.clone();
+        data.extend_from_slice(&[1, 0, 0, 0, b'a']);
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.push(0);
+        let err = decode_instruction(&test_idl(), &data);
+        assert!(matches!(err, Err(IdlError::InvalidData { name, .. }) if name == "initialize"));
+    }
+
+    #[test]
+    fn test_idl_instruction_builder_errors() {
+        let idl = dynamic_idl();
+        let program = IdlProgram::new(&idl).unwrap();
+        let maker = json!({ "maker": Pubkey::new_unique().to_string() });
+        let terms = json!({ "amount": 1, "taker": Pubkey::new_unique().to_string(), "ratios": [1, 2] });
+
+        let err = program.instruction("make").accounts_json(maker.clone()).args_json(json!({ "seed": 1 })).instruction();
+        assert!(matches!(err, Err(IdlError::MissingArg(name)) if name == "terms"));
+
+        let err = program
+            .instruction("make")
+            .accounts_json(maker.clone())
+            .args_json(json!({ "seed": -1, "terms": terms, "side": "Bid", "memo": null }))
+            .instruction();
+        assert!(matches!(err, Err(IdlError::InvalidArg { name, .. }) if name == "seed"));
+
+        let err = program
+            .instruction("make")
+            .accounts_json(maker)
+            .args_json(json!({ "seed": 1, "terms": terms, "side": "Hold", "memo": null }))
+            .instruction();
+        assert!(matches!(err, Err(IdlError::InvalidArg { name, .. }) if name == "side"));
+
+        let err = program.instruction("make").accounts_json(json!({ "maker": "nope" })).instruction();
+        assert!(matches!(err, Err(IdlError::InvalidAccount { .. })));
+    }
+
+    #[test]
+    fn test_parse_idl_invalid() {
+        assert!(matches!(parse_idl("not json"), Err(IdlError::ParseError(_))));
+    }
+}
@@ -32,13 +32,58 @@ where
     })
 }
 
+/// Builds an instruction with an arbitrary data prefix instead of an Anchor discriminator
+///
+/// This is useful for non-Anchor programs that dispatch on a leading byte or enum tag,
+/// such as native programs or CPI targets built with other frameworks. The prefix is
+/// written as-is, followed by the Borsh-serialized arguments.
+pub fn build_raw_instruction<T>(
+    program_id: &Pubkey,
+    prefix: &[u8],
+    accounts: Vec<AccountMeta>,
+    args: T,
+) -> Result<Instruction, Box<dyn std::error::Error>>
+where
+    T: AnchorSerialize,
+{
+    let mut data = prefix.to_vec();
+    args.serialize(&mut data)?;
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data,
+    })
+}
+
 /// Calculate the Anchor instruction discriminator
 ///
 /// Anchor uses the first 8 bytes of sha256("global:<instruction_name>")
 /// as the instruction discriminator
 pub fn calculate_anchor_discriminator(instruction_name: &str) -> [u8; 8] {
+    namespaced_discriminator("global", instruction_name)
+}
+
+/// Calculate the Anchor account discriminator
+///
+/// Anchor uses the first 8 bytes of sha256("account:<AccountName>")
+/// as the account discriminator. The name is the account struct's name, e.g. `Escrow`.
+pub fn calculate_account_discriminator(account_name: &str) -> [u8; 8] {
+    namespaced_discriminator("account", account_name)
+}
+
+/// Calculate the Anchor event discriminator
+///
+/// Anchor uses the first 8 bytes of sha256("event:<EventName>")
+/// as the event discriminator. The name is the event struct's name, e.g. `TransferEvent`.
+pub fn calculate_event_discriminator(event_name: &str) -> [u8; 8] {
+    namespaced_discriminator("event", event_name)
+}
+
+/// First 8 bytes of sha256("<namespace>:<name>")
+fn namespaced_discriminator(namespace: &str, name: &str) -> [u8; 8] {
     let mut hasher = Sha256::new();
-    hasher.update(format!("global:{}", instruction_name));
+    hasher.update(format!("{}:{}", namespace, name));
     let hash = hasher.finalize();
 
     let mut discriminator = [0u8; 8];
@@ -46,6 +91,73 @@ pub fn calculate_anchor_discriminator(instruction_name: &str) -> [u8; 8] {
     discriminator
 }
 
+/// Discriminator used by [`ping_instruction`] that no Anchor instruction should match
+const PING_DISCRIMINATOR: [u8; 8] = [0xff; 8];
+
+/// Build a no-op "ping" instruction for a program
+///
+/// The instruction has no accounts and a bogus discriminator, so a deployed
+/// program will reject it during dispatch. This is useful for checking that a
+/// program is deployed and reachable without depending on its instruction set.
+pub fn ping_instruction(program_id: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![],
+        data: PING_DISCRIMINATOR.to_vec(),
+    }
+}
+
+/// Assert that an instruction has the expected number of accounts
+///
+/// # Panics
+///
+/// Panics if the number of account metas differs from `expected`
+pub fn assert_account_count(instruction: &Instruction, expected: usize) {
+    assert_eq!(
+        instruction.accounts.len(),
+        expected,
+        "Instruction account count mismatch for program {}. Expected: {}, Actual: {}",
+        instruction.program_id,
+        expected,
+        instruction.accounts.len()
+    );
+}
+
+/// Assert that an instruction's data exactly equals the expected bytes
+///
+/// Useful for golden tests that lock in the serialized form an instruction builder
+/// produces, catching unintended discriminator or argument encoding changes.
+///
+/// # Panics
+///
+/// Panics with the first differing offset if the data doesn't match
+pub fn assert_instruction_data(instruction: &Instruction, expected: &[u8]) {
+    if instruction.data == expected {
+        return;
+    }
+
+    match instruction.data.iter().zip(expected).position(|(a, e)| a != e) {
+        Some(offset) => panic!(
+            "Instruction data mismatch for program {} at offset {}. Expected: {:#04x}, Actual: {:#04x}\nExpected data: {:?}\nActual data: {:?}",
+            instruction.program_id,
+            offset,
+            expected[offset],
+            instruction.data[offset],
+            expected,
+            instruction.data
+        ),
+        None => panic!(
+            "Instruction data length mismatch for program {} at offset {}. Expected: {} bytes, Actual: {} bytes\nExpected data: {:?}\nActual data: {:?}",
+            instruction.program_id,
+            instruction.data.len().min(expected.len()),
+            expected.len(),
+            instruction.data.len(),
+            expected,
+            instruction.data
+        ),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -66,6 +178,23 @@ mod tests {
         assert_ne!(make_discriminator, test_discriminator);
     }
 
+    #[test]
+    fn test_account_and_event_discriminators() {
+        use anchor_lang::prelude::*;
+
+        #[event]
+        struct TransferEvent {
+            amount: u64,
+        }
+
+        // First 8 bytes of SHA256("account:Escrow")
+        let expected_escrow = [0x1f, 0xd5, 0x7b, 0xbb, 0xba, 0x16, 0xda, 0x9b];
+        assert_eq!(calculate_account_discriminator("Escrow"), expected_escrow);
+        assert_eq!(calculate_event_discriminator("TransferEvent"), TransferEvent::DISCRIMINATOR);
+        assert_ne!(calculate_account_discriminator("Escrow"), calculate_event_discriminator("Escrow"));
+        assert_ne!(calculate_account_discriminator("make"), calculate_anchor_discriminator("make"));
+    }
+
     #[test]
     fn test_instruction_building() {
         // In anchor 1.0.0-rc.2, AnchorSerialize is an alias for BorshSerialize
@@ -92,4 +221,72 @@ mod tests {
         assert_eq!(instruction.accounts.len(), 2);
         assert!(instruction.data.len() >= 8); // At least discriminator
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_build_raw_instruction() {
+        #[derive(BorshSerialize)]
+        struct TestArgs {
+            value: u64,
+        }
+
+        let program_id = Pubkey::new_unique();
+        let instruction = build_raw_instruction(
+            &program_id,
+            &[3],
+            vec![AccountMeta::new(Pubkey::new_unique(), true)],
+            TestArgs { value: 42 },
+        ).unwrap();
+
+        assert_eq!(instruction.program_id, program_id);
+        assert_eq!(instruction.accounts.len(), 1);
+        assert_eq!(instruction.data[0], 3);
+        assert_eq!(instruction.data[1..], 42u64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_assert_instruction_data() {
+        let ix = build_raw_instruction(&Pubkey::new_unique(), &[1, 2], vec![], 7u8).unwrap();
+        assert_instruction_data(&ix, &[1, 2, 7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "at offset 1")]
+    fn test_assert_instruction_data_reports_offset() {
+        let ix = build_raw_instruction(&Pubkey::new_unique(), &[1, 2], vec![], 7u8).unwrap();
+        assert_instruction_data(&ix, &[1, 3, 7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "length mismatch for program")]
+    fn test_assert_instruction_data_length_mismatch() {
+        let ix = build_raw_instruction(&Pubkey::new_unique(), &[1, 2], vec![], 7u8).unwrap();
+        assert_instruction_data(&ix, &[1, 2]);
+    }
+
+    #[test]
+    fn test_ping_instruction() {
+        let program_id = Pubkey::new_unique();
+        let ix = ping_instruction(&program_id);
+
+        assert_eq!(ix.program_id, program_id);
+        assert!(ix.accounts.is_empty());
+        assert_eq!(ix.data, PING_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn test_assert_account_count() {
+        let ix = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta::new(Pubkey::new_unique(), true)],
+            data: vec![],
+        };
+        assert_account_count(&ix, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Instruction account count mismatch")]
+    fn test_assert_account_count_fails() {
+        let ix = ping_instruction(&Pubkey::new_unique());
+        assert_account_count(&ix, 2);
+    }
+}
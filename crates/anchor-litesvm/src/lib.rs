@@ -121,29 +121,51 @@
 //! - [`account`] - Account deserialization utilities
 //! - [`builder`] - Test environment builders
 //! - [`context`] - Main test context (`AnchorContext`)
+//! - [`errors`] - Typed Anchor error assertions
 //! - [`events`] - Event parsing helpers
+//! - [`idl`] - Anchor IDL loading and account resolution
 //! - [`instruction`] - Instruction building utilities
 //! - [`program`] - Simplified Program API
+//! - [`return_data`] - Typed instruction return data
+//! - `rpc_clone` - Cloning accounts and programs from a live cluster (`rpc-clone` feature)
 
 pub mod account;
 pub mod builder;
 pub mod context;
+pub mod errors;
 pub mod events;
+pub mod idl;
 pub mod instruction;
 pub mod program;
+pub mod return_data;
+#[cfg(feature = "rpc-clone")]
+pub mod rpc_clone;
 
 // Re-export main types for convenience
-pub use account::{get_anchor_account, get_anchor_account_unchecked, AccountError};
+pub use account::{
+    get_anchor_account, get_anchor_account_full, get_anchor_account_unchecked, get_anchor_accounts,
+    get_program_accounts, set_anchor_account, AccountError, AccountFilter, TypedAccount,
+};
 pub use builder::{AnchorLiteSVM, ProgramTestExt};
-pub use context::AnchorContext;
-pub use events::{parse_event_data, EventError, EventHelpers};
-pub use instruction::{build_anchor_instruction, calculate_anchor_discriminator};
-pub use program::{InstructionBuilder, Program};
+pub use context::{AnchorContext, Snapshot, TransactionRecord};
+pub use errors::{AnchorErrorDetails, AnchorErrorHelpers};
+pub use events::{parse_event_data, EventError, EventHelpers, EventSequence};
+pub use idl::{
+    decode_instruction, instruction_name_for, load_idl, parse_idl, resolve_accounts,
+    validate_instruction_accounts, Idl, IdlAccountInputs, IdlError, IdlInstructionBuilder, IdlProgram,
+};
+pub use instruction::{
+    assert_account_count, assert_instruction_data, build_anchor_instruction,
+    build_raw_instruction, calculate_account_discriminator, calculate_anchor_discriminator,
+    calculate_event_discriminator, ping_instruction,
+};
+pub use program::{InstructionBuilder, Program, RequestBuilder};
+pub use return_data::{ReturnDataError, ReturnDataHelpers};
 
 // Re-export litesvm-utils functionality for convenience
 pub use litesvm_utils::{
-    AssertionHelpers, LiteSVMBuilder, TestHelpers, TransactionError, TransactionHelpers,
-    TransactionResult,
+    AccountBuilder, AccountChange, AssertionHelpers, BalanceTracker, CpiInstruction, LiteSVMBuilder,
+    TestHelpers, TransactionError, TransactionHelpers, TransactionResult,
 };
 
 // Re-export commonly used external types
@@ -2,12 +2,20 @@
 //!
 //! This module provides a clean, testing-focused API that removes unnecessary
 //! RPC-layer abstractions like `.request()` and `.remove(0)`.
+//!
+//! For migrating existing anchor-client tests, [`Program::request`] provides a
+//! [`RequestBuilder`] with the same method names as anchor-client's.
 
+use crate::idl::{resolve_accounts, Idl, IdlAccountInputs};
+use crate::AnchorContext;
 use anchor_lang::{InstructionData, ToAccountMetas};
+use litesvm_utils::TransactionResult;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
 use solana_program::{
-    instruction::Instruction,
+    instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
 };
+use solana_sdk::signature::{Keypair, Signer};
 
 /// A lightweight Program wrapper for building instructions in tests.
 ///
@@ -49,6 +57,64 @@ impl Program {
             program_id: self.program_id,
             accounts: accounts.to_account_metas(None),
             data: Vec::new(),
+            signers: Vec::new(),
+            pre_instructions: Vec::new(),
+            post_instructions: Vec::new(),
+            compute_limit: None,
+            compute_unit_price: None,
+        }
+    }
+
+    /// Start building an instruction with accounts resolved from an Anchor IDL
+    ///
+    /// Accounts are ordered and flagged as declared in the IDL. PDAs are derived
+    /// from their seed definitions and fixed addresses are filled in, so only the
+    /// accounts and arguments the seeds depend on need to be provided.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let idl = load_idl("target/idl/my_program.json")?;
+    /// let ix = ctx.program()
+    ///     .accounts_from_idl(
+    ///         &idl,
+    ///         "initialize",
+    ///         IdlAccountInputs::new().account("user", user.pubkey()),
+    ///     )?
+    ///     .args(my_program::instruction::Initialize { value: 42 })
+    ///     .instruction()?;
+    /// ```
+    pub fn accounts_from_idl(
+        self,
+        idl: &Idl,
+        instruction: &str,
+        inputs: IdlAccountInputs,
+    ) -> Result<InstructionBuilder, Box<dyn std::error::Error>> {
+        let accounts = resolve_accounts(idl, &self.program_id, instruction, &inputs)?;
+        Ok(self.accounts(accounts))
+    }
+
+    /// Start an anchor-client style request
+    ///
+    /// The returned [`RequestBuilder`] uses the same method names as anchor-client's
+    /// `RequestBuilder`, so existing test bodies can be reused. The only difference
+    /// is that `.send()` takes the context to execute against.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = ctx.program()
+    ///     .request()
+    ///     .accounts(my_program::accounts::Initialize { ... })
+    ///     .args(my_program::instruction::Initialize { value: 42 })
+    ///     .signer(&user)
+    ///     .send(&mut ctx)?;
+    /// ```
+    pub fn request(self) -> RequestBuilder {
+        RequestBuilder {
+            program_id: self.program_id,
+            accounts: Vec::new(),
+            data: None,
+            instructions: Vec::new(),
+            signers: Vec::new(),
         }
     }
 
@@ -63,8 +129,13 @@ impl Program {
 /// You typically don't create this directly - use `program().accounts()` instead.
 pub struct InstructionBuilder {
     program_id: Pubkey,
-    accounts: Vec<solana_program::instruction::AccountMeta>,
+    accounts: Vec<AccountMeta>,
     data: Vec<u8>,
+    signers: Vec<Keypair>,
+    pre_instructions: Vec<Instruction>,
+    post_instructions: Vec<Instruction>,
+    compute_limit: Option<u32>,
+    compute_unit_price: Option<u64>,
 }
 
 impl InstructionBuilder {
@@ -79,6 +150,179 @@ impl InstructionBuilder {
         self
     }
 
+    /// Append remaining accounts after the typed accounts struct
+    ///
+    /// These show up in the program as `ctx.remaining_accounts`, in the order given.
+    /// Calling this more than once appends further accounts.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .remaining_accounts(vec![
+    ///     AccountMeta::new(extra_vault, false),
+    ///     AccountMeta::new_readonly(oracle, false),
+    /// ])
+    /// ```
+    pub fn remaining_accounts(mut self, accounts: Vec<AccountMeta>) -> Self {
+        self.accounts.extend(accounts);
+        self
+    }
+
+    /// Add an instruction to run before this one in the same transaction
+    ///
+    /// Useful for bundling setup such as ATA creation. Pre-instructions run in the
+    /// order they are added.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .pre_instruction(create_associated_token_account(&user, &user, &mint, &spl_token::id()))
+    /// ```
+    pub fn pre_instruction(mut self, instruction: Instruction) -> Self {
+        self.pre_instructions.push(instruction);
+        self
+    }
+
+    /// Add an instruction to run after this one in the same transaction
+    ///
+    /// Post-instructions run in the order they are added.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .post_instruction(memo_ix)
+    /// ```
+    pub fn post_instruction(mut self, instruction: Instruction) -> Self {
+        self.post_instructions.push(instruction);
+        self
+    }
+
+    /// Set the compute unit limit for the transaction
+    ///
+    /// A `SetComputeUnitLimit` compute budget instruction is prepended to the
+    /// instructions produced by `.instructions()` and `.send()`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .with_compute_limit(1_400_000)
+    /// ```
+    pub fn with_compute_limit(mut self, units: u32) -> Self {
+        self.compute_limit = Some(units);
+        self
+    }
+
+    /// Set the compute unit price (priority fee) for the transaction
+    ///
+    /// A `SetComputeUnitPrice` compute budget instruction is prepended to the
+    /// instructions produced by `.instructions()` and `.send()`, after the compute
+    /// unit limit if one is set. This lets programs that inspect the compute budget
+    /// instructions be exercised. Note that LiteSVM doesn't charge prioritization
+    /// fees, so the fee payer is only debited the signature fee.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .with_compute_limit(200_000)
+    /// .with_compute_unit_price(10_000)
+    /// ```
+    pub fn with_compute_unit_price(mut self, micro_lamports: u64) -> Self {
+        self.compute_unit_price = Some(micro_lamports);
+        self
+    }
+
+    /// Set the signers used when sending the instruction with `.send()`
+    ///
+    /// The first signer pays the transaction fee. If no signers are set, the
+    /// context's payer signs and pays.
+    ///
+    /// # Example
+    /// ```ignore
+    /// .signers(&[&user])
+    /// ```
+    pub fn signers(mut self, signers: &[&Keypair]) -> Self {
+        self.signers = signers.iter().map(|s| s.insecure_clone()).collect();
+        self
+    }
+
+    /// Build the instructions and execute them through the given context
+    ///
+    /// This mirrors anchor-client's `.rpc()`, returning the transaction result directly.
+    /// Pre- and post-instructions are sent atomically in the same transaction.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = ctx.program()
+    ///     .accounts(...)
+    ///     .args(...)
+    ///     .signers(&[&user])
+    ///     .send(&mut ctx)?;
+    /// result.assert_success();
+    /// ```
+    pub fn send(
+        mut self,
+        ctx: &mut AnchorContext,
+    ) -> Result<TransactionResult, Box<dyn std::error::Error>> {
+        let mut signers = std::mem::take(&mut self.signers);
+        if signers.is_empty() {
+            signers.push(ctx.payer().insecure_clone());
+        }
+        let signer_refs: Vec<&Keypair> = signers.iter().collect();
+
+        let mut instructions = self.instructions()?;
+        if instructions.len() == 1 {
+            ctx.execute_instruction(instructions.remove(0), &signer_refs)
+        } else {
+            ctx.execute_instructions(instructions, &signer_refs)
+        }
+    }
+
+    /// Simulate the instruction through the given context without committing state
+    ///
+    /// Only the main instruction is simulated; pre- and post-instructions and compute
+    /// budget settings are ignored. Signers default to the context's payer, as with `.send()`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = ctx.program()
+    ///     .accounts(...)
+    ///     .args(my_program::instruction::GetPrice {})
+    ///     .simulate(&ctx)?;
+    /// result.assert_success();
+    /// ```
+    pub fn simulate(
+        mut self,
+        ctx: &AnchorContext,
+    ) -> Result<TransactionResult, Box<dyn std::error::Error>> {
+        let mut signers = std::mem::take(&mut self.signers);
+        if signers.is_empty() {
+            signers.push(ctx.payer().insecure_clone());
+        }
+        let signer_refs: Vec<&Keypair> = signers.iter().collect();
+        ctx.simulate_instruction(self.instruction()?, &signer_refs)
+    }
+
+    /// Build all instructions: compute budget, pre-instructions, this instruction, then post-instructions
+    ///
+    /// # Example
+    /// ```ignore
+    /// let ixs = ctx.program()
+    ///     .accounts(...)
+    ///     .args(...)
+    ///     .pre_instruction(create_ata_ix)
+    ///     .instructions()?;
+    /// ctx.execute_instructions(ixs, &[&user])?;
+    /// ```
+    pub fn instructions(mut self) -> Result<Vec<Instruction>, Box<dyn std::error::Error>> {
+        let mut instructions = Vec::new();
+        if let Some(units) = self.compute_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(units));
+        }
+        if let Some(micro_lamports) = self.compute_unit_price {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(micro_lamports));
+        }
+        instructions.append(&mut self.pre_instructions);
+        let post_instructions = std::mem::take(&mut self.post_instructions);
+        instructions.push(self.instruction()?);
+        instructions.extend(post_instructions);
+        Ok(instructions)
+    }
+
     /// Build and return the instruction.
     ///
     /// This is the final method in the chain that produces the `Instruction`.
@@ -103,12 +347,99 @@ impl InstructionBuilder {
     }
 }
 
+/// anchor-client compatible request builder, created with [`Program::request`]
+///
+/// The context's payer always pays for and signs the transaction; additional
+/// signers are added with `.signer()`.
+pub struct RequestBuilder {
+    program_id: Pubkey,
+    accounts: Vec<AccountMeta>,
+    data: Option<Vec<u8>>,
+    instructions: Vec<Instruction>,
+    signers: Vec<Keypair>,
+}
+
+impl RequestBuilder {
+    /// Append accounts for the program instruction
+    ///
+    /// Can be called multiple times; accounts are appended in order.
+    pub fn accounts<T: ToAccountMetas>(mut self, accounts: T) -> Self {
+        self.accounts.extend(accounts.to_account_metas(None));
+        self
+    }
+
+    /// Set the instruction arguments for the program instruction
+    pub fn args<T: InstructionData>(mut self, args: T) -> Self {
+        self.data = Some(args.data());
+        self
+    }
+
+    /// Add an instruction to run before the program instruction
+    pub fn instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Add a signer to the transaction
+    pub fn signer(mut self, signer: &Keypair) -> Self {
+        self.signers.push(signer.insecure_clone());
+        self
+    }
+
+    /// Build all instructions in the request
+    ///
+    /// Instructions added with `.instruction()` come first, followed by the program
+    /// instruction if `.args()` was called.
+    pub fn instructions(&self) -> Result<Vec<Instruction>, Box<dyn std::error::Error>> {
+        let mut instructions = self.instructions.clone();
+        if let Some(data) = &self.data {
+            instructions.push(Instruction {
+                program_id: self.program_id,
+                accounts: self.accounts.clone(),
+                data: data.clone(),
+            });
+        }
+        Ok(instructions)
+    }
+
+    /// Execute the request through the given context
+    ///
+    /// # Example
+    /// ```ignore
+    /// let result = ctx.program()
+    ///     .request()
+    ///     .accounts(...)
+    ///     .args(...)
+    ///     .send(&mut ctx)?;
+    /// result.assert_success();
+    /// ```
+    pub fn send(
+        self,
+        ctx: &mut AnchorContext,
+    ) -> Result<TransactionResult, Box<dyn std::error::Error>> {
+        let instructions = self.instructions()?;
+        if instructions.is_empty() {
+            return Err("No instructions in request. Call .args() or .instruction() before .send()".into());
+        }
+
+        let payer = ctx.payer().insecure_clone();
+        let mut signers: Vec<&Keypair> = vec![&payer];
+        signers.extend(self.signers.iter().filter(|s| s.pubkey() != payer.pubkey()));
+        ctx.execute_instructions(instructions, &signers)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Program;
+    use solana_compute_budget_interface::ComputeBudgetInstruction;
+    use crate::AnchorContext;
     use anchor_lang::{prelude::*, InstructionData, ToAccountMetas};
+    use litesvm::LiteSVM;
+    use litesvm_utils::{AssertionHelpers, TestHelpers};
     use solana_program::pubkey::Pubkey;
-    use solana_program::instruction::AccountMeta;
+    use solana_program::instruction::{AccountMeta, Instruction};
+    use solana_sdk::signature::{Keypair, Signer};
 
     struct TestAccounts {
         user: Pubkey,
@@ -142,6 +473,312 @@ mod tests {
         }
     }
 
+    struct MintToAccounts {
+        mint: Pubkey,
+        account: Pubkey,
+        authority: Pubkey,
+    }
+
+    impl ToAccountMetas for MintToAccounts {
+        fn to_account_metas(&self, _is_signer: Option<bool>) -> Vec<AccountMeta> {
+            vec![
+                AccountMeta::new(self.mint, false),
+                AccountMeta::new(self.account, false),
+                AccountMeta::new_readonly(self.authority, true),
+            ]
+        }
+    }
+
+    /// SPL Token `MintTo`, so the builder can be exercised against a deployed program
+    #[derive(AnchorSerialize)]
+    struct MintToArgs {
+        amount: u64,
+    }
+
+    impl anchor_lang::Discriminator for MintToArgs {
+        const DISCRIMINATOR: &'static [u8] = &[7];
+    }
+
+    impl InstructionData for MintToArgs {
+        fn data(&self) -> Vec<u8> {
+            let mut data = Self::DISCRIMINATOR.to_vec();
+            self.serialize(&mut data).unwrap();
+            data
+        }
+    }
+
+    /// Create a context for SPL Token with a mint and a token account owned by the payer
+    fn token_setup() -> (AnchorContext, Pubkey, Pubkey) {
+        let mut ctx = AnchorContext::new(LiteSVM::new(), spl_token::id());
+        let payer = ctx.payer().insecure_clone();
+        let mint = ctx.svm.create_token_mint(&payer, 9).unwrap();
+        let ata = ctx.svm.create_associated_token_account(&mint.pubkey(), &payer).unwrap();
+        (ctx, mint.pubkey(), ata)
+    }
+
+    #[test]
+    fn test_simulate_does_not_commit() {
+        let (ctx, mint, ata) = token_setup();
+        let authority = ctx.payer().pubkey();
+
+        let result = ctx.program()
+            .accounts(MintToAccounts { mint, account: ata, authority })
+            .args(MintToArgs { amount: 500 })
+            .simulate(&ctx)
+            .unwrap();
+        result.assert_success();
+        assert!(result.compute_units() > 0);
+        ctx.svm.assert_token_balance(&ata, 0);
+    }
+
+    #[test]
+    fn test_request_instructions() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let extra = Pubkey::new_unique();
+        let pre = Instruction::new_with_bytes(Pubkey::new_unique(), &[1], vec![]);
+
+        let ixs = Program::new(program_id)
+            .request()
+            .instruction(pre.clone())
+            .accounts(TestAccounts { user, account })
+            .accounts(vec![AccountMeta::new_readonly(extra, false)])
+            .args(TestArgs { amount: 5 })
+            .instructions()
+            .unwrap();
+
+        assert_eq!(ixs.len(), 2);
+        assert_eq!(ixs[0], pre);
+        assert_eq!(ixs[1].program_id, program_id);
+        assert_eq!(ixs[1].accounts.len(), 3);
+        assert_eq!(ixs[1].accounts[2].pubkey, extra);
+        assert_eq!(ixs[1].data, TestArgs { amount: 5 }.data());
+
+        assert!(Program::new(program_id).request().instructions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_request_send() {
+        let (mut ctx, mint, ata) = token_setup();
+        let authority = ctx.payer().insecure_clone();
+
+        ctx.program()
+            .request()
+            .accounts(MintToAccounts { mint, account: ata, authority: authority.pubkey() })
+            .args(MintToArgs { amount: 250 })
+            .signer(&authority)
+            .send(&mut ctx)
+            .unwrap()
+            .assert_success();
+        ctx.svm.assert_token_balance(&ata, 250);
+
+        assert!(ctx.program().request().send(&mut ctx).is_err());
+    }
+
+    #[test]
+    fn test_remaining_accounts() {
+        let user = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+        let extra = Pubkey::new_unique();
+
+        let ix = Program::new(Pubkey::new_unique())
+            .accounts(TestAccounts { user, account })
+            .remaining_accounts(vec![AccountMeta::new_readonly(extra, false)])
+            .args(TestArgs { amount: 1 })
+            .instruction()
+            .unwrap();
+
+        assert_eq!(ix.accounts.len(), 3);
+        assert_eq!(ix.accounts[0].pubkey, user);
+        assert_eq!(ix.accounts[2], AccountMeta::new_readonly(extra, false));
+    }
+
+    #[test]
+    fn test_pre_and_post_instructions() {
+        let program_id = Pubkey::new_unique();
+        let pre = Instruction::new_with_bytes(Pubkey::new_unique(), &[1], vec![]);
+        let post = Instruction::new_with_bytes(Pubkey::new_unique(), &[2], vec![]);
+
+        let ixs = Program::new(program_id)
+            .accounts(TestAccounts { user: Pubkey::new_unique(), account: Pubkey::new_unique() })
+            .args(TestArgs { amount: 1 })
+            .pre_instruction(pre.clone())
+            .post_instruction(post.clone())
+            .instructions()
+            .unwrap();
+
+        assert_eq!(ixs.len(), 3);
+        assert_eq!(ixs[0], pre);
+        assert_eq!(ixs[1].program_id, program_id);
+        assert_eq!(ixs[2], post);
+    }
+
+    #[test]
+    fn test_send_with_pre_instruction_is_atomic() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+        let recipient = Keypair::new();
+        let recipient_ata = spl_associated_token_account::get_associated_token_address(&recipient.pubkey(), &mint);
+
+        ctx.program()
+            .accounts(MintToAccounts { mint, account: recipient_ata, authority: payer.pubkey() })
+            .args(MintToArgs { amount: 42 })
+            .pre_instruction(spl_associated_token_account::instruction::create_associated_token_account(
+                &payer.pubkey(),
+                &recipient.pubkey(),
+                &mint,
+                &spl_token::id(),
+            ))
+            .post_instruction(spl_token::instruction::mint_to(&spl_token::id(), &mint, &ata, &payer.pubkey(), &[], 1).unwrap())
+            .signers(&[&payer])
+            .send(&mut ctx)
+            .unwrap()
+            .assert_success();
+
+        ctx.svm.assert_token_balance(&recipient_ata, 42);
+        ctx.svm.assert_token_balance(&ata, 1);
+    }
+
+    #[test]
+    fn test_with_compute_limit() {
+        let ixs = Program::new(Pubkey::new_unique())
+            .accounts(TestAccounts { user: Pubkey::new_unique(), account: Pubkey::new_unique() })
+            .args(TestArgs { amount: 1 })
+            .pre_instruction(Instruction::new_with_bytes(Pubkey::new_unique(), &[1], vec![]))
+            .with_compute_limit(400_000)
+            .instructions()
+            .unwrap();
+
+        assert_eq!(ixs.len(), 3);
+        assert_eq!(ixs[0], ComputeBudgetInstruction::set_compute_unit_limit(400_000));
+    }
+
+    #[test]
+    fn test_send_with_compute_limit_enforced() {
+        let (mut ctx, mint, ata) = token_setup();
+        let authority = ctx.payer().pubkey();
+
+        let result = ctx
+            .program()
+            .accounts(MintToAccounts { mint, account: ata, authority })
+            .args(MintToArgs { amount: 5 })
+            .with_compute_limit(100)
+            .send(&mut ctx)
+            .unwrap();
+        result.assert_failure();
+        result.assert_error("ComputationalBudgetExceeded");
+    }
+
+    #[test]
+    fn test_with_compute_unit_price() {
+        let ixs = Program::new(Pubkey::new_unique())
+            .accounts(TestAccounts { user: Pubkey::new_unique(), account: Pubkey::new_unique() })
+            .args(TestArgs { amount: 1 })
+            .with_compute_unit_price(5_000)
+            .with_compute_limit(300_000)
+            .instructions()
+            .unwrap();
+
+        assert_eq!(ixs.len(), 3);
+        assert_eq!(ixs[0], ComputeBudgetInstruction::set_compute_unit_limit(300_000));
+        assert_eq!(ixs[1], ComputeBudgetInstruction::set_compute_unit_price(5_000));
+    }
+
+    #[test]
+    fn test_send_with_compute_unit_price_debits_fee_payer() {
+        let (mut ctx, mint, ata) = token_setup();
+        let authority = ctx.payer().pubkey();
+        let balance_before = ctx.payer_balance();
+
+        let result = ctx
+            .program()
+            .accounts(MintToAccounts { mint, account: ata, authority })
+            .args(MintToArgs { amount: 5 })
+            .with_compute_limit(200_000)
+            .with_compute_unit_price(1_000_000)
+            .send(&mut ctx)
+            .unwrap();
+        result.assert_success();
+
+        assert_eq!(balance_before - ctx.payer_balance(), litesvm_utils::LAMPORTS_PER_SIGNATURE);
+    }
+
+    #[test]
+    fn test_send_with_signers() {
+        let (mut ctx, mint, ata) = token_setup();
+        let payer = ctx.payer().insecure_clone();
+
+        let result = ctx
+            .program()
+            .accounts(MintToAccounts { mint, account: ata, authority: payer.pubkey() })
+            .args(MintToArgs { amount: 500 })
+            .signers(&[&payer])
+            .send(&mut ctx)
+            .unwrap();
+
+        result.assert_success();
+        ctx.svm.assert_token_balance(&ata, 500);
+    }
+
+    #[test]
+    fn test_send_defaults_to_context_payer() {
+        let (mut ctx, mint, ata) = token_setup();
+        let authority = ctx.payer().pubkey();
+
+        ctx.program()
+            .accounts(MintToAccounts { mint, account: ata, authority })
+            .args(MintToArgs { amount: 5 })
+            .send(&mut ctx)
+            .unwrap()
+            .assert_success();
+    }
+
+    #[test]
+    fn test_send_without_args_fails() {
+        let (mut ctx, mint, ata) = token_setup();
+        let authority = ctx.payer().pubkey();
+
+        let result = ctx
+            .program()
+            .accounts(MintToAccounts { mint, account: ata, authority })
+            .send(&mut ctx);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_accounts_from_idl() {
+        let idl: crate::Idl = serde_json::from_value(serde_json::json!({
+            "address": "11111111111111111111111111111111",
+            "metadata": { "name": "test_program", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [{
+                "name": "initialize",
+                "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                "accounts": [
+                    { "name": "user", "writable": true, "signer": true },
+                    { "name": "vault", "writable": true, "pda": { "seeds": [
+                        { "kind": "const", "value": [118, 97, 117, 108, 116] },
+                        { "kind": "account", "path": "user" }
+                    ]}}
+                ],
+                "args": [{ "name": "amount", "type": "u64" }]
+            }]
+        }))
+        .unwrap();
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+
+        let ix = Program::new(program_id)
+            .accounts_from_idl(&idl, "initialize", crate::IdlAccountInputs::new().account("user", user))
+            .unwrap()
+            .args(TestArgs { amount: 100 })
+            .instruction()
+            .unwrap();
+
+        let (vault, _) = Pubkey::find_program_address(&[b"vault", user.as_ref()], &program_id);
+        assert_eq!(ix.accounts, vec![AccountMeta::new(user, true), AccountMeta::new(vault, false)]);
+    }
+
     #[test]
     fn test_simplified_syntax() {
         let program_id = Pubkey::new_unique();
@@ -0,0 +1,82 @@
+//! Typed access to instruction return data
+//!
+//! Anchor instructions with a return type serialize the value with borsh and
+//! pass it to `set_return_data`. These helpers decode it from a
+//! `TransactionResult`.
+
+use anchor_lang::AnchorDeserialize;
+use litesvm_utils::TransactionResult;
+
+/// Return data decoding error types
+#[derive(Debug, thiserror::Error)]
+pub enum ReturnDataError {
+    #[error("Transaction has no return data")]
+    NoReturnData,
+
+    #[error("Failed to deserialize return data: {0}")]
+    DeserializeError(String),
+}
+
+/// Extension trait for TransactionResult to decode return data
+pub trait ReturnDataHelpers {
+    /// Deserialize the transaction's return data as `T`
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // pub fn get_balance(ctx: Context<GetBalance>) -> Result<u64>
+    /// let result = ctx.execute_instruction(ix, &[&user]).unwrap();
+    /// let balance: u64 = result.return_data_as().unwrap();
+    /// assert_eq!(balance, 1_000_000);
+    /// ```
+    fn return_data_as<T: AnchorDeserialize>(&self) -> Result<T, ReturnDataError>;
+}
+
+impl ReturnDataHelpers for TransactionResult {
+    fn return_data_as<T: AnchorDeserialize>(&self) -> Result<T, ReturnDataError> {
+        let mut data = self.return_data().ok_or(ReturnDataError::NoReturnData)?;
+        T::deserialize(&mut data).map_err(|e| ReturnDataError::DeserializeError(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+    use litesvm::types::TransactionMetadata;
+
+    #[derive(AnchorSerialize, AnchorDeserialize, Debug, PartialEq)]
+    struct Quote {
+        amount_out: u64,
+        fee: u16,
+    }
+
+    fn result_with_return_data(data: Vec<u8>) -> TransactionResult {
+        let mut meta = TransactionMetadata::default();
+        meta.return_data.data = data;
+        TransactionResult::new(meta, None)
+    }
+
+    #[test]
+    fn test_return_data_as() {
+        let quote = Quote { amount_out: 1_000, fee: 30 };
+        let mut data = Vec::new();
+        quote.serialize(&mut data).unwrap();
+        let result = result_with_return_data(data);
+
+        assert_eq!(result.return_data_as::<Quote>().unwrap(), quote);
+        assert_eq!(result.return_data_as::<u64>().unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_return_data_as_errors() {
+        let result = result_with_return_data(vec![]);
+        assert!(matches!(result.return_data_as::<u64>(), Err(ReturnDataError::NoReturnData)));
+
+        let result = result_with_return_data(vec![1, 2]);
+        assert!(matches!(
+            result.return_data_as::<u64>(),
+            Err(ReturnDataError::DeserializeError(_))
+        ));
+    }
+}
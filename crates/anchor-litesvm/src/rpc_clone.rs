@@ -0,0 +1,358 @@
+//! Cloning accounts and programs from a live cluster
+//!
+//! Available with the `rpc-clone` feature. Accounts are fetched with the
+//! `getAccountInfo` JSON-RPC method and cached to disk, so each account is
+//! only downloaded once.
+
+use base64::{engine::general_purpose, Engine as _};
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use solana_program::pubkey::Pubkey;
+use sha2::{Digest, Sha256};
+use solana_sdk::account::Account;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Default directory for cached RPC responses, relative to the working directory
+pub const DEFAULT_RPC_CACHE_DIR: &str = "target/rpc-cache";
+
+/// Largest RPC response read, well above a 10 MiB program encoded as base64
+const MAX_RESPONSE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Fetch an account from an RPC endpoint, using the on-disk cache when available
+///
+/// Responses are cached per cluster in `cache_dir` as `<cluster>/<pubkey>.json`
+/// (see [`cluster_cache_dir`]), so an account cloned from one cluster is never
+/// returned for another. Delete the file to fetch the account again.
+///
+/// # Example
+/// ```ignore
+/// let account = fetch_account(
+///     "https://api.mainnet-beta.solana.com",
+///     &pool,
+///     Path::new(DEFAULT_RPC_CACHE_DIR),
+/// )?;
+/// ```
+pub fn fetch_account(
+    rpc_url: &str,
+    address: &Pubkey,
+    cache_dir: &Path,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let cache_dir = cluster_cache_dir(cache_dir, rpc_url);
+    let cache_file = cache_dir.join(format!("{}.json", address));
+    let value = if cache_file.exists() {
+        serde_json::from_str(&std::fs::read_to_string(&cache_file)?)?
+    } else {
+        let value = request_account(rpc_url, address)?;
+        std::fs::create_dir_all(&cache_dir)?;
+        std::fs::write(&cache_file, serde_json::to_string_pretty(&value)?)?;
+        value
+    };
+    parse_account(address, &value)
+}
+
+/// Fetch a program's ELF bytes, resolving the ProgramData account for upgradeable programs
+pub fn fetch_program(
+    rpc_url: &str,
+    program_id: &Pubkey,
+    cache_dir: &Path,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let program = fetch_account(rpc_url, program_id, cache_dir)?;
+    if !program.executable {
+        return Err(format!("Account {} is not an executable program", program_id).into());
+    }
+    if program.owner != solana_sdk_ids::bpf_loader_upgradeable::id() {
+        return Ok(program.data);
+    }
+
+    let programdata_address = match bincode::deserialize(&program.data) {
+        Ok(UpgradeableLoaderState::Program { programdata_address }) => programdata_address,
+        _ => return Err(format!("Program account {} has invalid loader state", program_id).into()),
+    };
+    let programdata = fetch_account(rpc_url, &programdata_address, cache_dir)?;
+    let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+    if programdata.data.len() < metadata_len {
+        return Err(format!("ProgramData account {} is too small", programdata_address).into());
+    }
+    Ok(programdata.data[metadata_len..].to_vec())
+}
+
+/// Directory under `cache_dir` holding the cached accounts of one RPC endpoint
+///
+/// The directory is named after the endpoint's host followed by a short hash of
+/// the full URL, e.g. `api.devnet.solana.com-1a2b3c4d`. The hash separates
+/// endpoints on the same host without writing API keys in the URL to disk.
+pub fn cluster_cache_dir(cache_dir: &Path, rpc_url: &str) -> PathBuf {
+    let without_scheme = rpc_url.split_once("://").map_or(rpc_url, |(_, rest)| rest);
+    let host: String = without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+        .collect();
+    let hash = Sha256::digest(rpc_url.as_bytes());
+    let hash: String = hash[..4].iter().map(|byte| format!("{:02x}", byte)).collect();
+    cache_dir.join(format!("{}-{}", host, hash))
+}
+
+/// Default cache directory as a path
+pub(crate) fn default_cache_dir() -> PathBuf {
+    PathBuf::from(DEFAULT_RPC_CACHE_DIR)
+}
+
+/// Request the `value` of `getAccountInfo` for an account
+fn request_account(
+    rpc_url: &str,
+    address: &Pubkey,
+) -> Result<serde_json::Value, Box<dyn std::error::Error>> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "getAccountInfo",
+        "params": [address.to_string(), { "encoding": "base64" }],
+    });
+    let request_failed = |e: ureq::Error| format!("RPC request for {} to {} failed: {}", address, rpc_url, e);
+    let text = ureq::post(rpc_url)
+        .header("Content-Type", "application/json")
+        .send(body.to_string())
+        .map_err(request_failed)?
+        .body_mut()
+        .with_config()
+        .limit(MAX_RESPONSE_BYTES)
+        .read_to_string()
+        .map_err(request_failed)?;
+
+    let mut response: serde_json::Value = serde_json::from_str(&text)?;
+    if let Some(error) = response.get("error") {
+        return Err(format!("RPC error for {}: {}", address, error).into());
+    }
+    match response["result"]["value"].take() {
+        serde_json::Value::Null => Err(format!("Account {} not found on {}", address, rpc_url).into()),
+        value => Ok(value),
+    }
+}
+
+/// Convert a `getAccountInfo` value into an Account
+fn parse_account(
+    address: &Pubkey,
+    value: &serde_json::Value,
+) -> Result<Account, Box<dyn std::error::Error>> {
+    let invalid = || format!("Invalid account data for {} in RPC response", address);
+    let data = value["data"][0].as_str().ok_or_else(invalid)?;
+    let owner = value["owner"].as_str().ok_or_else(invalid)?;
+
+    Ok(Account {
+        lamports: value["lamports"].as_u64().ok_or_else(invalid)?,
+        data: general_purpose::STANDARD.decode(data)?,
+        owner: Pubkey::from_str(owner)?,
+        executable: value["executable"].as_bool().unwrap_or(false),
+        rent_epoch: value["rentEpoch"].as_u64().unwrap_or(u64::MAX),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("anchor-litesvm-rpc-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    const RPC_URL: &str = "http://127.0.0.1:1";
+
+    fn write_cached(dir: &Path, address: &Pubkey, account: &Account) {
+        let dir = cluster_cache_dir(dir, RPC_URL);
+        std::fs::create_dir_all(&dir).unwrap();
+        let value = serde_json::json!({
+            "data": [general_purpose::STANDARD.encode(&account.data), "base64"],
+            "executable": account.executable,
+            "lamports": account.lamports,
+            "owner": account.owner.to_string(),
+            "rentEpoch": account.rent_epoch,
+        });
+        std::fs::write(dir.join(format!("{}.json", address)), value.to_string()).unwrap();
+    }
+
+    /// Serve a single JSON-RPC response on a local port, returning its URL and the request received
+    fn serve_once(response: serde_json::Value) -> (String, std::thread::JoinHandle<String>) {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            // Read the headers, then the body announced by Content-Length
+            while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                let n = stream.read(&mut buf).unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+            let text = String::from_utf8_lossy(&request).to_string();
+            let (headers, body) = text.split_once("\r\n\r\n").unwrap();
+            let content_length: usize = headers
+                .lines()
+                .find_map(|line| line.to_lowercase().strip_prefix("content-length:").map(|n| n.trim().parse().unwrap()))
+                .unwrap_or(0);
+            let mut body = body.as_bytes().to_vec();
+            while body.len() < content_length {
+                let n = stream.read(&mut buf).unwrap();
+                body.extend_from_slice(&buf[..n]);
+            }
+
+            let response = response.to_string();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+            String::from_utf8(body).unwrap()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_fetch_account_from_rpc() {
+        let dir = temp_cache_dir("rpc");
+        let address = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let (url, server) = serve_once(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "context": { "slot": 1 },
+                "value": {
+                    "data": [general_purpose::STANDARD.encode([7, 8, 9]), "base64"],
+                    "executable": false,
+                    "lamports": 42,
+                    "owner": owner.to_string(),
+                    "rentEpoch": 0,
+                },
+            },
+        }));
+
+        let account = fetch_account(&url, &address, &dir).unwrap();
+        assert_eq!(account.data, vec![7, 8, 9]);
+        assert_eq!((account.lamports, account.owner), (42, owner));
+
+        let request: serde_json::Value = serde_json::from_str(&server.join().unwrap()).unwrap();
+        assert_eq!(request["method"], "getAccountInfo");
+        assert_eq!(request["params"][0], address.to_string());
+
+        // The response was cached, so the server (now gone) isn't contacted again
+        assert_eq!(fetch_account(&url, &address, &dir).unwrap(), account);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_missing_account_from_rpc() {
+        let dir = temp_cache_dir("rpc-missing");
+        let address = Pubkey::new_unique();
+        let (url, server) = serve_once(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": { "context": { "slot": 1 }, "value": null },
+        }));
+
+        let err = fetch_account(&url, &address, &dir).unwrap_err();
+        assert!(err.to_string().contains("not found"), "{}", err);
+        server.join().unwrap();
+        assert!(!cluster_cache_dir(&dir, &url).join(format!("{}.json", address)).exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_account_unreachable_rpc() {
+        let dir = temp_cache_dir("rpc-unreachable");
+        let err = fetch_account(RPC_URL, &Pubkey::new_unique(), &dir).unwrap_err();
+        assert!(err.to_string().contains("RPC request for"), "{}", err);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_account_from_cache() {
+        let dir = temp_cache_dir("account");
+        let address = Pubkey::new_unique();
+        let account = Account {
+            lamports: 1_000_000,
+            data: vec![1, 2, 3],
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        write_cached(&dir, &address, &account);
+
+        // The RPC URL is never contacted when the account is cached
+        let fetched = fetch_account(RPC_URL, &address, &dir).unwrap();
+        assert_eq!(fetched, account);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_cache_is_per_cluster() {
+        let devnet = cluster_cache_dir(Path::new("cache"), "https://api.devnet.solana.com");
+        let mainnet = cluster_cache_dir(Path::new("cache"), "https://api.mainnet-beta.solana.com");
+        assert_ne!(devnet, mainnet);
+        assert!(devnet.starts_with("cache"));
+        assert!(devnet.file_name().unwrap().to_str().unwrap().starts_with("api.devnet.solana.com-"));
+        // Endpoints on the same host are kept apart too
+        assert_ne!(
+            cluster_cache_dir(Path::new("cache"), "https://rpc.example.com/?api-key=a"),
+            cluster_cache_dir(Path::new("cache"), "https://rpc.example.com/?api-key=b")
+        );
+
+        // An account cached for one cluster isn't returned for another
+        let dir = temp_cache_dir("cluster");
+        let address = Pubkey::new_unique();
+        let account = Account {
+            lamports: 1,
+            data: vec![],
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        };
+        write_cached(&dir, &address, &account);
+        assert_eq!(fetch_account(RPC_URL, &address, &dir).unwrap(), account);
+        assert!(fetch_account("http://127.0.0.2:1", &address, &dir).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_upgradeable_program_from_cache() {
+        let dir = temp_cache_dir("program");
+        let program_id = Pubkey::new_unique();
+        let programdata_address = solana_loader_v3_interface::get_program_data_address(&program_id);
+        let elf = vec![0x7f, b'E', b'L', b'F', 1, 2, 3];
+
+        let mut programdata = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: None,
+        })
+        .unwrap();
+        programdata.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+        programdata.extend_from_slice(&elf);
+
+        let loader = solana_sdk_ids::bpf_loader_upgradeable::id();
+        write_cached(&dir, &program_id, &Account {
+            lamports: 1,
+            data: bincode::serialize(&UpgradeableLoaderState::Program { programdata_address }).unwrap(),
+            owner: loader,
+            executable: true,
+            rent_epoch: 0,
+        });
+        write_cached(&dir, &programdata_address, &Account {
+            lamports: 1,
+            data: programdata,
+            owner: loader,
+            executable: false,
+            rent_epoch: 0,
+        });
+
+        assert_eq!(fetch_program(RPC_URL, &program_id, &dir).unwrap(), elf);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -3,10 +3,138 @@
 //! This module provides convenient assertion methods for verifying
 //! account states in tests.
 
+use crate::transaction::TransactionResult;
 use litesvm::LiteSVM;
 use solana_program::pubkey::Pubkey;
 use litesvm_token::spl_token;
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
 use solana_program_pack::Pack;
+use std::ops::RangeInclusive;
+
+/// Recorded SOL and token balances for asserting balance changes after a transaction
+///
+/// Created with [`AssertionHelpers::track_balances`] before executing a transaction.
+/// Token amounts are recorded for every tracked account; accounts that aren't
+/// token accounts (or don't exist yet) are recorded with an amount of 0.
+///
+/// # Example
+/// ```ignore
+/// let tracker = svm.track_balances(&[user.pubkey(), vault]);
+/// let result = svm.send_instruction(deposit_ix, &[&user])?;
+/// tracker.assert_change_excluding_fee(&svm, &user.pubkey(), -1_000_000, &result);
+/// tracker.assert_change(&svm, &vault, 1_000_000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BalanceTracker {
+    balances: Vec<(Pubkey, u64)>,
+    token_amounts: Vec<(Pubkey, u64)>,
+}
+
+impl BalanceTracker {
+    /// Record the current SOL and token balances of the given accounts
+    pub fn new(svm: &LiteSVM, accounts: &[Pubkey]) -> Self {
+        Self {
+            balances: accounts
+                .iter()
+                .map(|address| (*address, svm.get_balance(address).unwrap_or(0)))
+                .collect(),
+            token_amounts: accounts
+                .iter()
+                .map(|address| (*address, token_amount(svm, address).unwrap_or(0)))
+                .collect(),
+        }
+    }
+
+    /// Get the change in token amount of a tracked token account since it was recorded
+    ///
+    /// Closed token accounts count as holding 0 tokens. Token-2022 accounts with
+    /// extensions are supported.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the account is not tracked, or exists but is not a token account
+    pub fn token_delta(&self, svm: &LiteSVM, token_account: &Pubkey) -> i128 {
+        let before = self
+            .token_amounts
+            .iter()
+            .find(|(tracked, _)| tracked == token_account)
+            .map(|(_, amount)| *amount)
+            .unwrap_or_else(|| panic!("Account {} is not tracked", token_account));
+        let after = match svm.get_account(token_account) {
+            Some(account) if account.lamports > 0 => token_amount(svm, token_account)
+                .unwrap_or_else(|| panic!("Failed to unpack token account {}", token_account)),
+            _ => 0,
+        };
+        after as i128 - before as i128
+    }
+
+    /// Get the change in lamports of a tracked account since it was recorded
+    ///
+    /// # Panics
+    ///
+    /// Panics if the account is not tracked
+    pub fn delta(&self, svm: &LiteSVM, address: &Pubkey) -> i128 {
+        let before = self
+            .balances
+            .iter()
+            .find(|(tracked, _)| tracked == address)
+            .map(|(_, lamports)| *lamports)
+            .unwrap_or_else(|| panic!("Account {} is not tracked", address));
+        svm.get_balance(address).unwrap_or(0) as i128 - before as i128
+    }
+
+    /// Assert the exact change in lamports of a tracked account
+    ///
+    /// # Panics
+    ///
+    /// Panics if the account is not tracked or the change differs
+    pub fn assert_change(&self, svm: &LiteSVM, address: &Pubkey, expected: i128) {
+        let actual = self.delta(svm, address);
+        assert_eq!(
+            actual, expected,
+            "SOL balance change mismatch for account {}. Expected: {}, Actual: {}",
+            address, expected, actual
+        );
+    }
+
+    /// Assert that the change in lamports of a tracked account is within a range
+    ///
+    /// # Panics
+    ///
+    /// Panics if the account is not tracked or the change is outside the range
+    pub fn assert_change_in_range(&self, svm: &LiteSVM, address: &Pubkey, range: RangeInclusive<i128>) {
+        let actual = self.delta(svm, address);
+        assert!(
+            range.contains(&actual),
+            "SOL balance change out of range for account {}. Expected: {:?}, Actual: {}",
+            address, range, actual
+        );
+    }
+
+    /// Assert the change in lamports of the fee payer, not counting the transaction fee
+    ///
+    /// # Panics
+    ///
+    /// Panics if the account is not tracked, the result has no recorded fee, or the
+    /// change differs once the fee is added back
+    pub fn assert_change_excluding_fee(
+        &self,
+        svm: &LiteSVM,
+        address: &Pubkey,
+        expected: i128,
+        result: &TransactionResult,
+    ) {
+        let fee = result
+            .fee
+            .expect("Transaction result has no recorded fee");
+        let actual = self.delta(svm, address) + fee as i128;
+        assert_eq!(
+            actual, expected,
+            "SOL balance change mismatch for account {} excluding fee of {}. Expected: {}, Actual: {}",
+            address, fee, expected, actual
+        );
+    }
+}
 
 /// Assertion helper methods for LiteSVM
 pub trait AssertionHelpers {
@@ -101,9 +229,95 @@ pub trait AssertionHelpers {
     /// svm.assert_account_data_len(&account, 100);
     /// ```
     fn assert_account_data_len(&self, account: &Pubkey, expected_len: usize);
+
+    /// Assert the upgrade authority of a program deployed with the upgradeable loader
+    ///
+    /// Reads the program's ProgramData account and compares its upgrade authority.
+    /// Pass `None` to assert that the program is immutable.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::AssertionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let svm = LiteSVM::new();
+    /// # let program_id = Pubkey::new_unique();
+    /// # let authority = Pubkey::new_unique();
+    /// svm.assert_upgrade_authority(&program_id, Some(authority));
+    /// ```
+    fn assert_upgrade_authority(&self, program_id: &Pubkey, expected: Option<Pubkey>);
+
+    /// Assert the close authority of a token account
+    ///
+    /// Pass `None` to assert that no close authority is set (the owner can close the account).
+    /// Token-2022 accounts with extensions are supported.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::AssertionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let svm = LiteSVM::new();
+    /// # let token_account = Pubkey::new_unique();
+    /// # let authority = Pubkey::new_unique();
+    /// svm.assert_token_close_authority(&token_account, Some(authority));
+    /// ```
+    fn assert_token_close_authority(&self, account: &Pubkey, expected: Option<Pubkey>);
+
+    /// Record SOL balances to assert changes after a transaction
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::AssertionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let svm = LiteSVM::new();
+    /// # let vault = Pubkey::new_unique();
+    /// let tracker = svm.track_balances(&[vault]);
+    /// // ... execute a transaction ...
+    /// tracker.assert_change(&svm, &vault, 1_000_000);
+    /// ```
+    fn track_balances(&self, accounts: &[Pubkey]) -> BalanceTracker;
+
+    /// Assert the change in token amount of a token account since it was tracked
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::AssertionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let svm = LiteSVM::new();
+    /// # let ata = Pubkey::new_unique();
+    /// let tracker = svm.track_balances(&[ata]);
+    /// // ... execute a transaction ...
+    /// svm.assert_token_balance_change(&tracker, &ata, 100);
+    /// ```
+    fn assert_token_balance_change(&self, tracker: &BalanceTracker, token_account: &Pubkey, expected: i128);
+}
+
+/// Read the amount held by a token account, supporting Token-2022 extensions
+fn token_amount(svm: &LiteSVM, token_account: &Pubkey) -> Option<u64> {
+    let account = svm.get_account(token_account)?;
+    let data = account.data.get(..spl_token::state::Account::LEN)?;
+    spl_token::state::Account::unpack_from_slice(data)
+        .ok()
+        .map(|token| token.amount)
 }
 
 impl AssertionHelpers for LiteSVM {
+    fn track_balances(&self, accounts: &[Pubkey]) -> BalanceTracker {
+        BalanceTracker::new(self, accounts)
+    }
+
+    fn assert_token_balance_change(&self, tracker: &BalanceTracker, token_account: &Pubkey, expected: i128) {
+        let actual = tracker.token_delta(self, token_account);
+        assert_eq!(
+            actual, expected,
+            "Token balance change mismatch for account {}. Expected: {}, Actual: {}",
+            token_account, expected, actual
+        );
+    }
+
     fn assert_account_closed(&self, pubkey: &Pubkey) {
         let account = self.get_account(pubkey);
         assert!(
@@ -129,7 +343,9 @@ impl AssertionHelpers for LiteSVM {
             .get_account(token_account)
             .unwrap_or_else(|| panic!("Token account {} not found", token_account));
 
-        let token_data = spl_token::state::Account::unpack(&account.data)
+        // Token-2022 accounts share the base layout and append extensions after it
+        let data = account.data.get(..spl_token::state::Account::LEN).unwrap_or(&[]);
+        let token_data = spl_token::state::Account::unpack_from_slice(data)
             .unwrap_or_else(|_| panic!("Failed to unpack token account {}", token_account));
 
         assert_eq!(
@@ -154,7 +370,9 @@ impl AssertionHelpers for LiteSVM {
             .get_account(mint)
             .unwrap_or_else(|| panic!("Mint {} not found", mint));
 
-        let mint_data = spl_token::state::Mint::unpack(&account.data)
+        // Token-2022 mints share the base layout and append extensions after it
+        let data = account.data.get(..spl_token::state::Mint::LEN).unwrap_or(&[]);
+        let mint_data = spl_token::state::Mint::unpack_from_slice(data)
             .unwrap_or_else(|_| panic!("Failed to unpack mint {}", mint));
 
         assert_eq!(
@@ -190,12 +408,69 @@ impl AssertionHelpers for LiteSVM {
             acc.data.len()
         );
     }
+
+    fn assert_upgrade_authority(&self, program_id: &Pubkey, expected: Option<Pubkey>) {
+        let program = self
+            .get_account(program_id)
+            .unwrap_or_else(|| panic!("Program {} not found", program_id));
+
+        assert_eq!(
+            program.owner,
+            solana_sdk_ids::bpf_loader_upgradeable::id(),
+            "Program {} is not owned by the upgradeable loader (owner: {})",
+            program_id,
+            program.owner
+        );
+
+        let programdata_address = match bincode::deserialize(&program.data) {
+            Ok(UpgradeableLoaderState::Program { programdata_address }) => programdata_address,
+            _ => panic!("Failed to read program account state for {}", program_id),
+        };
+
+        let programdata = self
+            .get_account(&programdata_address)
+            .unwrap_or_else(|| panic!("ProgramData account {} not found", programdata_address));
+
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata()
+            .min(programdata.data.len());
+        let actual = match bincode::deserialize(&programdata.data[..metadata_len]) {
+            Ok(UpgradeableLoaderState::ProgramData { upgrade_authority_address, .. }) => {
+                upgrade_authority_address
+            }
+            _ => panic!("Failed to read ProgramData state for {}", program_id),
+        };
+
+        assert_eq!(
+            actual, expected,
+            "Upgrade authority mismatch for program {}. Expected: {:?}, Actual: {:?}",
+            program_id, expected, actual
+        );
+    }
+
+    fn assert_token_close_authority(&self, account: &Pubkey, expected: Option<Pubkey>) {
+        let acc = self
+            .get_account(account)
+            .unwrap_or_else(|| panic!("Token account {} not found", account));
+
+        // Token-2022 accounts share the base layout and append extensions after it
+        let data = acc.data.get(..spl_token::state::Account::LEN).unwrap_or(&[]);
+        let token_data = spl_token::state::Account::unpack_from_slice(data)
+            .unwrap_or_else(|_| panic!("Failed to unpack token account {}", account));
+
+        let actual: Option<Pubkey> = token_data.close_authority.into();
+        assert_eq!(
+            actual, expected,
+            "Close authority mismatch for token account {}. Expected: {:?}, Actual: {:?}",
+            account, expected, actual
+        );
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test_helpers::TestHelpers;
+    use crate::transaction::TransactionHelpers;
     use solana_sdk::signature::Signer;
 
     #[test]
@@ -282,6 +557,80 @@ mod tests {
         svm.assert_sol_balance(&account.pubkey(), 2_000_000_000);
     }
 
+    #[test]
+    fn test_balance_tracker() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(1_000_000_000).unwrap();
+        let recipient = Pubkey::new_unique();
+        let tracker = svm.track_balances(&[payer.pubkey(), recipient]);
+
+        let ix = solana_system_interface::instruction::transfer(&payer.pubkey(), &recipient, 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+        result.assert_success();
+
+        tracker.assert_change(&svm, &recipient, 1_000_000);
+        tracker.assert_change(&svm, &payer.pubkey(), -1_005_000);
+        tracker.assert_change_excluding_fee(&svm, &payer.pubkey(), -1_000_000, &result);
+        tracker.assert_change_in_range(&svm, &payer.pubkey(), -1_010_000..=-1_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "SOL balance change mismatch")]
+    fn test_balance_tracker_fails() {
+        let mut svm = LiteSVM::new();
+        let account = svm.create_funded_account(1_000_000_000).unwrap();
+        let tracker = svm.track_balances(&[account.pubkey()]);
+        svm.airdrop(&account.pubkey(), 1).unwrap();
+
+        tracker.assert_change(&svm, &account.pubkey(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not tracked")]
+    fn test_balance_tracker_untracked() {
+        let svm = LiteSVM::new();
+        svm.track_balances(&[]).delta(&svm, &Pubkey::new_unique());
+    }
+
+    #[test]
+    fn test_assert_token_balance_change() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&authority, 6).unwrap();
+        let source = svm.create_associated_token_account(&mint.pubkey(), &authority).unwrap();
+        svm.mint_to(&mint.pubkey(), &source, &authority, 1_000).unwrap();
+        let destination = spl_associated_token_account::get_associated_token_address(
+            &recipient.pubkey(),
+            &mint.pubkey(),
+        );
+
+        // The destination doesn't exist yet and is recorded as holding 0 tokens
+        let tracker = svm.track_balances(&[source, destination]);
+        svm.create_associated_token_account(&mint.pubkey(), &recipient).unwrap();
+        let ix = spl_token::instruction::transfer(
+            &spl_token::id(), &source, &destination, &authority.pubkey(), &[], 400,
+        )
+        .unwrap();
+        svm.send_instruction(ix, &[&authority]).unwrap().assert_success();
+
+        svm.assert_token_balance_change(&tracker, &source, -400);
+        svm.assert_token_balance_change(&tracker, &destination, 400);
+    }
+
+    #[test]
+    #[should_panic(expected = "Token balance change mismatch")]
+    fn test_assert_token_balance_change_fails() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&authority, 6).unwrap();
+        let ata = svm.create_associated_token_account(&mint.pubkey(), &authority).unwrap();
+
+        let tracker = svm.track_balances(&[ata]);
+        svm.mint_to(&mint.pubkey(), &ata, &authority, 50).unwrap();
+        svm.assert_token_balance_change(&tracker, &ata, 100);
+    }
+
     #[test]
     fn test_assert_sol_balance_zero_for_nonexistent() {
         let svm = LiteSVM::new();
@@ -390,4 +739,109 @@ mod tests {
         // Token account data is 165 bytes
         svm.assert_account_data_len(&token_account.pubkey(), 165);
     }
-}
\ No newline at end of file
+
+    /// Write program and ProgramData accounts as the upgradeable loader lays them out
+    fn set_upgradeable_program(svm: &mut LiteSVM, program_id: &Pubkey, authority: Option<Pubkey>) {
+        let programdata_address = solana_loader_v3_interface::get_program_data_address(program_id);
+        let loader = solana_sdk_ids::bpf_loader_upgradeable::id();
+
+        // Programs are stored as non-executable here so LiteSVM doesn't try to load them
+        let program_state = UpgradeableLoaderState::Program { programdata_address };
+        svm.set_account(
+            *program_id,
+            solana_sdk::account::Account {
+                lamports: 1_000_000,
+                data: bincode::serialize(&program_state).unwrap(),
+                owner: loader,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let programdata_state = UpgradeableLoaderState::ProgramData {
+            slot: 0,
+            upgrade_authority_address: authority,
+        };
+        svm.set_account(
+            programdata_address,
+            solana_sdk::account::Account {
+                lamports: 1_000_000,
+                data: bincode::serialize(&programdata_state).unwrap(),
+                owner: loader,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_assert_upgrade_authority() {
+        let mut svm = LiteSVM::new();
+        let program_id = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        set_upgradeable_program(&mut svm, &program_id, Some(authority));
+
+        svm.assert_upgrade_authority(&program_id, Some(authority));
+    }
+
+    #[test]
+    fn test_assert_upgrade_authority_immutable() {
+        let mut svm = LiteSVM::new();
+        let program_id = Pubkey::new_unique();
+        set_upgradeable_program(&mut svm, &program_id, None);
+
+        svm.assert_upgrade_authority(&program_id, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Upgrade authority mismatch")]
+    fn test_assert_upgrade_authority_fails() {
+        let mut svm = LiteSVM::new();
+        let program_id = Pubkey::new_unique();
+        set_upgradeable_program(&mut svm, &program_id, Some(Pubkey::new_unique()));
+
+        svm.assert_upgrade_authority(&program_id, None);
+    }
+
+    #[test]
+    fn test_assert_token_close_authority() {
+        let mut svm = LiteSVM::new();
+        let owner = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&owner, 9).unwrap();
+        let token_account = svm
+            .create_associated_token_account(&mint.pubkey(), &owner)
+            .unwrap();
+
+        // No close authority by default
+        svm.assert_token_close_authority(&token_account, None);
+
+        let close_authority = Pubkey::new_unique();
+        let ix = spl_token::instruction::set_authority(
+            &spl_token::id(),
+            &token_account,
+            Some(&close_authority),
+            spl_token::instruction::AuthorityType::CloseAccount,
+            &owner.pubkey(),
+            &[],
+        )
+        .unwrap();
+        svm.send_instruction(ix, &[&owner]).unwrap().assert_success();
+
+        svm.assert_token_close_authority(&token_account, Some(close_authority));
+    }
+
+    #[test]
+    #[should_panic(expected = "Close authority mismatch")]
+    fn test_assert_token_close_authority_fails() {
+        let mut svm = LiteSVM::new();
+        let owner = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&owner, 9).unwrap();
+        let token_account = svm
+            .create_associated_token_account(&mint.pubkey(), &owner)
+            .unwrap();
+
+        svm.assert_token_close_authority(&token_account, Some(owner.pubkey()));
+    }
+}
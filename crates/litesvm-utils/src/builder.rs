@@ -1,10 +1,12 @@
 //! Builder pattern for simplified test environment setup
 //!
 //! This module provides a fluent API for setting up test environments
-//! with automatic program deployment and configuration.
+//! with automatic program deployment and configuration, and for writing
+//! arbitrary accounts into them.
 
 use litesvm::LiteSVM;
 use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
 
 /// Builder for creating a LiteSVM instance with programs pre-deployed
 ///
@@ -167,10 +169,140 @@ impl ProgramTestExt for LiteSVM {
     }
 }
 
+/// Builder for writing arbitrary accounts into LiteSVM
+///
+/// Useful for fabricating accounts owned by external programs (oracles, config
+/// accounts) whose initialization flows are impractical to replay in tests.
+/// Accounts default to zero lamports, no data and the system program as owner.
+///
+/// # Example
+/// ```ignore
+/// use litesvm_utils::AccountBuilder;
+///
+/// AccountBuilder::new()
+///     .owner(oracle_program_id)
+///     .data(price_feed_bytes)
+///     .rent_exempt()
+///     .write(&mut svm, &price_feed)?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AccountBuilder {
+    lamports: u64,
+    owner: Pubkey,
+    data: Vec<u8>,
+    executable: bool,
+    rent_exempt: bool,
+}
+
+impl AccountBuilder {
+    /// Create a new account builder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the account balance in lamports
+    pub fn lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// Set the program that owns the account
+    pub fn owner(mut self, owner: Pubkey) -> Self {
+        self.owner = owner;
+        self
+    }
+
+    /// Set the raw account data
+    pub fn data(mut self, data: Vec<u8>) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Mark the account as executable
+    pub fn executable(mut self, executable: bool) -> Self {
+        self.executable = executable;
+        self
+    }
+
+    /// Fund the account with at least the rent-exempt minimum for its data size
+    ///
+    /// If `lamports` is also set, the larger of the two is used.
+    pub fn rent_exempt(mut self) -> Self {
+        self.rent_exempt = true;
+        self
+    }
+
+    /// Write the account into LiteSVM, replacing any existing account at the address
+    ///
+    /// # Example
+    /// ```ignore
+    /// AccountBuilder::new().lamports(1_000_000).write(&mut svm, &address)?;
+    /// ```
+    pub fn write(self, svm: &mut LiteSVM, address: &Pubkey) -> Result<(), Box<dyn std::error::Error>> {
+        let lamports = if self.rent_exempt {
+            self.lamports.max(svm.minimum_balance_for_rent_exemption(self.data.len()))
+        } else {
+            self.lamports
+        };
+
+        svm.set_account(
+            *address,
+            Account {
+                lamports,
+                data: self.data,
+                owner: self.owner,
+                executable: self.executable,
+                rent_epoch: 0,
+            },
+        )
+        .map_err(|e| format!("Failed to write account {}: {:?}", address, e))?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_account_builder() {
+        let mut svm = LiteSVM::new();
+        let address = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+
+        AccountBuilder::new()
+            .owner(owner)
+            .data(vec![1, 2, 3])
+            .rent_exempt()
+            .write(&mut svm, &address)
+            .unwrap();
+
+        let account = svm.get_account(&address).unwrap();
+        assert_eq!(account.owner, owner);
+        assert_eq!(account.data, vec![1, 2, 3]);
+        assert_eq!(account.lamports, svm.minimum_balance_for_rent_exemption(3));
+        assert!(!account.executable);
+    }
+
+    #[test]
+    fn test_account_builder_lamports() {
+        let mut svm = LiteSVM::new();
+        let address = Pubkey::new_unique();
+
+        AccountBuilder::new().lamports(1_000).write(&mut svm, &address).unwrap();
+        let account = svm.get_account(&address).unwrap();
+        assert_eq!(account.lamports, 1_000);
+        assert_eq!(account.owner, Pubkey::default());
+
+        // Explicit lamports above the rent-exempt minimum are kept
+        AccountBuilder::new()
+            .lamports(10_000_000_000)
+            .rent_exempt()
+            .write(&mut svm, &address)
+            .unwrap();
+        assert_eq!(svm.get_account(&address).unwrap().lamports, 10_000_000_000);
+    }
+
     #[test]
     fn test_builder_new() {
         let builder = LiteSVMBuilder::new();
@@ -0,0 +1,196 @@
+//! Compute unit regression baselines
+//!
+//! Baselines map a name (usually the test name) to the compute units an
+//! instruction used when the baseline was recorded. They are stored as a sorted
+//! JSON object so the file can be checked in and reviewed like any other
+//! snapshot.
+//!
+//! Missing entries are recorded the first time they are checked. Set
+//! `UPDATE_CU_BASELINE=1` to overwrite existing entries after an intentional
+//! change.
+//!
+//! Test binaries run as separate processes, so several of them may share a
+//! baseline file. Saving merges with whatever is on disk and replaces the file
+//! atomically, so concurrent binaries don't lose each other's entries or leave
+//! a half-written file behind.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Default baseline file, relative to the working directory (the crate root under `cargo test`)
+pub const DEFAULT_CU_BASELINE_PATH: &str = "cu-baseline.json";
+
+/// Environment variable that makes baseline assertions overwrite recorded values
+pub const UPDATE_CU_BASELINE_ENV: &str = "UPDATE_CU_BASELINE";
+
+/// Serializes read-modify-write cycles between tests running in parallel threads
+///
+/// This only covers threads within one test binary; other processes are handled
+/// by merging and renaming in [`CuBaseline::save`].
+static BASELINE_LOCK: Mutex<()> = Mutex::new(());
+
+/// A set of recorded compute unit baselines backed by a JSON file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CuBaseline {
+    path: PathBuf,
+    entries: BTreeMap<String, u64>,
+}
+
+impl CuBaseline {
+    /// Load baselines from `path`, starting empty if the file doesn't exist
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref().to_path_buf();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| format!("Invalid baseline file {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(format!("Failed to read baseline file {}: {}", path.display(), e).into()),
+        };
+        Ok(Self { path, entries })
+    }
+
+    /// Get the recorded compute units for `name`
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.entries.get(name).copied()
+    }
+
+    /// Record the compute units for `name`, replacing any previous value
+    pub fn record(&mut self, name: &str, compute_units: u64) {
+        self.entries.insert(name.to_string(), compute_units);
+    }
+
+    /// Write the baselines back to their file
+    ///
+    /// Entries written to the file since it was loaded (e.g. by another test
+    /// binary) are kept unless this baseline records the same name. The file is
+    /// written to a temporary sibling and renamed into place, so readers never
+    /// see a partial file.
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut entries = Self::load(&self.path)?.entries;
+        entries.extend(self.entries.iter().map(|(name, units)| (name.clone(), *units)));
+
+        let mut contents = serde_json::to_string_pretty(&entries)?;
+        contents.push('\n');
+        let mut temp = self.path.clone().into_os_string();
+        temp.push(format!(".{}.tmp", std::process::id()));
+        std::fs::write(&temp, contents)?;
+        std::fs::rename(&temp, &self.path)?;
+        Ok(())
+    }
+}
+
+/// Check `compute_units` against the baseline for `name`, recording it if missing or `update` is set
+///
+/// # Panics
+///
+/// Panics if the baseline file can't be read or written, or if `compute_units`
+/// exceeds the baseline by more than `tolerance_pct` percent
+pub(crate) fn check_baseline(path: &Path, name: &str, compute_units: u64, tolerance_pct: f64, update: bool) {
+    let _guard = BASELINE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut baseline = CuBaseline::load(path).unwrap_or_else(|e| panic!("{}", e));
+
+    let expected = match baseline.get(name) {
+        Some(expected) if !update => expected,
+        _ => {
+            baseline.record(name, compute_units);
+            baseline
+                .save()
+                .unwrap_or_else(|e| panic!("Failed to write baseline file {}: {}", path.display(), e));
+            return;
+        }
+    };
+
+    let allowed = expected as f64 * (1.0 + tolerance_pct / 100.0);
+    if compute_units as f64 > allowed {
+        panic!(
+            "Compute units for {} regressed: {} vs baseline {} (+{:.1}%, tolerance {}%)\nRun with {}=1 to accept the new value.",
+            name,
+            compute_units,
+            expected,
+            (compute_units as f64 / expected.max(1) as f64 - 1.0) * 100.0,
+            tolerance_pct,
+            UPDATE_CU_BASELINE_ENV
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_baseline(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("litesvm-utils-cu-{}-{}.json", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_baseline_roundtrip() {
+        let path = temp_baseline("roundtrip");
+        let mut baseline = CuBaseline::load(&path).unwrap();
+        assert_eq!(baseline.get("swap"), None);
+
+        baseline.record("swap", 12_000);
+        baseline.record("deposit", 5_000);
+        baseline.save().unwrap();
+
+        let loaded = CuBaseline::load(&path).unwrap();
+        assert_eq!(loaded, baseline);
+        assert_eq!(loaded.get("swap"), Some(12_000));
+        // Entries are sorted for stable diffs
+        let contents = std::fs::read_to_string(&path).unwrap();
+        assert!(contents.find("deposit").unwrap() < contents.find("swap").unwrap());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_save_merges_concurrent_entries() {
+        let path = temp_baseline("merge");
+        let mut first = CuBaseline::load(&path).unwrap();
+        let mut second = CuBaseline::load(&path).unwrap();
+
+        first.record("swap", 12_000);
+        first.save().unwrap();
+        second.record("deposit", 5_000);
+        second.save().unwrap();
+
+        let loaded = CuBaseline::load(&path).unwrap();
+        assert_eq!(loaded.get("swap"), Some(12_000));
+        assert_eq!(loaded.get("deposit"), Some(5_000));
+        // No temporary file is left behind
+        let dir = std::fs::read_dir(path.parent().unwrap()).unwrap();
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        assert!(!dir
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.file_name().to_string_lossy().starts_with(&format!("{}.", file_name))));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_check_baseline() {
+        let path = temp_baseline("check");
+
+        // First run records, later runs compare within tolerance
+        check_baseline(&path, "swap", 10_000, 5.0, false);
+        check_baseline(&path, "swap", 10_400, 5.0, false);
+        check_baseline(&path, "swap", 8_000, 5.0, false);
+        assert_eq!(CuBaseline::load(&path).unwrap().get("swap"), Some(10_000));
+
+        check_baseline(&path, "swap", 20_000, 0.0, true);
+        assert_eq!(CuBaseline::load(&path).unwrap().get("swap"), Some(20_000));
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "regressed")]
+    fn test_check_baseline_regression() {
+        let path = temp_baseline("regression");
+        check_baseline(&path, "swap", 10_000, 5.0, false);
+
+        let outcome = std::panic::catch_unwind(|| check_baseline(&path, "swap", 10_600, 5.0, false));
+        std::fs::remove_file(&path).unwrap();
+        std::panic::resume_unwind(outcome.unwrap_err());
+    }
+}
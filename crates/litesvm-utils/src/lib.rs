@@ -152,22 +152,36 @@
 //! ## Modules
 //!
 //! - [`assertions`] - Assertion helper implementations
-//! - [`builder`] - Test environment builders
+//! - [`builder`] - Test environment and account builders
+//! - [`cu_baseline`] - Compute unit regression baselines
+//! - `nft` - Metaplex Token Metadata NFT fixtures (`nft` feature)
 //! - [`test_helpers`] - Test helper implementations
 //! - [`transaction`] - Transaction execution and result analysis
 
 pub mod assertions;
 pub mod builder;
+pub mod cu_baseline;
+#[cfg(feature = "nft")]
+pub mod nft;
 pub mod test_helpers;
 pub mod transaction;
 
 // Re-export main types for convenience
-pub use assertions::AssertionHelpers;
-pub use builder::{LiteSVMBuilder, ProgramTestExt};
-pub use test_helpers::TestHelpers;
-pub use transaction::{TransactionError, TransactionHelpers, TransactionResult};
+pub use assertions::{AssertionHelpers, BalanceTracker};
+pub use builder::{AccountBuilder, LiteSVMBuilder, ProgramTestExt};
+pub use cu_baseline::{CuBaseline, DEFAULT_CU_BASELINE_PATH, UPDATE_CU_BASELINE_ENV};
+pub use test_helpers::{TestHelpers, MS_PER_SLOT};
+pub use transaction::{
+    check_transaction_size, label_addresses, normalize_addresses, normalize_compute_units, parse_call_graph,
+    transaction_fee, tx_size, AccountChange, CpiInstruction, Invocation, TransactionError, TransactionHelpers,
+    TransactionResult, LAMPORTS_PER_SIGNATURE, MAX_TRANSACTION_SIZE, UPDATE_GOLDEN_ENV,
+};
 
 // Re-export commonly used external types
 pub use litesvm::LiteSVM;
+#[cfg(feature = "regex")]
+pub use regex::Regex;
 pub use solana_program::pubkey::Pubkey;
-pub use solana_sdk::signature::Keypair;
\ No newline at end of file
+pub use solana_sdk::signature::Keypair;
+pub use spl_token_2022_interface::extension::ExtensionType;
+pub use spl_token_metadata_interface::state::TokenMetadata;
\ No newline at end of file
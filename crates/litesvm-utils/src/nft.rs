@@ -0,0 +1,837 @@
+//! Metaplex Token Metadata NFT fixtures
+//!
+//! Available with the `nft` feature. The Token Metadata program isn't bundled with
+//! LiteSVM, so load it first with [`NftHelpers::load_token_metadata_program`], for
+//! example from a binary dumped with
+//! `solana program dump metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s mpl_token_metadata.so`.
+//! Instructions are encoded directly, so no Metaplex crate is required.
+//!
+//! Programmable NFTs created with a rule set also need the Token Auth Rules
+//! program at [`TOKEN_AUTH_RULES_PROGRAM_ID`] and an existing rule set account.
+
+use crate::test_helpers::TestHelpers;
+use litesvm::LiteSVM;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use std::error::Error;
+
+/// Address of the Metaplex Token Metadata program
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Address of the Metaplex Token Auth Rules program used by pNFT rule sets
+pub const TOKEN_AUTH_RULES_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
+
+/// `CreateMasterEditionV3` instruction discriminator
+const CREATE_MASTER_EDITION_V3: u8 = 17;
+/// `VerifySizedCollectionItem` instruction discriminator
+const VERIFY_SIZED_COLLECTION_ITEM: u8 = 30;
+/// `CreateMetadataAccountV3` instruction discriminator
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+/// `CreateV1` instruction discriminator
+const CREATE_V1: u8 = 42;
+/// `MintV1` instruction discriminator
+const MINT_V1: u8 = 43;
+/// `TokenStandard::ProgrammableNonFungible`
+const PROGRAMMABLE_NON_FUNGIBLE: u8 = 4;
+
+/// Accounts created for an NFT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NftAccounts {
+    /// The NFT mint (supply 1, mint authority held by the master edition)
+    pub mint: Pubkey,
+    /// The metadata PDA
+    pub metadata: Pubkey,
+    /// The master edition PDA
+    pub master_edition: Pubkey,
+    /// The owner's associated token account holding the NFT
+    pub ata: Pubkey,
+}
+
+/// Accounts created for a programmable NFT (pNFT)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PnftAccounts {
+    /// The pNFT mint (supply 1, mint and freeze authority held by the master edition)
+    pub mint: Pubkey,
+    /// The metadata PDA
+    pub metadata: Pubkey,
+    /// The master edition PDA
+    pub master_edition: Pubkey,
+    /// The owner's associated token account holding the pNFT (frozen by the program)
+    pub ata: Pubkey,
+    /// The token record PDA tracking the token account's state and delegate
+    pub token_record: Pubkey,
+    /// The authorization rule set enforced on transfers, if any
+    pub rule_set: Option<Pubkey>,
+}
+
+/// Derive the metadata PDA of a mint
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Derive the master edition PDA of a mint
+pub fn master_edition_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref(), b"edition"],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Derive the token record PDA of a pNFT token account
+pub fn token_record_address(mint: &Pubkey, token: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            b"token_record",
+            token.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Collection membership recorded in a metadata account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NftCollection {
+    /// Mint of the collection NFT
+    pub key: Pubkey,
+    /// Whether the collection authority has verified the item
+    pub verified: bool,
+}
+
+/// Read the collection field of a metadata account's data
+///
+/// Returns `None` if the NFT has no collection, or an error if the data isn't a
+/// valid metadata account.
+pub fn parse_metadata_collection(data: &[u8]) -> Result<Option<NftCollection>, Box<dyn Error>> {
+    let mut reader = BorshReader { data, offset: 0 };
+    // key, update_authority, mint
+    reader.skip(1 + 32 + 32)?;
+    // name, symbol, uri
+    for _ in 0..3 {
+        let len = reader.read_u32()? as usize;
+        reader.skip(len)?;
+    }
+    // seller_fee_basis_points
+    reader.skip(2)?;
+    if reader.read_option()? {
+        // creators: address, verified, share
+        let len = reader.read_u32()? as usize;
+        reader.skip(len * 34)?;
+    }
+    // primary_sale_happened, is_mutable
+    reader.skip(2)?;
+    // edition_nonce, token_standard
+    for _ in 0..2 {
+        if reader.read_option()? {
+            reader.skip(1)?;
+        }
+    }
+    if !reader.read_option()? {
+        return Ok(None);
+    }
+    let verified = reader.read_u8()? != 0;
+    let key = Pubkey::try_from(reader.take(32)?).map_err(|_| "Invalid collection key")?;
+    Ok(Some(NftCollection { key, verified }))
+}
+
+/// NFT helper methods for LiteSVM
+pub trait NftHelpers {
+    /// Deploy the Token Metadata program at its mainnet address
+    ///
+    /// # Example
+    /// ```ignore
+    /// svm.load_token_metadata_program(include_bytes!("fixtures/mpl_token_metadata.so"))?;
+    /// ```
+    fn load_token_metadata_program(&mut self, program_bytes: &[u8]) -> Result<(), Box<dyn Error>>;
+
+    /// Create a 1/1 NFT owned by `authority`
+    ///
+    /// Creates a zero-decimal mint, mints one token to the authority's ATA, and
+    /// creates the metadata and master edition accounts. `authority` pays for
+    /// everything and is the update authority.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let nft = svm.create_nft(&authority)?;
+    /// svm.assert_token_balance(&nft.ata, 1);
+    /// ```
+    fn create_nft(&mut self, authority: &Keypair) -> Result<NftAccounts, Box<dyn Error>>;
+
+    /// Create a 1/1 NFT with the given name, symbol and URI
+    fn create_nft_with_metadata(
+        &mut self,
+        authority: &Keypair,
+        name: &str,
+        symbol: &str,
+        uri: &str,
+    ) -> Result<NftAccounts, Box<dyn Error>>;
+
+    /// Create a sized collection NFT whose update authority is `authority`
+    ///
+    /// # Example
+    /// ```ignore
+    /// let collection = svm.create_collection(&authority)?;
+    /// let item = svm.mint_nft_into_collection(&authority, &collection.mint)?;
+    /// svm.verify_collection_item(&item.mint, &collection.mint, &authority)?;
+    /// svm.assert_nft_in_collection(&item.mint, &collection.mint);
+    /// ```
+    fn create_collection(&mut self, authority: &Keypair) -> Result<NftAccounts, Box<dyn Error>>;
+
+    /// Create an NFT that belongs to `collection_mint`
+    ///
+    /// The membership is unverified until [`verify_collection_item`](Self::verify_collection_item)
+    /// is called with the collection's update authority.
+    fn mint_nft_into_collection(
+        &mut self,
+        authority: &Keypair,
+        collection_mint: &Pubkey,
+    ) -> Result<NftAccounts, Box<dyn Error>>;
+
+    /// Verify an NFT as a member of a sized collection
+    ///
+    /// `collection_authority` must be the collection's update authority and pays
+    /// the transaction fee.
+    fn verify_collection_item(
+        &mut self,
+        mint: &Pubkey,
+        collection_mint: &Pubkey,
+        collection_authority: &Keypair,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Assert that an NFT is a verified member of a collection
+    fn assert_nft_in_collection(&self, mint: &Pubkey, collection_mint: &Pubkey);
+
+    /// Create a programmable NFT owned by `authority`
+    ///
+    /// Runs `CreateV1` and `MintV1`, which create the mint, metadata, master
+    /// edition, the owner's (frozen) ATA and its token record. Pass `rule_set`
+    /// to enforce an authorization rule set on transfers; it must already exist
+    /// and the Token Auth Rules program must be loaded.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let pnft = svm.create_pnft(&authority, None)?;
+    /// svm.assert_token_balance(&pnft.ata, 1);
+    /// ```
+    fn create_pnft(
+        &mut self,
+        authority: &Keypair,
+        rule_set: Option<&Pubkey>,
+    ) -> Result<PnftAccounts, Box<dyn Error>>;
+}
+
+impl NftHelpers for LiteSVM {
+    fn load_token_metadata_program(&mut self, program_bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.add_program(TOKEN_METADATA_PROGRAM_ID, program_bytes)
+            .map_err(|e| format!("Failed to load Token Metadata program: {:?}", e))?;
+        Ok(())
+    }
+
+    fn create_nft(&mut self, authority: &Keypair) -> Result<NftAccounts, Box<dyn Error>> {
+        self.create_nft_with_metadata(authority, "Test NFT", "TNFT", "")
+    }
+
+    fn create_nft_with_metadata(
+        &mut self,
+        authority: &Keypair,
+        name: &str,
+        symbol: &str,
+        uri: &str,
+    ) -> Result<NftAccounts, Box<dyn Error>> {
+        create_nft_with(
+            self,
+            authority,
+            MetadataArgs { name, symbol, uri, collection: None, is_collection: false },
+        )
+    }
+
+    fn create_collection(&mut self, authority: &Keypair) -> Result<NftAccounts, Box<dyn Error>> {
+        create_nft_with(
+            self,
+            authority,
+            MetadataArgs {
+                name: "Test Collection",
+                symbol: "TCOL",
+                uri: "",
+                collection: None,
+                is_collection: true,
+            },
+        )
+    }
+
+    fn mint_nft_into_collection(
+        &mut self,
+        authority: &Keypair,
+        collection_mint: &Pubkey,
+    ) -> Result<NftAccounts, Box<dyn Error>> {
+        create_nft_with(
+            self,
+            authority,
+            MetadataArgs {
+                name: "Test NFT",
+                symbol: "TNFT",
+                uri: "",
+                collection: Some(*collection_mint),
+                is_collection: false,
+            },
+        )
+    }
+
+    fn verify_collection_item(
+        &mut self,
+        mint: &Pubkey,
+        collection_mint: &Pubkey,
+        collection_authority: &Keypair,
+    ) -> Result<(), Box<dyn Error>> {
+        let ix = verify_sized_collection_item(mint, collection_mint, &collection_authority.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&collection_authority.pubkey()),
+            &[collection_authority],
+            self.latest_blockhash(),
+        );
+
+        self.send_transaction(tx)
+            .map_err(|e| format!("Failed to verify collection item: {:?}", e.err))?;
+        Ok(())
+    }
+
+    fn assert_nft_in_collection(&self, mint: &Pubkey, collection_mint: &Pubkey) {
+        let metadata = metadata_address(mint);
+        let account = self
+            .get_account(&metadata)
+            .unwrap_or_else(|| panic!("Metadata account {} for NFT {} does not exist", metadata, mint));
+        let collection = parse_metadata_collection(&account.data)
+            .unwrap_or_else(|e| panic!("Failed to parse metadata for NFT {}: {}", mint, e));
+
+        match collection {
+            Some(NftCollection { key, verified: true }) if key == *collection_mint => {}
+            Some(NftCollection { key, verified: false }) if key == *collection_mint => {
+                panic!("NFT {} is in collection {} but is not verified", mint, collection_mint)
+            }
+            Some(NftCollection { key, .. }) => {
+                panic!("NFT {} is in collection {}, expected {}", mint, key, collection_mint)
+            }
+            None => panic!("NFT {} has no collection, expected {}", mint, collection_mint),
+        }
+    }
+
+    fn create_pnft(
+        &mut self,
+        authority: &Keypair,
+        rule_set: Option<&Pubkey>,
+    ) -> Result<PnftAccounts, Box<dyn Error>> {
+        if self.get_account(&TOKEN_METADATA_PROGRAM_ID).is_none() {
+            return Err("Token Metadata program is not loaded; call load_token_metadata_program() first".into());
+        }
+
+        let mint = Keypair::new();
+        let ata = spl_associated_token_account::get_associated_token_address(&authority.pubkey(), &mint.pubkey());
+        let pnft = PnftAccounts {
+            mint: mint.pubkey(),
+            metadata: metadata_address(&mint.pubkey()),
+            master_edition: master_edition_address(&mint.pubkey()),
+            ata,
+            token_record: token_record_address(&mint.pubkey(), &ata),
+            rule_set: rule_set.copied(),
+        };
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                create_v1_pnft(&pnft, &authority.pubkey()),
+                mint_v1_pnft(&pnft, &authority.pubkey()),
+            ],
+            Some(&authority.pubkey()),
+            &[authority, &mint],
+            self.latest_blockhash(),
+        );
+
+        self.send_transaction(tx)
+            .map_err(|e| format!("Failed to create pNFT: {:?}", e.err))?;
+        Ok(pnft)
+    }
+}
+
+/// Metadata written by `CreateMetadataAccountV3`
+struct MetadataArgs<'a> {
+    name: &'a str,
+    symbol: &'a str,
+    uri: &'a str,
+    /// Unverified collection membership
+    collection: Option<Pubkey>,
+    /// Create a sized collection NFT
+    is_collection: bool,
+}
+
+/// Create a 1/1 NFT owned by `authority`
+fn create_nft_with(
+    svm: &mut LiteSVM,
+    authority: &Keypair,
+    args: MetadataArgs,
+) -> Result<NftAccounts, Box<dyn Error>> {
+    if svm.get_account(&TOKEN_METADATA_PROGRAM_ID).is_none() {
+        return Err("Token Metadata program is not loaded; call load_token_metadata_program() first".into());
+    }
+
+    let mint = svm.create_token_mint(authority, 0)?.pubkey();
+    let ata = svm.create_associated_token_account(&mint, authority)?;
+    svm.mint_to(&mint, &ata, authority, 1)?;
+
+    let nft = NftAccounts {
+        mint,
+        metadata: metadata_address(&mint),
+        master_edition: master_edition_address(&mint),
+        ata,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            create_metadata_account_v3(&nft, &authority.pubkey(), &args),
+            create_master_edition_v3(&nft, &authority.pubkey(), Some(0)),
+        ],
+        Some(&authority.pubkey()),
+        &[authority],
+        svm.latest_blockhash(),
+    );
+
+    svm.send_transaction(tx)
+        .map_err(|e| format!("Failed to create NFT: {:?}", e.err))?;
+    Ok(nft)
+}
+
+/// Build a `CreateMetadataAccountV3` instruction where `authority` is mint
+/// authority, update authority and payer
+fn create_metadata_account_v3(nft: &NftAccounts, authority: &Pubkey, args: &MetadataArgs) -> Instruction {
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3];
+    // DataV2
+    write_string(&mut data, args.name);
+    write_string(&mut data, args.symbol);
+    write_string(&mut data, args.uri);
+    data.extend_from_slice(&0u16.to_le_bytes()); // seller_fee_basis_points
+    data.push(0); // creators: None
+    match args.collection {
+        Some(key) => {
+            data.extend_from_slice(&[1, 0]); // Some(Collection { verified: false, .. })
+            data.extend_from_slice(key.as_ref());
+        }
+        None => data.push(0),
+    }
+    data.push(0); // uses: None
+    data.push(1); // is_mutable
+    if args.is_collection {
+        // collection_details: Some(V1 { size: 0 })
+        data.extend_from_slice(&[1, 0]);
+        data.extend_from_slice(&0u64.to_le_bytes());
+    } else {
+        data.push(0);
+    }
+
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(nft.metadata, false),
+            AccountMeta::new_readonly(nft.mint, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Build a `CreateMasterEditionV3` instruction where `authority` is mint
+/// authority, update authority and payer
+fn create_master_edition_v3(nft: &NftAccounts, authority: &Pubkey, max_supply: Option<u64>) -> Instruction {
+    let mut data = vec![CREATE_MASTER_EDITION_V3];
+    write_option_u64(&mut data, max_supply);
+
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(nft.master_edition, false),
+            AccountMeta::new(nft.mint, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new(nft.metadata, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Build a `VerifySizedCollectionItem` instruction where `collection_authority` also pays
+fn verify_sized_collection_item(mint: &Pubkey, collection_mint: &Pubkey, collection_authority: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(metadata_address(mint), false),
+            AccountMeta::new_readonly(*collection_authority, true),
+            AccountMeta::new(*collection_authority, true),
+            AccountMeta::new_readonly(*collection_mint, false),
+            AccountMeta::new(metadata_address(collection_mint), false),
+            AccountMeta::new_readonly(master_edition_address(collection_mint), false),
+        ],
+        data: vec![VERIFY_SIZED_COLLECTION_ITEM],
+    }
+}
+
+/// Build a `CreateV1` instruction for a pNFT where `authority` is mint authority,
+/// update authority and payer
+fn create_v1_pnft(pnft: &PnftAccounts, authority: &Pubkey) -> Instruction {
+    let mut data = vec![CREATE_V1, 0]; // CreateArgs::V1
+    // AssetData
+    write_string(&mut data, "Test pNFT");
+    write_string(&mut data, "TPNFT");
+    write_string(&mut data, "");
+    data.extend_from_slice(&0u16.to_le_bytes()); // seller_fee_basis_points
+    data.push(0); // creators: None
+    data.push(0); // primary_sale_happened
+    data.push(1); // is_mutable
+    data.push(PROGRAMMABLE_NON_FUNGIBLE);
+    data.push(0); // collection: None
+    data.push(0); // uses: None
+    data.push(0); // collection_details: None
+    match pnft.rule_set {
+        Some(rule_set) => {
+            data.push(1);
+            data.extend_from_slice(rule_set.as_ref());
+        }
+        None => data.push(0),
+    }
+    data.extend_from_slice(&[1, 0]); // decimals: Some(0)
+    data.extend_from_slice(&[1, 0]); // print_supply: Some(PrintSupply::Zero)
+
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(pnft.metadata, false),
+            AccountMeta::new(pnft.master_edition, false),
+            AccountMeta::new(pnft.mint, true),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new_readonly(solana_sdk_ids::sysvar::instructions::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Build a `MintV1` instruction minting one pNFT to `authority`'s ATA
+///
+/// Omitted optional accounts are passed as the Token Metadata program ID, as
+/// the program expects.
+fn mint_v1_pnft(pnft: &PnftAccounts, authority: &Pubkey) -> Instruction {
+    let mut data = vec![MINT_V1, 0]; // MintArgs::V1
+    data.extend_from_slice(&1u64.to_le_bytes()); // amount
+    data.push(0); // authorization_data: None
+
+    let (rules_program, rules) = match pnft.rule_set {
+        Some(rule_set) => (TOKEN_AUTH_RULES_PROGRAM_ID, rule_set),
+        None => (TOKEN_METADATA_PROGRAM_ID, TOKEN_METADATA_PROGRAM_ID),
+    };
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(pnft.ata, false),
+            AccountMeta::new_readonly(*authority, false),
+            AccountMeta::new_readonly(pnft.metadata, false),
+            AccountMeta::new(pnft.master_edition, false),
+            AccountMeta::new(pnft.token_record, false),
+            AccountMeta::new(pnft.mint, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(TOKEN_METADATA_PROGRAM_ID, false), // delegate_record
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new_readonly(solana_sdk_ids::sysvar::instructions::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(rules_program, false),
+            AccountMeta::new_readonly(rules, false),
+        ],
+        data,
+    }
+}
+
+/// Borsh-encode a string
+fn write_string(data: &mut Vec<u8>, value: &str) {
+    data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    data.extend_from_slice(value.as_bytes());
+}
+
+/// Borsh-encode an optional u64
+fn write_option_u64(data: &mut Vec<u8>, value: Option<u64>) {
+    match value {
+        Some(value) => {
+            data.push(1);
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        None => data.push(0),
+    }
+}
+
+/// Minimal cursor over borsh-encoded account data
+struct BorshReader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> BorshReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Box<dyn Error>> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + len)
+            .ok_or("Unexpected end of metadata account data")?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), Box<dyn Error>> {
+        self.take(len).map(|_| ())
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Box<dyn Error>> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, Box<dyn Error>> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into()?))
+    }
+
+    fn read_option(&mut self) -> Result<bool, Box<dyn Error>> {
+        Ok(self.read_u8()? != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nft_addresses() {
+        let mint = Pubkey::new_unique();
+        let (metadata, _) = Pubkey::find_program_address(
+            &[b"metadata", TOKEN_METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+            &TOKEN_METADATA_PROGRAM_ID,
+        );
+        assert_eq!(metadata_address(&mint), metadata);
+        assert_ne!(master_edition_address(&mint), metadata);
+    }
+
+    #[test]
+    fn test_create_metadata_account_v3_encoding() {
+        let mint = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let nft = NftAccounts {
+            mint,
+            metadata: metadata_address(&mint),
+            master_edition: master_edition_address(&mint),
+            ata: Pubkey::new_unique(),
+        };
+
+        let args = MetadataArgs { name: "NFT", symbol: "N", uri: "u", collection: None, is_collection: false };
+        let ix = create_metadata_account_v3(&nft, &authority, &args);
+        let mut expected = vec![CREATE_METADATA_ACCOUNT_V3];
+        expected.extend_from_slice(&[3, 0, 0, 0, b'N', b'F', b'T']);
+        expected.extend_from_slice(&[1, 0, 0, 0, b'N']);
+        expected.extend_from_slice(&[1, 0, 0, 0, b'u']);
+        expected.extend_from_slice(&[0, 0, 0, 0, 0, 1, 0]);
+        assert_eq!(ix.data, expected);
+        assert_eq!(ix.accounts[0].pubkey, nft.metadata);
+        assert!(ix.accounts[0].is_writable);
+
+        let ix = create_master_edition_v3(&nft, &authority, Some(0));
+        assert_eq!(ix.data, vec![CREATE_MASTER_EDITION_V3, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(ix.accounts[0].pubkey, nft.master_edition);
+    }
+
+    /// Metadata account data as written by the program, up to the collection field
+    fn metadata_data(collection: Option<NftCollection>) -> Vec<u8> {
+        let mut data = vec![4]; // Key::MetadataV1
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        write_string(&mut data, "Test NFT");
+        write_string(&mut data, "TNFT");
+        write_string(&mut data, "");
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&[1, 1, 0, 0, 0]); // one creator
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(&[1, 100]);
+        data.extend_from_slice(&[0, 1]); // primary_sale_happened, is_mutable
+        data.extend_from_slice(&[1, 255]); // edition_nonce
+        data.extend_from_slice(&[1, 0]); // token_standard
+        match collection {
+            Some(collection) => {
+                data.extend_from_slice(&[1, collection.verified as u8]);
+                data.extend_from_slice(collection.key.as_ref());
+            }
+            None => data.push(0),
+        }
+        data.push(0); // uses
+        data
+    }
+
+    #[test]
+    fn test_parse_metadata_collection() {
+        let collection = NftCollection { key: Pubkey::new_unique(), verified: true };
+        assert_eq!(parse_metadata_collection(&metadata_data(Some(collection))).unwrap(), Some(collection));
+        assert_eq!(parse_metadata_collection(&metadata_data(None)).unwrap(), None);
+        assert!(parse_metadata_collection(&[4, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn test_collection_instruction_encoding() {
+        let mint = Pubkey::new_unique();
+        let collection_mint = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let nft = NftAccounts {
+            mint,
+            metadata: metadata_address(&mint),
+            master_edition: master_edition_address(&mint),
+            ata: Pubkey::new_unique(),
+        };
+
+        let args = MetadataArgs {
+            name: "",
+            symbol: "",
+            uri: "",
+            collection: Some(collection_mint),
+            is_collection: false,
+        };
+        let data = create_metadata_account_v3(&nft, &authority, &args).data;
+        assert_eq!(&data[16..18], &[1, 0]);
+        assert_eq!(&data[18..50], collection_mint.as_ref());
+        assert_eq!(&data[50..], &[0, 1, 0]);
+
+        let args = MetadataArgs { collection: None, is_collection: true, ..args };
+        let data = create_metadata_account_v3(&nft, &authority, &args).data;
+        assert_eq!(&data[16..], &[0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let ix = verify_sized_collection_item(&mint, &collection_mint, &authority);
+        assert_eq!(ix.data, vec![VERIFY_SIZED_COLLECTION_ITEM]);
+        assert_eq!(ix.accounts[0].pubkey, nft.metadata);
+        assert_eq!(ix.accounts[4].pubkey, metadata_address(&collection_mint));
+        assert_eq!(ix.accounts[5].pubkey, master_edition_address(&collection_mint));
+    }
+
+    #[test]
+    fn test_pnft_instruction_encoding() {
+        let mint = Pubkey::new_unique();
+        let authority = Pubkey::new_unique();
+        let ata = spl_associated_token_account::get_associated_token_address(&authority, &mint);
+        let rule_set = Pubkey::new_unique();
+        let pnft = PnftAccounts {
+            mint,
+            metadata: metadata_address(&mint),
+            master_edition: master_edition_address(&mint),
+            ata,
+            token_record: token_record_address(&mint, &ata),
+            rule_set: Some(rule_set),
+        };
+        assert_ne!(pnft.token_record, token_record_address(&mint, &Pubkey::new_unique()));
+
+        let ix = create_v1_pnft(&pnft, &authority);
+        assert_eq!(&ix.data[..2], &[CREATE_V1, 0]);
+        // name, symbol and uri are followed by fee, creators, sale flag, mutability, standard
+        let asset_start = 2 + 4 + 9 + 4 + 5 + 4;
+        assert_eq!(
+            &ix.data[asset_start..asset_start + 9],
+            &[0, 0, 0, 0, 1, PROGRAMMABLE_NON_FUNGIBLE, 0, 0, 0]
+        );
+        assert_eq!(&ix.data[asset_start + 9..asset_start + 10], &[1]);
+        assert_eq!(&ix.data[asset_start + 10..asset_start + 42], rule_set.as_ref());
+        assert_eq!(&ix.data[asset_start + 42..], &[1, 0, 1, 0]);
+        assert!(ix.accounts[2].is_signer);
+
+        let ix = mint_v1_pnft(&pnft, &authority);
+        assert_eq!(ix.data, vec![MINT_V1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(ix.accounts[4].pubkey, pnft.token_record);
+        assert_eq!(ix.accounts[13].pubkey, TOKEN_AUTH_RULES_PROGRAM_ID);
+        assert_eq!(ix.accounts[14].pubkey, rule_set);
+
+        let ix = mint_v1_pnft(&PnftAccounts { rule_set: None, ..pnft }, &authority);
+        assert_eq!(ix.accounts[13].pubkey, TOKEN_METADATA_PROGRAM_ID);
+        assert_eq!(ix.accounts[14].pubkey, TOKEN_METADATA_PROGRAM_ID);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn test_assert_nft_in_collection_missing_metadata() {
+        let svm = LiteSVM::new();
+        svm.assert_nft_in_collection(&Pubkey::new_unique(), &Pubkey::new_unique());
+    }
+
+    #[test]
+    fn test_create_nft_requires_program() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let err = svm.create_nft(&authority).unwrap_err();
+        assert!(err.to_string().contains("load_token_metadata_program"));
+    }
+
+    #[test]
+    #[ignore = "requires MPL_TOKEN_METADATA_SO pointing at a Token Metadata program binary"]
+    fn test_create_nft() {
+        let program = std::fs::read(std::env::var("MPL_TOKEN_METADATA_SO").unwrap()).unwrap();
+        let mut svm = LiteSVM::new();
+        svm.load_token_metadata_program(&program).unwrap();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let nft = svm.create_nft(&authority).unwrap();
+
+        let metadata = svm.get_account(&nft.metadata).unwrap();
+        assert_eq!(metadata.owner, TOKEN_METADATA_PROGRAM_ID);
+        assert!(svm.get_account(&nft.master_edition).is_some());
+        crate::AssertionHelpers::assert_token_balance(&svm, &nft.ata, 1);
+    }
+
+    #[test]
+    #[ignore = "requires MPL_TOKEN_METADATA_SO pointing at a Token Metadata program binary"]
+    fn test_collection_flow() {
+        let program = std::fs::read(std::env::var("MPL_TOKEN_METADATA_SO").unwrap()).unwrap();
+        let mut svm = LiteSVM::new();
+        svm.load_token_metadata_program(&program).unwrap();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let collection = svm.create_collection(&authority).unwrap();
+        let item = svm.mint_nft_into_collection(&authority, &collection.mint).unwrap();
+        let metadata = svm.get_account(&item.metadata).unwrap();
+        assert_eq!(
+            parse_metadata_collection(&metadata.data).unwrap(),
+            Some(NftCollection { key: collection.mint, verified: false })
+        );
+
+        svm.verify_collection_item(&item.mint, &collection.mint, &authority).unwrap();
+        svm.assert_nft_in_collection(&item.mint, &collection.mint);
+    }
+
+    #[test]
+    #[ignore = "requires MPL_TOKEN_METADATA_SO pointing at a Token Metadata program binary"]
+    fn test_create_pnft() {
+        let program = std::fs::read(std::env::var("MPL_TOKEN_METADATA_SO").unwrap()).unwrap();
+        let mut svm = LiteSVM::new();
+        svm.load_token_metadata_program(&program).unwrap();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let pnft = svm.create_pnft(&authority, None).unwrap();
+
+        crate::AssertionHelpers::assert_token_balance(&svm, &pnft.ata, 1);
+        let token_record = svm.get_account(&pnft.token_record).unwrap();
+        assert_eq!(token_record.owner, TOKEN_METADATA_PROGRAM_ID);
+    }
+}
@@ -3,12 +3,30 @@
 //! This module provides convenient methods for creating and managing test accounts,
 //! token mints, and associated token accounts.
 
+use crate::assertions::AssertionHelpers;
 use litesvm::LiteSVM;
 use solana_program::pubkey::Pubkey;
+use solana_program::slot_hashes::SlotHashes;
+use solana_program_pack::Pack;
+use solana_nonce::{state::State as NonceState, versions::Versions as NonceVersions};
+use solana_sdk::account::Account;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
 use solana_sdk::signature::{Keypair, Signer};
 use solana_sdk::transaction::Transaction;
-use spl_associated_token_account::get_associated_token_address;
+use solana_stake_interface::state::{Authorized, Lockup, StakeStateV2};
+use solana_vote_interface::state::{VoteInit, VoteStateV3, VoteStateVersions};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use spl_token_2022_interface::extension::interest_bearing_mint::{self, InterestBearingConfig};
+use spl_token_2022_interface::extension::transfer_fee::{self, TransferFeeAmount};
+use spl_token_2022_interface::extension::metadata_pointer;
+use spl_token_2022_interface::extension::{
+    BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions, StateWithExtensionsMut,
+};
+use spl_token_2022_interface::state::{Account as Token2022Account, Mint as Token2022Mint};
+use spl_token_metadata_interface::state::TokenMetadata;
 use std::error::Error;
+use std::time::Duration;
 
 /// Test helper methods for LiteSVM
 pub trait TestHelpers {
@@ -39,6 +57,28 @@ pub trait TestHelpers {
         lamports: u64,
     ) -> Result<Vec<Keypair>, Box<dyn Error>>;
 
+    /// Create many funded keypairs by writing accounts directly into the SVM
+    ///
+    /// Unlike [`create_funded_accounts`](TestHelpers::create_funded_accounts), this
+    /// skips normal transaction processing (no airdrop transactions, no fees, no
+    /// signature checks) and stores system-owned accounts directly. This is much
+    /// faster for stress tests that need thousands of accounts.
+    ///
+    /// # Panics
+    ///
+    /// Panics if LiteSVM rejects writing an account. This shouldn't happen for
+    /// the plain system-owned accounts created here, so no `Result` is returned.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # let mut svm = LiteSVM::new();
+    /// let accounts = svm.bulk_create_funded_accounts(10_000, 1_000_000_000);
+    /// assert_eq!(accounts.len(), 10_000);
+    /// ```
+    fn bulk_create_funded_accounts(&mut self, count: usize, lamports: u64) -> Vec<Keypair>;
+
     /// Create and initialize a token mint
     ///
     /// # Example
@@ -56,6 +96,53 @@ pub trait TestHelpers {
         decimals: u8,
     ) -> Result<Keypair, Box<dyn Error>>;
 
+    /// Create and initialize a token mint with a freeze authority
+    ///
+    /// [`create_token_mint`](TestHelpers::create_token_mint) leaves the freeze
+    /// authority unset; use this when token accounts need to be frozen.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// let mint = svm
+    ///     .create_token_mint_with_freeze_authority(&authority, 9, &authority.pubkey())
+    ///     .unwrap();
+    /// ```
+    fn create_token_mint_with_freeze_authority(
+        &mut self,
+        authority: &Keypair,
+        decimals: u8,
+        freeze_authority: &Pubkey,
+    ) -> Result<Keypair, Box<dyn Error>>;
+
+    /// Create and initialize a token mint whose mint authority is a PDA
+    ///
+    /// The PDA doesn't need to sign; it is only recorded as the mint authority, so
+    /// the owning program can later mint with `invoke_signed`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let payer = Keypair::new();
+    /// # let program_id = Pubkey::new_unique();
+    /// let mint_authority = svm.get_pda(&[b"mint_authority"], &program_id);
+    /// let mint = svm.create_mint_with_pda_authority(&payer, &mint_authority, 6).unwrap();
+    /// ```
+    fn create_mint_with_pda_authority(
+        &mut self,
+        payer: &Keypair,
+        authority_pda: &Pubkey,
+        decimals: u8,
+    ) -> Result<Keypair, Box<dyn Error>>;
+
     /// Create a token account for a mint
     ///
     /// # Example
@@ -92,6 +179,57 @@ pub trait TestHelpers {
         owner: &Keypair,
     ) -> Result<Pubkey, Box<dyn Error>>;
 
+    /// Create an associated token account for an owner that doesn't sign
+    ///
+    /// The `payer` funds and signs the transaction, while `owner` is only a pubkey.
+    /// This allows creating ATAs for PDAs and wallets whose keys aren't available.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let payer = Keypair::new();
+    /// # let mint = Keypair::new();
+    /// # let vault_pda = Pubkey::new_unique();
+    /// let ata = svm.create_ata_for(&payer, &vault_pda, &mint.pubkey()).unwrap();
+    /// ```
+    fn create_ata_for(
+        &mut self,
+        payer: &Keypair,
+        owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey, Box<dyn Error>>;
+
+    /// Create an associated token account if it doesn't already exist
+    ///
+    /// Uses the associated token program's idempotent instruction, so calling this
+    /// for an existing ATA succeeds and returns the same address. `owner` can be any
+    /// pubkey, including a PDA, and the token program is taken from the mint.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let payer = Keypair::new();
+    /// # let mint = Keypair::new();
+    /// # let vault_pda = Pubkey::new_unique();
+    /// let ata = svm.create_ata_idempotent(&payer, &vault_pda, &mint.pubkey()).unwrap();
+    /// // Safe to call again
+    /// assert_eq!(svm.create_ata_idempotent(&payer, &vault_pda, &mint.pubkey()).unwrap(), ata);
+    /// ```
+    fn create_ata_idempotent(
+        &mut self,
+        payer: &Keypair,
+        owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey, Box<dyn Error>>;
+
     /// Mint tokens to an account
     ///
     /// # Example
@@ -114,46 +252,112 @@ pub trait TestHelpers {
         amount: u64,
     ) -> Result<(), Box<dyn Error>>;
 
-    /// Derive a program-derived address
+    /// Create and initialize a mint owned by the given token program
+    ///
+    /// Works with both SPL Token and Token-2022, so the same test can run against
+    /// either program. For Token-2022 mints with extensions use
+    /// [`create_token2022_mint`](TestHelpers::create_token2022_mint).
     ///
     /// # Example
     /// ```no_run
     /// # use litesvm_utils::TestHelpers;
     /// # use litesvm::LiteSVM;
-    /// # use solana_program::pubkey::Pubkey;
-    /// # let svm = LiteSVM::new();
-    /// # let program_id = Pubkey::new_unique();
-    /// let (pda, bump) = svm.derive_pda(&[b"seed"], &program_id);
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// for token_program in [spl_token::id(), spl_token_2022_interface::id()] {
+    ///     let mint = svm.create_token_mint_with_program(&token_program, &authority, 9).unwrap();
+    /// }
     /// ```
-    fn derive_pda(&self, seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8);
+    fn create_token_mint_with_program(
+        &mut self,
+        token_program_id: &Pubkey,
+        authority: &Keypair,
+        decimals: u8,
+    ) -> Result<Keypair, Box<dyn Error>>;
 
-    /// Get a program-derived address (convenience wrapper for Pubkey::find_program_address)
+    /// Create an associated token account for a mint of the given token program
+    fn create_associated_token_account_with_program(
+        &mut self,
+        token_program_id: &Pubkey,
+        mint: &Pubkey,
+        owner: &Keypair,
+    ) -> Result<Pubkey, Box<dyn Error>>;
+
+    /// Create an associated token account for a non-signing owner and a mint of the given token program
+    fn create_ata_for_with_program(
+        &mut self,
+        token_program_id: &Pubkey,
+        payer: &Keypair,
+        owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey, Box<dyn Error>>;
+
+    /// Mint tokens to an account using the given token program
+    fn mint_to_with_program(
+        &mut self,
+        token_program_id: &Pubkey,
+        mint: &Pubkey,
+        account: &Pubkey,
+        authority: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Create and initialize a Token-2022 mint with extensions
     ///
-    /// This is a more convenient version that returns just the PDA without the bump.
-    /// Use this when you don't need the bump seed.
+    /// The account is sized for the given extensions, and each extension is
+    /// initialized before the mint itself. Extensions that take configuration use
+    /// `authority` for every authority and neutral defaults otherwise: zero
+    /// transfer fees, a zero interest rate, a multiplier of 1, no transfer hook
+    /// program, and pointers to the mint itself. Account-only and confidential
+    /// extensions are not supported.
     ///
     /// # Example
     /// ```no_run
     /// # use litesvm_utils::TestHelpers;
     /// # use litesvm::LiteSVM;
-    /// # use solana_program::pubkey::Pubkey;
-    /// # use solana_sdk::signature::{Keypair, Signer};
-    /// # let svm = LiteSVM::new();
-    /// # let program_id = Pubkey::new_unique();
-    /// # let maker = Keypair::new();
-    /// # let seed = 42u64;
-    /// // Simple usage with multiple seeds
-    /// let escrow_pda = svm.get_pda(
-    ///     &[b"escrow", maker.pubkey().as_ref(), &seed.to_le_bytes()],
-    ///     &program_id
-    /// );
+    /// # use solana_sdk::signature::Keypair;
+    /// # use spl_token_2022_interface::extension::ExtensionType;
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// let mint = svm
+    ///     .create_token2022_mint(&authority, 6, &[ExtensionType::MintCloseAuthority])
+    ///     .unwrap();
     /// ```
-    fn get_pda(&self, seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
-        let (pda, _bump) = self.derive_pda(seeds, program_id);
-        pda
-    }
+    fn create_token2022_mint(
+        &mut self,
+        authority: &Keypair,
+        decimals: u8,
+        extensions: &[ExtensionType],
+    ) -> Result<Keypair, Box<dyn Error>>;
 
-    /// Get a program-derived address with bump (alias for derive_pda for consistency)
+    /// Create a Token-2022 mint with the transfer-fee extension
+    ///
+    /// `authority` is the mint authority as well as the fee config and
+    /// withdraw-withheld authority. Transfers are charged `fee_bps` basis points,
+    /// capped at `max_fee`, and the fee is withheld in the recipient account.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// // 1% fee, at most 5 tokens per transfer
+    /// let mint = svm.create_mint_with_transfer_fee(&authority, 6, 100, 5_000_000).unwrap();
+    /// ```
+    fn create_mint_with_transfer_fee(
+        &mut self,
+        authority: &Keypair,
+        decimals: u8,
+        fee_bps: u16,
+        max_fee: u64,
+    ) -> Result<Keypair, Box<dyn Error>>;
+
+    /// Get the transfer fees withheld in a Token-2022 token account
+    ///
+    /// Returns an error if the account doesn't exist or has no transfer-fee extension.
     ///
     /// # Example
     /// ```no_run
@@ -161,176 +365,1480 @@ pub trait TestHelpers {
     /// # use litesvm::LiteSVM;
     /// # use solana_program::pubkey::Pubkey;
     /// # let svm = LiteSVM::new();
-    /// # let program_id = Pubkey::new_unique();
-    /// let (pda, bump) = svm.get_pda_with_bump(&[b"seed"], &program_id);
+    /// # let recipient_ata = Pubkey::new_unique();
+    /// assert_eq!(svm.get_withheld_fees(&recipient_ata).unwrap(), 100);
     /// ```
-    fn get_pda_with_bump(&self, seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
-        self.derive_pda(seeds, program_id)
-    }
+    fn get_withheld_fees(&self, token_account: &Pubkey) -> Result<u64, Box<dyn Error>>;
 
-    /// Get the current slot
-    fn get_current_slot(&self) -> u64;
-
-    /// Advance the slot by a specified amount
-    fn advance_slot(&mut self, slots: u64);
-}
+    /// Create a Token-2022 mint with the interest-bearing extension
+    ///
+    /// `rate` is the annual interest rate in basis points, and `authority` is both
+    /// the mint authority and the rate authority. Interest only changes the UI
+    /// amount; raw token balances stay the same.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// // 5% APR
+    /// let mint = svm.create_interest_bearing_mint(&authority, 6, 500).unwrap();
+    /// ```
+    fn create_interest_bearing_mint(
+        &mut self,
+        authority: &Keypair,
+        decimals: u8,
+        rate: i16,
+    ) -> Result<Keypair, Box<dyn Error>>;
 
-impl TestHelpers for LiteSVM {
-    fn create_funded_account(&mut self, lamports: u64) -> Result<Keypair, Box<dyn Error>> {
-        let keypair = Keypair::new();
-        self.airdrop(&keypair.pubkey(), lamports)
-            .map_err(|e| format!("Failed to airdrop: {:?}", e))?;
-        Ok(keypair)
-    }
+    /// Get the UI amount of an interest-bearing token account at the current clock time
+    ///
+    /// Returns an error if the account or its mint doesn't exist, or the mint has
+    /// no interest-bearing extension.
+    fn get_accrued_ui_amount(&self, token_account: &Pubkey) -> Result<String, Box<dyn Error>>;
 
-    fn create_funded_accounts(
+    /// Advance the clock by `seconds` and return the accrued UI amount of a token account
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let ata = Pubkey::new_unique();
+    /// // One year later, 1 token at 5% APR (continuously compounded)
+    /// let ui_amount = svm.accrued_ui_amount_after(&ata, 31_556_736).unwrap();
+    /// assert_eq!(ui_amount, "1.051271");
+    /// ```
+    fn accrued_ui_amount_after(
         &mut self,
-        count: usize,
-        lamports: u64,
-    ) -> Result<Vec<Keypair>, Box<dyn Error>> {
-        let mut accounts = Vec::with_capacity(count);
-        for _ in 0..count {
-            accounts.push(self.create_funded_account(lamports)?);
-        }
-        Ok(accounts)
-    }
+        token_account: &Pubkey,
+        seconds: i64,
+    ) -> Result<String, Box<dyn Error>>;
 
-    fn create_token_mint(
+    /// Create a Token-2022 mint with on-mint token metadata
+    ///
+    /// The mint gets the metadata-pointer extension pointing at itself, and its
+    /// TokenMetadata is set to `name`, `symbol` and `uri`. `authority` is the mint
+    /// authority and the metadata update authority.
+    ///
+    /// The metadata is written directly into the mint account, so Token-2022's
+    /// metadata Initialize instruction is not exercised. The Token-2022 binary
+    /// bundled with LiteSVM fails that instruction with `InvalidRealloc` under the
+    /// default feature set (SIMD-0219 stricter ABI constraints), even when the
+    /// mint is funded for its final size. Later metadata instructions, such as
+    /// `UpdateField`, run through the program as usual.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// let mint = svm
+    ///     .create_mint_with_metadata(&authority, 0, "Test NFT", "TNFT", "https://example.com/nft.json")
+    ///     .unwrap();
+    /// ```
+    fn create_mint_with_metadata(
         &mut self,
         authority: &Keypair,
         decimals: u8,
-    ) -> Result<Keypair, Box<dyn Error>> {
-        let mint = Keypair::new();
-
-        // Calculate rent for mint account
-        let rent = self.minimum_balance_for_rent_exemption(82);
+        name: &str,
+        symbol: &str,
+        uri: &str,
+    ) -> Result<Keypair, Box<dyn Error>>;
 
-        // Create mint account
-        let create_account_ix = solana_system_interface::instruction::create_account(
-            &authority.pubkey(),
-            &mint.pubkey(),
-            rent,
-            82,
-            &spl_token::id(),
-        );
+    /// Read the TokenMetadata stored on a Token-2022 mint
+    ///
+    /// Returns an error if the mint doesn't exist or has no metadata.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let svm = LiteSVM::new();
+    /// # let mint = Pubkey::new_unique();
+    /// let metadata = svm.get_token_metadata(&mint).unwrap();
+    /// assert_eq!(metadata.symbol, "TNFT");
+    /// ```
+    fn get_token_metadata(&self, mint: &Pubkey) -> Result<TokenMetadata, Box<dyn Error>>;
 
-        // Initialize mint
-        let init_mint_ix = spl_token::instruction::initialize_mint(
-            &spl_token::id(),
-            &mint.pubkey(),
-            &authority.pubkey(),
-            None,
-            decimals,
-        )?;
+    /// Create an associated token account for a Token-2022 mint
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let owner = Keypair::new();
+    /// # let mint = Keypair::new();
+    /// let ata = svm.create_token2022_ata(&mint.pubkey(), &owner).unwrap();
+    /// ```
+    fn create_token2022_ata(
+        &mut self,
+        mint: &Pubkey,
+        owner: &Keypair,
+    ) -> Result<Pubkey, Box<dyn Error>>;
 
-        // Send transaction
-        let tx = Transaction::new_signed_with_payer(
-            &[create_account_ix, init_mint_ix],
-            Some(&authority.pubkey()),
-            &[authority, &mint],
-            self.latest_blockhash(),
-        );
+    /// Mint Token-2022 tokens to an account
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let mint = Keypair::new();
+    /// # let token_account = Pubkey::new_unique();
+    /// # let authority = Keypair::new();
+    /// svm.mint_to_token2022(&mint.pubkey(), &token_account, &authority, 1_000_000).unwrap();
+    /// ```
+    fn mint_to_token2022(
+        &mut self,
+        mint: &Pubkey,
+        account: &Pubkey,
+        authority: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Transfer tokens between token accounts
+    ///
+    /// Uses `TransferChecked` with the mint and decimals read from the source
+    /// account, so it works for SPL Token and Token-2022 (including transfer-fee
+    /// mints). `owner` is the owner or approved delegate of `source`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::Keypair;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let (sender_ata, receiver_ata) = (Pubkey::new_unique(), Pubkey::new_unique());
+    /// # let sender = Keypair::new();
+    /// svm.transfer_tokens(&sender_ata, &receiver_ata, &sender, 500).unwrap();
+    /// ```
+    fn transfer_tokens(
+        &mut self,
+        source: &Pubkey,
+        destination: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Burn tokens from a token account
+    ///
+    /// `owner` is the owner or approved delegate of `account`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::Keypair;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let ata = Pubkey::new_unique();
+    /// # let owner = Keypair::new();
+    /// svm.burn_tokens(&ata, &owner, 100).unwrap();
+    /// ```
+    fn burn_tokens(
+        &mut self,
+        account: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Approve a delegate to transfer or burn up to `amount` tokens from an account
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::Keypair;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let (ata, vault_authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+    /// # let owner = Keypair::new();
+    /// svm.approve_delegate(&ata, &vault_authority, &owner, 1_000).unwrap();
+    /// ```
+    fn approve_delegate(
+        &mut self,
+        account: &Pubkey,
+        delegate: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Revoke the delegate of a token account
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::Keypair;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let ata = Pubkey::new_unique();
+    /// # let owner = Keypair::new();
+    /// svm.revoke_delegate(&ata, &owner).unwrap();
+    /// ```
+    fn revoke_delegate(&mut self, account: &Pubkey, owner: &Keypair) -> Result<(), Box<dyn Error>>;
+
+    /// Freeze a token account using the mint's freeze authority
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::Keypair;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let ata = Pubkey::new_unique();
+    /// # let freeze_authority = Keypair::new();
+    /// svm.freeze_token_account(&ata, &freeze_authority).unwrap();
+    /// ```
+    fn freeze_token_account(
+        &mut self,
+        account: &Pubkey,
+        freeze_authority: &Keypair,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Thaw a frozen token account using the mint's freeze authority
+    fn thaw_token_account(
+        &mut self,
+        account: &Pubkey,
+        freeze_authority: &Keypair,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Change or remove an authority of a mint or token account
+    ///
+    /// `target` is a mint for `MintTokens`/`FreezeAccount` and a token account for
+    /// `AccountOwner`/`CloseAccount`. Passing `None` as `new_authority` removes the
+    /// authority, e.g. to burn the mint authority. Works for SPL Token and
+    /// Token-2022 targets.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # use solana_program::pubkey::Pubkey;
+    /// # use spl_token::instruction::AuthorityType;
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// # let mint = Keypair::new();
+    /// # let program_id = Pubkey::new_unique();
+    /// // Hand the mint authority over to a program PDA
+    /// let mint_authority = svm.get_pda(&[b"mint_authority"], &program_id);
+    /// svm.set_token_authority(&mint.pubkey(), AuthorityType::MintTokens, &authority, Some(&mint_authority))
+    ///     .unwrap();
+    /// ```
+    fn set_token_authority(
+        &mut self,
+        target: &Pubkey,
+        authority_type: spl_token::instruction::AuthorityType,
+        current_authority: &Keypair,
+        new_authority: Option<&Pubkey>,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Create an SPL Token multisig account requiring `m` of `signers`
+    ///
+    /// The returned multisig address can be used as a mint authority or token
+    /// account owner. `payer` funds the account.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let payer = Keypair::new();
+    /// # let (alice, bob, carol) = (Keypair::new(), Keypair::new(), Keypair::new());
+    /// let multisig = svm
+    ///     .create_token_multisig(&payer, &[alice.pubkey(), bob.pubkey(), carol.pubkey()], 2)
+    ///     .unwrap();
+    /// ```
+    fn create_token_multisig(
+        &mut self,
+        payer: &Keypair,
+        signers: &[Pubkey],
+        m: u8,
+    ) -> Result<Keypair, Box<dyn Error>>;
+
+    /// Mint tokens with a multisig mint authority
+    ///
+    /// `signers` must include at least `m` of the multisig's signers. The first
+    /// signer pays the transaction fee.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let mut svm = LiteSVM::new();
+    /// # let (mint, ata, multisig) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    /// # let (alice, bob) = (Keypair::new(), Keypair::new());
+    /// svm.mint_to_multisig(&mint, &ata, &multisig, &[&alice, &bob], 1_000).unwrap();
+    /// ```
+    fn mint_to_multisig(
+        &mut self,
+        mint: &Pubkey,
+        account: &Pubkey,
+        multisig: &Pubkey,
+        signers: &[&Keypair],
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Transfer tokens out of an account owned by a multisig
+    ///
+    /// `signers` must include at least `m` of the multisig's signers. The first
+    /// signer pays the transaction fee.
+    fn transfer_multisig(
+        &mut self,
+        source: &Pubkey,
+        destination: &Pubkey,
+        multisig: &Pubkey,
+        signers: &[&Keypair],
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Smoke-test the token stack with an end-to-end SPL transfer
+    ///
+    /// Creates two funded users with associated token accounts for `mint`, mints
+    /// `amount` to the first, transfers it to the second, and asserts both balances.
+    /// `mint_authority` must be the mint authority of `mint` and also pays for setup.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any step fails or a balance doesn't match
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// let mint = svm.create_token_mint(&authority, 9).unwrap();
+    /// svm.assert_transfer_works(&mint.pubkey(), &authority, 1_000_000);
+    /// ```
+    fn assert_transfer_works(&mut self, mint: &Pubkey, mint_authority: &Keypair, amount: u64);
+
+    /// Create and initialize a durable nonce account
+    ///
+    /// The authority funds the account and is set as the nonce authority.
+    ///
+    /// # Returns
+    ///
+    /// The nonce account keypair and its current durable nonce
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// let (nonce_account, nonce) = svm.create_nonce_account(&authority).unwrap();
+    /// ```
+    fn create_nonce_account(
+        &mut self,
+        authority: &Keypair,
+    ) -> Result<(Keypair, Hash), Box<dyn Error>>;
+
+    /// Read the current durable nonce stored in a nonce account
+    fn get_durable_nonce(&self, nonce_account: &Pubkey) -> Result<Hash, Box<dyn Error>>;
+
+    /// Advance a durable nonce account, invalidating transactions signed with its current nonce
+    ///
+    /// The blockhash is expired first so the nonce can always be advanced.
+    /// `authority` must be the nonce authority and pays the transaction fee.
+    ///
+    /// # Returns
+    ///
+    /// The new durable nonce
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// let (nonce_account, nonce) = svm.create_nonce_account(&authority).unwrap();
+    /// let advanced = svm.advance_nonce_account(&nonce_account.pubkey(), &authority).unwrap();
+    /// assert_ne!(advanced, nonce);
+    /// ```
+    fn advance_nonce_account(&mut self, nonce_account: &Pubkey, authority: &Keypair) -> Result<Hash, Box<dyn Error>>;
+
+    /// Create a minimal vote account for stake delegation
+    ///
+    /// The account is written directly with an initialized vote state whose node
+    /// identity, voter and withdrawer are all `authority`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let validator = Keypair::new();
+    /// let vote_account = svm.create_vote_account(&validator.pubkey()).unwrap();
+    /// ```
+    fn create_vote_account(&mut self, authority: &Pubkey) -> Result<Pubkey, Box<dyn Error>>;
+
+    /// Create and initialize a stake account funded with `lamports`
+    ///
+    /// The staker pays for the account and is set as both stake and withdraw
+    /// authority. `lamports` includes the rent-exempt reserve, so it must exceed
+    /// the reserve by at least the minimum delegation before delegating.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let staker = Keypair::new();
+    /// # let validator = Keypair::new();
+    /// let stake = svm.create_stake_account(&staker, 2_000_000_000).unwrap();
+    /// let vote_account = svm.create_vote_account(&validator.pubkey()).unwrap();
+    /// svm.delegate_stake(&stake.pubkey(), &vote_account, &staker).unwrap();
+    /// ```
+    fn create_stake_account(&mut self, staker: &Keypair, lamports: u64) -> Result<Keypair, Box<dyn Error>>;
+
+    /// Delegate a stake account to a vote account
+    fn delegate_stake(
+        &mut self,
+        stake: &Pubkey,
+        vote_account: &Pubkey,
+        staker: &Keypair,
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Deactivate a delegated stake account
+    fn deactivate_stake(&mut self, stake: &Pubkey, staker: &Keypair) -> Result<(), Box<dyn Error>>;
+
+    /// Read the state of a stake account
+    fn get_stake_state(&self, stake: &Pubkey) -> Result<StakeStateV2, Box<dyn Error>>;
+
+    /// Create an address lookup table holding `addresses`
+    ///
+    /// The authority pays for and owns the table. The slot is advanced by one
+    /// afterwards so the addresses can be used by v0 transactions right away.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::{TestHelpers, TransactionHelpers};
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::instruction::Instruction;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let authority = Keypair::new();
+    /// # let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+    /// # let accounts: Vec<Pubkey> = vec![];
+    /// let table = svm.create_lookup_table(&authority, &accounts).unwrap();
+    /// let result = svm.send_v0_instruction(ix, &[&authority], &[table]).unwrap();
+    /// ```
+    fn create_lookup_table(&mut self, authority: &Keypair, addresses: &[Pubkey]) -> Result<Pubkey, Box<dyn Error>>;
+
+    /// Append addresses to an address lookup table
+    ///
+    /// Addresses are added in batches to stay within the transaction size limit,
+    /// and the slot is advanced by one so they are active immediately.
+    fn extend_lookup_table(
+        &mut self,
+        lookup_table: &Pubkey,
+        authority: &Keypair,
+        addresses: &[Pubkey],
+    ) -> Result<(), Box<dyn Error>>;
+
+    /// Derive a program-derived address
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let svm = LiteSVM::new();
+    /// # let program_id = Pubkey::new_unique();
+    /// let (pda, bump) = svm.derive_pda(&[b"seed"], &program_id);
+    /// ```
+    fn derive_pda(&self, seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8);
+
+    /// Get a program-derived address (convenience wrapper for Pubkey::find_program_address)
+    ///
+    /// This is a more convenient version that returns just the PDA without the bump.
+    /// Use this when you don't need the bump seed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let svm = LiteSVM::new();
+    /// # let program_id = Pubkey::new_unique();
+    /// # let maker = Keypair::new();
+    /// # let seed = 42u64;
+    /// // Simple usage with multiple seeds
+    /// let escrow_pda = svm.get_pda(
+    ///     &[b"escrow", maker.pubkey().as_ref(), &seed.to_le_bytes()],
+    ///     &program_id
+    /// );
+    /// ```
+    fn get_pda(&self, seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+        let (pda, _bump) = self.derive_pda(seeds, program_id);
+        pda
+    }
+
+    /// Get a program-derived address with bump (alias for derive_pda for consistency)
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # let svm = LiteSVM::new();
+    /// # let program_id = Pubkey::new_unique();
+    /// let (pda, bump) = svm.get_pda_with_bump(&[b"seed"], &program_id);
+    /// ```
+    fn get_pda_with_bump(&self, seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+        self.derive_pda(seeds, program_id)
+    }
+
+    /// Get the current slot
+    fn get_current_slot(&self) -> u64;
+
+    /// Advance the slot by a specified amount
+    fn advance_slot(&mut self, slots: u64);
+
+    /// Set the Clock sysvar's unix timestamp
+    ///
+    /// Moving forward also advances the slot proportionally, at
+    /// [`MS_PER_SLOT`] milliseconds per slot, so slot- and time-based logic stay
+    /// consistent. Moving backward only rewrites the timestamp.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # let mut svm = LiteSVM::new();
+    /// // Jump past a vesting cliff
+    /// svm.set_unix_timestamp(1_735_689_600);
+    /// ```
+    fn set_unix_timestamp(&mut self, unix_timestamp: i64);
+
+    /// Advance the Clock sysvar's unix timestamp, and the slot proportionally
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use std::time::Duration;
+    /// # let mut svm = LiteSVM::new();
+    /// // Let the auction end
+    /// svm.advance_time(Duration::from_secs(24 * 60 * 60));
+    /// ```
+    fn advance_time(&mut self, duration: Duration);
+
+    /// Warp to the first slot of an epoch
+    ///
+    /// Uses the EpochSchedule sysvar to find the slot, so custom schedules are
+    /// respected. The Clock's epoch fields are updated along with the slot, and
+    /// the unix timestamp advances proportionally when moving forward.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TestHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # let mut svm = LiteSVM::new();
+    /// // Cross an epoch boundary so rewards can be distributed
+    /// svm.warp_to_epoch(20);
+    /// ```
+    fn warp_to_epoch(&mut self, epoch: u64);
+}
+
+/// Slot duration used to advance slots along with the clock
+pub const MS_PER_SLOT: u64 = 400;
+
+impl TestHelpers for LiteSVM {
+    fn create_funded_account(&mut self, lamports: u64) -> Result<Keypair, Box<dyn Error>> {
+        let keypair = Keypair::new();
+        self.airdrop(&keypair.pubkey(), lamports)
+            .map_err(|e| format!("Failed to airdrop: {:?}", e))?;
+        Ok(keypair)
+    }
+
+    fn create_funded_accounts(
+        &mut self,
+        count: usize,
+        lamports: u64,
+    ) -> Result<Vec<Keypair>, Box<dyn Error>> {
+        let mut accounts = Vec::with_capacity(count);
+        for _ in 0..count {
+            accounts.push(self.create_funded_account(lamports)?);
+        }
+        Ok(accounts)
+    }
+
+    fn bulk_create_funded_accounts(&mut self, count: usize, lamports: u64) -> Vec<Keypair> {
+        let mut accounts = Vec::with_capacity(count);
+        for _ in 0..count {
+            let keypair = Keypair::new();
+            self.set_account(
+                keypair.pubkey(),
+                Account {
+                    lamports,
+                    data: vec![],
+                    owner: solana_system_interface::program::id(),
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            )
+            .expect("Failed to write account");
+            accounts.push(keypair);
+        }
+        accounts
+    }
+
+    fn create_token_mint(
+        &mut self,
+        authority: &Keypair,
+        decimals: u8,
+    ) -> Result<Keypair, Box<dyn Error>> {
+        self.create_token_mint_with_program(&spl_token::id(), authority, decimals)
+    }
+
+    fn create_token_mint_with_freeze_authority(
+        &mut self,
+        authority: &Keypair,
+        decimals: u8,
+        freeze_authority: &Pubkey,
+    ) -> Result<Keypair, Box<dyn Error>> {
+        create_mint(self, &spl_token::id(), authority, decimals, Some(freeze_authority))
+    }
+
+    fn create_mint_with_pda_authority(
+        &mut self,
+        payer: &Keypair,
+        authority_pda: &Pubkey,
+        decimals: u8,
+    ) -> Result<Keypair, Box<dyn Error>> {
+        let mint = Keypair::new();
+
+        // Calculate rent for mint account
+        let rent = self.minimum_balance_for_rent_exemption(82);
+
+        // Create mint account
+        let create_account_ix = solana_system_interface::instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent,
+            82,
+            &spl_token::id(),
+        );
+
+        // Initialize mint - the authority is only recorded, it doesn't sign
+        let init_mint_ix = spl_token::instruction::initialize_mint(
+            &spl_token::id(),
+            &mint.pubkey(),
+            authority_pda,
+            None,
+            decimals,
+        )?;
+
+        // Send transaction
+        let tx = Transaction::new_signed_with_payer(
+            &[create_account_ix, init_mint_ix],
+            Some(&payer.pubkey()),
+            &[payer, &mint],
+            self.latest_blockhash(),
+        );
+
+        self.send_transaction(tx)
+            .map_err(|e| format!("Failed to create mint: {:?}", e.err))?;
+        Ok(mint)
+    }
+
+    fn create_token_account(
+        &mut self,
+        mint: &Pubkey,
+        owner: &Keypair,
+    ) -> Result<Keypair, Box<dyn Error>> {
+        let token_account = Keypair::new();
+
+        // Calculate rent for token account
+        let rent = self.minimum_balance_for_rent_exemption(165);
+
+        // Create account
+        let create_account_ix = solana_system_interface::instruction::create_account(
+            &owner.pubkey(),
+            &token_account.pubkey(),
+            rent,
+            165,
+            &spl_token::id(),
+        );
+
+        // Initialize token account
+        let init_account_ix = spl_token::instruction::initialize_account(
+            &spl_token::id(),
+            &token_account.pubkey(),
+            mint,
+            &owner.pubkey(),
+        )?;
+
+        // Send transaction
+        let tx = Transaction::new_signed_with_payer(
+            &[create_account_ix, init_account_ix],
+            Some(&owner.pubkey()),
+            &[owner, &token_account],
+            self.latest_blockhash(),
+        );
+
+        self.send_transaction(tx)
+            .map_err(|e| format!("Failed to create token account: {:?}", e.err))?;
+        Ok(token_account)
+    }
+
+    fn create_associated_token_account(
+        &mut self,
+        mint: &Pubkey,
+        owner: &Keypair,
+    ) -> Result<Pubkey, Box<dyn Error>> {
+        self.create_associated_token_account_with_program(&spl_token::id(), mint, owner)
+    }
+
+    fn create_ata_for(
+        &mut self,
+        payer: &Keypair,
+        owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey, Box<dyn Error>> {
+        self.create_ata_for_with_program(&spl_token::id(), payer, owner, mint)
+    }
+
+    fn create_ata_idempotent(
+        &mut self,
+        payer: &Keypair,
+        owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey, Box<dyn Error>> {
+        let token_program = self
+            .get_account(mint)
+            .ok_or_else(|| format!("Mint {} not found", mint))?
+            .owner;
+        let ata = get_associated_token_address_with_program_id(owner, mint, &token_program);
+
+        let create_ata_ix =
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                owner,
+                mint,
+                &token_program,
+            );
+        send_single_instruction(self, create_ata_ix, payer, "create ATA")?;
+        Ok(ata)
+    }
+
+    fn mint_to(
+        &mut self,
+        mint: &Pubkey,
+        account: &Pubkey,
+        authority: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        self.mint_to_with_program(&spl_token::id(), mint, account, authority, amount)
+    }
+
+    fn create_token_mint_with_program(
+        &mut self,
+        token_program_id: &Pubkey,
+        authority: &Keypair,
+        decimals: u8,
+    ) -> Result<Keypair, Box<dyn Error>> {
+        create_mint(self, token_program_id, authority, decimals, None)
+    }
+
+    fn create_associated_token_account_with_program(
+        &mut self,
+        token_program_id: &Pubkey,
+        mint: &Pubkey,
+        owner: &Keypair,
+    ) -> Result<Pubkey, Box<dyn Error>> {
+        self.create_ata_for_with_program(token_program_id, owner, &owner.pubkey(), mint)
+    }
+
+    fn create_ata_for_with_program(
+        &mut self,
+        token_program_id: &Pubkey,
+        payer: &Keypair,
+        owner: &Pubkey,
+        mint: &Pubkey,
+    ) -> Result<Pubkey, Box<dyn Error>> {
+        let ata = get_associated_token_address_with_program_id(owner, mint, token_program_id);
+
+        // Create ATA instruction - the owner doesn't need to sign
+        let create_ata_ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            owner,
+            mint,
+            token_program_id,
+        );
+
+        // Send transaction
+        let tx = Transaction::new_signed_with_payer(
+            &[create_ata_ix],
+            Some(&payer.pubkey()),
+            &[payer],
+            self.latest_blockhash(),
+        );
+
+        self.send_transaction(tx)
+            .map_err(|e| format!("Failed to create ATA: {:?}", e.err))?;
+        Ok(ata)
+    }
+
+    fn mint_to_with_program(
+        &mut self,
+        token_program_id: &Pubkey,
+        mint: &Pubkey,
+        account: &Pubkey,
+        authority: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        // Create mint_to instruction
+        let mint_to_ix = spl_token_2022_interface::instruction::mint_to(
+            token_program_id,
+            mint,
+            account,
+            &authority.pubkey(),
+            &[],
+            amount,
+        )?;
+
+        // Send transaction
+        let tx = Transaction::new_signed_with_payer(
+            &[mint_to_ix],
+            Some(&authority.pubkey()),
+            &[authority],
+            self.latest_blockhash(),
+        );
+
+        self.send_transaction(tx)
+            .map_err(|e| format!("Failed to mint tokens: {:?}", e.err))?;
+        Ok(())
+    }
+
+    fn create_token2022_mint(
+        &mut self,
+        authority: &Keypair,
+        decimals: u8,
+        extensions: &[ExtensionType],
+    ) -> Result<Keypair, Box<dyn Error>> {
+        let mint = Keypair::new();
+        let extension_ixs = extensions
+            .iter()
+            .map(|extension| extension_init_instruction(*extension, &mint.pubkey(), &authority.pubkey()))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        self.send_transaction(tx)
-            .map_err(|e| format!("Failed to create mint: {:?}", e.err))?;
-        Ok(mint)
+        create_token2022_mint_with(self, authority, mint, decimals, extensions, extension_ixs)
     }
 
-    fn create_token_account(
+    fn create_mint_with_transfer_fee(
         &mut self,
-        mint: &Pubkey,
-        owner: &Keypair,
+        authority: &Keypair,
+        decimals: u8,
+        fee_bps: u16,
+        max_fee: u64,
     ) -> Result<Keypair, Box<dyn Error>> {
-        let token_account = Keypair::new();
+        let mint = Keypair::new();
+        let init_fee_ix = transfer_fee::instruction::initialize_transfer_fee_config(
+            &spl_token_2022_interface::id(),
+            &mint.pubkey(),
+            Some(&authority.pubkey()),
+            Some(&authority.pubkey()),
+            fee_bps,
+            max_fee,
+        )?;
 
-        // Calculate rent for token account
-        let rent = self.minimum_balance_for_rent_exemption(165);
+        create_token2022_mint_with(
+            self,
+            authority,
+            mint,
+            decimals,
+            &[ExtensionType::TransferFeeConfig],
+            vec![init_fee_ix],
+        )
+    }
 
-        // Create account
-        let create_account_ix = solana_system_interface::instruction::create_account(
-            &owner.pubkey(),
-            &token_account.pubkey(),
-            rent,
-            165,
-            &spl_token::id(),
-        );
+    fn get_withheld_fees(&self, token_account: &Pubkey) -> Result<u64, Box<dyn Error>> {
+        let account = self
+            .get_account(token_account)
+            .ok_or_else(|| format!("Token account {} not found", token_account))?;
+        let state = StateWithExtensions::<Token2022Account>::unpack(&account.data)
+            .map_err(|e| format!("Failed to unpack token account {}: {:?}", token_account, e))?;
+        let fee_amount = state
+            .get_extension::<TransferFeeAmount>()
+            .map_err(|_| format!("Token account {} has no transfer fee extension", token_account))?;
+        Ok(fee_amount.withheld_amount.into())
+    }
 
-        // Initialize token account
-        let init_account_ix = spl_token::instruction::initialize_account(
-            &spl_token::id(),
-            &token_account.pubkey(),
+    fn create_interest_bearing_mint(
+        &mut self,
+        authority: &Keypair,
+        decimals: u8,
+        rate: i16,
+    ) -> Result<Keypair, Box<dyn Error>> {
+        let mint = Keypair::new();
+        let init_interest_ix = interest_bearing_mint::instruction::initialize(
+            &spl_token_2022_interface::id(),
+            &mint.pubkey(),
+            Some(authority.pubkey()),
+            rate,
+        )?;
+
+        create_token2022_mint_with(
+            self,
+            authority,
             mint,
-            &owner.pubkey(),
+            decimals,
+            &[ExtensionType::InterestBearingConfig],
+            vec![init_interest_ix],
+        )
+    }
+
+    fn get_accrued_ui_amount(&self, token_account: &Pubkey) -> Result<String, Box<dyn Error>> {
+        let account = self
+            .get_account(token_account)
+            .ok_or_else(|| format!("Token account {} not found", token_account))?;
+        let token = StateWithExtensions::<Token2022Account>::unpack(&account.data)
+            .map_err(|e| format!("Failed to unpack token account {}: {:?}", token_account, e))?
+            .base;
+
+        let mint_account = self
+            .get_account(&token.mint)
+            .ok_or_else(|| format!("Mint {} not found", token.mint))?;
+        let mint = StateWithExtensions::<Token2022Mint>::unpack(&mint_account.data)
+            .map_err(|e| format!("Failed to unpack mint {}: {:?}", token.mint, e))?;
+        let config = mint
+            .get_extension::<InterestBearingConfig>()
+            .map_err(|_| format!("Mint {} has no interest-bearing extension", token.mint))?;
+
+        let now = self.get_sysvar::<solana_program::clock::Clock>().unix_timestamp;
+        config
+            .amount_to_ui_amount(token.amount, mint.base.decimals, now)
+            .ok_or_else(|| format!("Failed to calculate UI amount for {}", token_account).into())
+    }
+
+    fn accrued_ui_amount_after(
+        &mut self,
+        token_account: &Pubkey,
+        seconds: i64,
+    ) -> Result<String, Box<dyn Error>> {
+        let mut clock = self.get_sysvar::<solana_program::clock::Clock>();
+        clock.unix_timestamp += seconds;
+        self.set_sysvar(&clock);
+        self.get_accrued_ui_amount(token_account)
+    }
+
+    fn create_mint_with_metadata(
+        &mut self,
+        authority: &Keypair,
+        decimals: u8,
+        name: &str,
+        symbol: &str,
+        uri: &str,
+    ) -> Result<Keypair, Box<dyn Error>> {
+        let mint = Keypair::new();
+        let init_pointer_ix = metadata_pointer::instruction::initialize(
+            &spl_token_2022_interface::id(),
+            &mint.pubkey(),
+            Some(authority.pubkey()),
+            Some(mint.pubkey()),
+        )?;
+        let mint = create_token2022_mint_with(
+            self,
+            authority,
+            mint,
+            decimals,
+            &[ExtensionType::MetadataPointer],
+            vec![init_pointer_ix],
         )?;
 
-        // Send transaction
-        let tx = Transaction::new_signed_with_payer(
-            &[create_account_ix, init_account_ix],
-            Some(&owner.pubkey()),
-            &[owner, &token_account],
-            self.latest_blockhash(),
-        );
+        // The metadata is written straight into the mint's TLV data, matching what
+        // the metadata Initialize instruction stores. That instruction can't run
+        // here; see test_token_metadata_initialize_instruction_fails.
+        let metadata = TokenMetadata {
+            update_authority: Some(authority.pubkey()).try_into()?,
+            mint: mint.pubkey(),
+            name: name.to_string(),
+            symbol: symbol.to_string(),
+            uri: uri.to_string(),
+            additional_metadata: vec![],
+        };
+        let mut account = self
+            .get_account(&mint.pubkey())
+            .ok_or_else(|| format!("Mint {} not found", mint.pubkey()))?;
+        account.data.resize(account.data.len() + metadata.tlv_size_of()?, 0);
+        StateWithExtensionsMut::<Token2022Mint>::unpack(&mut account.data)
+            .and_then(|mut state| state.init_variable_len_extension(&metadata, false))
+            .map_err(|e| format!("Failed to initialize token metadata: {:?}", e))?;
+        account.lamports = account
+            .lamports
+            .max(self.minimum_balance_for_rent_exemption(account.data.len()));
+
+        self.set_account(mint.pubkey(), account)
+            .map_err(|e| format!("Failed to write mint: {:?}", e))?;
+        Ok(mint)
+    }
 
-        self.send_transaction(tx)
-            .map_err(|e| format!("Failed to create token account: {:?}", e.err))?;
-        Ok(token_account)
+    fn get_token_metadata(&self, mint: &Pubkey) -> Result<TokenMetadata, Box<dyn Error>> {
+        let account = self
+            .get_account(mint)
+            .ok_or_else(|| format!("Mint {} not found", mint))?;
+        let state = StateWithExtensions::<Token2022Mint>::unpack(&account.data)
+            .map_err(|e| format!("Failed to unpack mint {}: {:?}", mint, e))?;
+        state
+            .get_variable_len_extension::<TokenMetadata>()
+            .map_err(|_| format!("Mint {} has no token metadata", mint).into())
     }
 
-    fn create_associated_token_account(
+    fn create_token2022_ata(
         &mut self,
         mint: &Pubkey,
         owner: &Keypair,
     ) -> Result<Pubkey, Box<dyn Error>> {
-        let ata = get_associated_token_address(&owner.pubkey(), mint);
+        self.create_associated_token_account_with_program(&spl_token_2022_interface::id(), mint, owner)
+    }
 
-        // Create ATA instruction
-        let create_ata_ix = spl_associated_token_account::instruction::create_associated_token_account(
+    fn mint_to_token2022(
+        &mut self,
+        mint: &Pubkey,
+        account: &Pubkey,
+        authority: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        self.mint_to_with_program(&spl_token_2022_interface::id(), mint, account, authority, amount)
+    }
+
+    fn transfer_tokens(
+        &mut self,
+        source: &Pubkey,
+        destination: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let (token_program, mint, decimals) = token_account_mint(self, source)?;
+        let transfer_ix = spl_token_2022_interface::instruction::transfer_checked(
+            &token_program,
+            source,
+            &mint,
+            destination,
+            &owner.pubkey(),
+            &[],
+            amount,
+            decimals,
+        )?;
+        send_single_instruction(self, transfer_ix, owner, "transfer tokens")
+    }
+
+    fn burn_tokens(
+        &mut self,
+        account: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let (token_program, mint, _) = token_account_mint(self, account)?;
+        let burn_ix = spl_token_2022_interface::instruction::burn(
+            &token_program,
+            account,
+            &mint,
             &owner.pubkey(),
+            &[],
+            amount,
+        )?;
+        send_single_instruction(self, burn_ix, owner, "burn tokens")
+    }
+
+    fn approve_delegate(
+        &mut self,
+        account: &Pubkey,
+        delegate: &Pubkey,
+        owner: &Keypair,
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let (token_program, _, _) = token_account_mint(self, account)?;
+        let approve_ix = spl_token_2022_interface::instruction::approve(
+            &token_program,
+            account,
+            delegate,
             &owner.pubkey(),
-            mint,
+            &[],
+            amount,
+        )?;
+        send_single_instruction(self, approve_ix, owner, "approve delegate")
+    }
+
+    fn revoke_delegate(&mut self, account: &Pubkey, owner: &Keypair) -> Result<(), Box<dyn Error>> {
+        let (token_program, _, _) = token_account_mint(self, account)?;
+        let revoke_ix = spl_token_2022_interface::instruction::revoke(&token_program, account, &owner.pubkey(), &[])?;
+        send_single_instruction(self, revoke_ix, owner, "revoke delegate")
+    }
+
+    fn freeze_token_account(
+        &mut self,
+        account: &Pubkey,
+        freeze_authority: &Keypair,
+    ) -> Result<(), Box<dyn Error>> {
+        let (token_program, mint, _) = token_account_mint(self, account)?;
+        let freeze_ix = spl_token_2022_interface::instruction::freeze_account(
+            &token_program,
+            account,
+            &mint,
+            &freeze_authority.pubkey(),
+            &[],
+        )?;
+        send_single_instruction(self, freeze_ix, freeze_authority, "freeze token account")
+    }
+
+    fn thaw_token_account(
+        &mut self,
+        account: &Pubkey,
+        freeze_authority: &Keypair,
+    ) -> Result<(), Box<dyn Error>> {
+        let (token_program, mint, _) = token_account_mint(self, account)?;
+        let thaw_ix = spl_token_2022_interface::instruction::thaw_account(
+            &token_program,
+            account,
+            &mint,
+            &freeze_authority.pubkey(),
+            &[],
+        )?;
+        send_single_instruction(self, thaw_ix, freeze_authority, "thaw token account")
+    }
+
+    fn set_token_authority(
+        &mut self,
+        target: &Pubkey,
+        authority_type: spl_token::instruction::AuthorityType,
+        current_authority: &Keypair,
+        new_authority: Option<&Pubkey>,
+    ) -> Result<(), Box<dyn Error>> {
+        use spl_token::instruction::AuthorityType;
+        use spl_token_2022_interface::instruction::AuthorityType as Token2022AuthorityType;
+
+        let token_program = self
+            .get_account(target)
+            .ok_or_else(|| format!("Account {} not found", target))?
+            .owner;
+        // The Token-2022 builder accepts either program and its authority types extend SPL Token's
+        let authority_type = match authority_type {
+            AuthorityType::MintTokens => Token2022AuthorityType::MintTokens,
+            AuthorityType::FreezeAccount => Token2022AuthorityType::FreezeAccount,
+            AuthorityType::AccountOwner => Token2022AuthorityType::AccountOwner,
+            AuthorityType::CloseAccount => Token2022AuthorityType::CloseAccount,
+        };
+        let set_authority_ix = spl_token_2022_interface::instruction::set_authority(
+            &token_program,
+            target,
+            new_authority,
+            authority_type,
+            &current_authority.pubkey(),
+            &[],
+        )?;
+        send_single_instruction(self, set_authority_ix, current_authority, "set token authority")
+    }
+
+    fn create_token_multisig(
+        &mut self,
+        payer: &Keypair,
+        signers: &[Pubkey],
+        m: u8,
+    ) -> Result<Keypair, Box<dyn Error>> {
+        let multisig = Keypair::new();
+        let space = spl_token::state::Multisig::LEN;
+        let rent = self.minimum_balance_for_rent_exemption(space);
+
+        let create_account_ix = solana_system_interface::instruction::create_account(
+            &payer.pubkey(),
+            &multisig.pubkey(),
+            rent,
+            space as u64,
             &spl_token::id(),
         );
+        let signer_refs: Vec<&Pubkey> = signers.iter().collect();
+        let init_multisig_ix = spl_token::instruction::initialize_multisig2(
+            &spl_token::id(),
+            &multisig.pubkey(),
+            &signer_refs,
+            m,
+        )?;
 
-        // Send transaction
         let tx = Transaction::new_signed_with_payer(
-            &[create_ata_ix],
-            Some(&owner.pubkey()),
-            &[owner],
+            &[create_account_ix, init_multisig_ix],
+            Some(&payer.pubkey()),
+            &[payer, &multisig],
             self.latest_blockhash(),
         );
 
         self.send_transaction(tx)
-            .map_err(|e| format!("Failed to create ATA: {:?}", e.err))?;
-        Ok(ata)
+            .map_err(|e| format!("Failed to create multisig: {:?}", e.err))?;
+        Ok(multisig)
     }
 
-    fn mint_to(
+    fn mint_to_multisig(
         &mut self,
         mint: &Pubkey,
         account: &Pubkey,
-        authority: &Keypair,
+        multisig: &Pubkey,
+        signers: &[&Keypair],
         amount: u64,
     ) -> Result<(), Box<dyn Error>> {
-        // Create mint_to instruction
-        let mint_to_ix = spl_token::instruction::mint_to(
-            &spl_token::id(),
+        let token_program = self
+            .get_account(mint)
+            .ok_or_else(|| format!("Mint {} not found", mint))?
+            .owner;
+        let signer_pubkeys: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+        let mint_to_ix = spl_token_2022_interface::instruction::mint_to(
+            &token_program,
             mint,
             account,
-            &authority.pubkey(),
-            &[],
+            multisig,
+            &signer_pubkeys.iter().collect::<Vec<_>>(),
+            amount,
+        )?;
+        send_multisig_instruction(self, mint_to_ix, signers, "mint tokens")
+    }
+
+    fn transfer_multisig(
+        &mut self,
+        source: &Pubkey,
+        destination: &Pubkey,
+        multisig: &Pubkey,
+        signers: &[&Keypair],
+        amount: u64,
+    ) -> Result<(), Box<dyn Error>> {
+        let (token_program, mint, decimals) = token_account_mint(self, source)?;
+        let signer_pubkeys: Vec<Pubkey> = signers.iter().map(|signer| signer.pubkey()).collect();
+        let transfer_ix = spl_token_2022_interface::instruction::transfer_checked(
+            &token_program,
+            source,
+            &mint,
+            destination,
+            multisig,
+            &signer_pubkeys.iter().collect::<Vec<_>>(),
             amount,
+            decimals,
         )?;
+        send_multisig_instruction(self, transfer_ix, signers, "transfer tokens")
+    }
+
+    fn assert_transfer_works(&mut self, mint: &Pubkey, mint_authority: &Keypair, amount: u64) {
+        let sender = self
+            .create_funded_account(1_000_000_000)
+            .expect("Failed to create sender");
+        let receiver = self
+            .create_funded_account(1_000_000_000)
+            .expect("Failed to create receiver");
+
+        let sender_ata = self
+            .create_ata_for(mint_authority, &sender.pubkey(), mint)
+            .expect("Failed to create sender token account");
+        let receiver_ata = self
+            .create_ata_for(mint_authority, &receiver.pubkey(), mint)
+            .expect("Failed to create receiver token account");
+        self.mint_to(mint, &sender_ata, mint_authority, amount)
+            .expect("Failed to mint tokens to sender");
+
+        let transfer_ix = spl_token::instruction::transfer(
+            &spl_token::id(),
+            &sender_ata,
+            &receiver_ata,
+            &sender.pubkey(),
+            &[],
+            amount,
+        )
+        .expect("Failed to build transfer instruction");
+        let tx = Transaction::new_signed_with_payer(
+            &[transfer_ix],
+            Some(&sender.pubkey()),
+            &[&sender],
+            self.latest_blockhash(),
+        );
+        self.send_transaction(tx)
+            .unwrap_or_else(|e| panic!("Transfer failed: {:?}\nLogs:\n{}", e.err, e.meta.logs.join("\n")));
+
+        self.assert_token_balance(&sender_ata, 0);
+        self.assert_token_balance(&receiver_ata, amount);
+    }
+
+    fn create_nonce_account(
+        &mut self,
+        authority: &Keypair,
+    ) -> Result<(Keypair, Hash), Box<dyn Error>> {
+        let nonce_account = Keypair::new();
+        let rent = self.minimum_balance_for_rent_exemption(NonceState::size());
+
+        // Create and initialize the nonce account
+        let instructions = solana_system_interface::instruction::create_nonce_account(
+            &authority.pubkey(),
+            &nonce_account.pubkey(),
+            &authority.pubkey(),
+            rent,
+        );
 
         // Send transaction
         let tx = Transaction::new_signed_with_payer(
-            &[mint_to_ix],
+            &instructions,
             Some(&authority.pubkey()),
-            &[authority],
+            &[authority, &nonce_account],
             self.latest_blockhash(),
         );
 
         self.send_transaction(tx)
-            .map_err(|e| format!("Failed to mint tokens: {:?}", e.err))?;
+            .map_err(|e| format!("Failed to create nonce account: {:?}", e.err))?;
+
+        let nonce = durable_nonce(self, &nonce_account.pubkey())
+            .ok_or("Failed to read nonce account state")?;
+        Ok((nonce_account, nonce))
+    }
+
+    fn get_durable_nonce(&self, nonce_account: &Pubkey) -> Result<Hash, Box<dyn Error>> {
+        durable_nonce(self, nonce_account)
+            .ok_or_else(|| format!("Account {} is not an initialized nonce account", nonce_account).into())
+    }
+
+    fn advance_nonce_account(&mut self, nonce_account: &Pubkey, authority: &Keypair) -> Result<Hash, Box<dyn Error>> {
+        // A nonce can't be advanced while it still matches the current blockhash
+        self.expire_blockhash();
+
+        let ix = solana_system_interface::instruction::advance_nonce_account(nonce_account, &authority.pubkey());
+        send_single_instruction(self, ix, authority, "advance nonce account")?;
+        self.get_durable_nonce(nonce_account)
+    }
+
+    fn create_vote_account(&mut self, authority: &Pubkey) -> Result<Pubkey, Box<dyn Error>> {
+        let vote_account = Pubkey::new_unique();
+        let vote_init = VoteInit {
+            node_pubkey: *authority,
+            authorized_voter: *authority,
+            authorized_withdrawer: *authority,
+            commission: 0,
+        };
+        let clock = self.get_sysvar::<solana_program::clock::Clock>();
+        let vote_state = VoteStateVersions::new_v3(VoteStateV3::new(&vote_init, &clock));
+
+        let mut data = vec![0; VoteStateV3::size_of()];
+        VoteStateV3::serialize(&vote_state, &mut data)
+            .map_err(|e| format!("Failed to serialize vote state: {:?}", e))?;
+        self.set_account(
+            vote_account,
+            Account {
+                lamports: self.minimum_balance_for_rent_exemption(data.len()),
+                data,
+                owner: solana_sdk_ids::vote::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .map_err(|e| format!("Failed to create vote account: {:?}", e))?;
+        Ok(vote_account)
+    }
+
+    fn create_stake_account(&mut self, staker: &Keypair, lamports: u64) -> Result<Keypair, Box<dyn Error>> {
+        let stake = Keypair::new();
+        let instructions = solana_stake_interface::instruction::create_account(
+            &staker.pubkey(),
+            &stake.pubkey(),
+            &Authorized::auto(&staker.pubkey()),
+            &Lockup::default(),
+            lamports,
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&staker.pubkey()),
+            &[staker, &stake],
+            self.latest_blockhash(),
+        );
+
+        self.send_transaction(tx)
+            .map_err(|e| format!("Failed to create stake account: {:?}", e.err))?;
+        Ok(stake)
+    }
+
+    fn delegate_stake(
+        &mut self,
+        stake: &Pubkey,
+        vote_account: &Pubkey,
+        staker: &Keypair,
+    ) -> Result<(), Box<dyn Error>> {
+        let ix = solana_stake_interface::instruction::delegate_stake(stake, &staker.pubkey(), vote_account);
+        send_single_instruction(self, ix, staker, "delegate stake")
+    }
+
+    fn deactivate_stake(&mut self, stake: &Pubkey, staker: &Keypair) -> Result<(), Box<dyn Error>> {
+        let ix = solana_stake_interface::instruction::deactivate_stake(stake, &staker.pubkey());
+        send_single_instruction(self, ix, staker, "deactivate stake")
+    }
+
+    fn get_stake_state(&self, stake: &Pubkey) -> Result<StakeStateV2, Box<dyn Error>> {
+        let account = self.get_account(stake).ok_or("Stake account not found")?;
+        if account.owner != solana_sdk_ids::stake::id() {
+            return Err(format!("Account {} is not a stake account", stake).into());
+        }
+        bincode::deserialize(&account.data).map_err(|e| format!("Failed to parse stake account: {}", e).into())
+    }
+
+    fn create_lookup_table(&mut self, authority: &Keypair, addresses: &[Pubkey]) -> Result<Pubkey, Box<dyn Error>> {
+        // The table address is derived from a slot that must be present in SlotHashes,
+        // which LiteSVM only seeds for the genesis slot
+        let recent_slot = self.get_sysvar::<solana_program::clock::Clock>().slot;
+        let mut slot_hashes = self.get_sysvar::<SlotHashes>();
+        if slot_hashes.get(&recent_slot).is_none() {
+            let mut entries = slot_hashes.slot_hashes().to_vec();
+            entries.push((recent_slot, self.latest_blockhash()));
+            slot_hashes = SlotHashes::new(&entries);
+            self.set_sysvar(&slot_hashes);
+        }
+
+        let (ix, lookup_table) = solana_address_lookup_table_interface::instruction::create_lookup_table(
+            authority.pubkey(),
+            authority.pubkey(),
+            recent_slot,
+        );
+        send_single_instruction(self, ix, authority, "create lookup table")?;
+
+        // Extending also advances the slot, which keeps the next table's address unique
+        self.extend_lookup_table(&lookup_table, authority, addresses)?;
+        Ok(lookup_table)
+    }
+
+    fn extend_lookup_table(
+        &mut self,
+        lookup_table: &Pubkey,
+        authority: &Keypair,
+        addresses: &[Pubkey],
+    ) -> Result<(), Box<dyn Error>> {
+        for batch in addresses.chunks(LOOKUP_TABLE_EXTEND_BATCH) {
+            let ix = solana_address_lookup_table_interface::instruction::extend_lookup_table(
+                *lookup_table,
+                authority.pubkey(),
+                Some(authority.pubkey()),
+                batch.to_vec(),
+            );
+            send_single_instruction(self, ix, authority, "extend lookup table")?;
+        }
+
+        // Addresses only become usable in the slot after they were added
+        self.advance_slot(1);
         Ok(())
     }
 
@@ -338,22 +1846,274 @@ impl TestHelpers for LiteSVM {
         Pubkey::find_program_address(seeds, program_id)
     }
 
-    fn get_current_slot(&self) -> u64 {
-        // LiteSVM doesn't have get_clock, use slot directly
-        self.get_sysvar::<solana_program::clock::Clock>().slot
-    }
+    fn get_current_slot(&self) -> u64 {
+        // LiteSVM doesn't have get_clock, use slot directly
+        self.get_sysvar::<solana_program::clock::Clock>().slot
+    }
+
+    fn advance_slot(&mut self, slots: u64) {
+        let current_slot = self.get_sysvar::<solana_program::clock::Clock>().slot;
+        for i in 0..slots {
+            self.warp_to_slot(current_slot + i + 1);
+        }
+    }
+
+    fn set_unix_timestamp(&mut self, unix_timestamp: i64) {
+        let now = self.get_sysvar::<solana_program::clock::Clock>().unix_timestamp;
+        if unix_timestamp > now {
+            self.advance_time(Duration::from_secs((unix_timestamp - now) as u64));
+        } else {
+            let mut clock = self.get_sysvar::<solana_program::clock::Clock>();
+            clock.unix_timestamp = unix_timestamp;
+            self.set_sysvar(&clock);
+        }
+    }
+
+    fn advance_time(&mut self, duration: Duration) {
+        let mut clock = self.get_sysvar::<solana_program::clock::Clock>();
+        let slots = (duration.as_millis() / MS_PER_SLOT as u128) as u64;
+        if slots > 0 {
+            self.warp_to_slot(clock.slot + slots);
+            clock = self.get_sysvar::<solana_program::clock::Clock>();
+        }
+        clock.unix_timestamp += duration.as_secs() as i64;
+        self.set_sysvar(&clock);
+    }
+
+    fn warp_to_epoch(&mut self, epoch: u64) {
+        let schedule = self.get_sysvar::<solana_program::epoch_schedule::EpochSchedule>();
+        let slot = schedule.get_first_slot_in_epoch(epoch);
+        let mut clock = self.get_sysvar::<solana_program::clock::Clock>();
+        if slot > clock.slot {
+            clock.unix_timestamp += ((slot - clock.slot) * MS_PER_SLOT / 1000) as i64;
+        }
+        self.warp_to_slot(slot);
+        clock.slot = slot;
+        clock.epoch = epoch;
+        clock.leader_schedule_epoch = schedule.get_leader_schedule_epoch(slot);
+        clock.epoch_start_timestamp = clock.unix_timestamp;
+        self.set_sysvar(&clock);
+    }
+}
+
+/// Maximum number of addresses added per `ExtendLookupTable` transaction
+const LOOKUP_TABLE_EXTEND_BATCH: usize = 20;
+
+/// Create a mint owned by `token_program_id` with an optional freeze authority
+fn create_mint(
+    svm: &mut LiteSVM,
+    token_program_id: &Pubkey,
+    authority: &Keypair,
+    decimals: u8,
+    freeze_authority: Option<&Pubkey>,
+) -> Result<Keypair, Box<dyn Error>> {
+    let mint = Keypair::new();
+
+    // Calculate rent for mint account
+    let rent = svm.minimum_balance_for_rent_exemption(82);
+
+    // Create mint account
+    let create_account_ix = solana_system_interface::instruction::create_account(
+        &authority.pubkey(),
+        &mint.pubkey(),
+        rent,
+        82,
+        token_program_id,
+    );
+
+    // Initialize mint - the Token-2022 instruction builders accept either program id
+    let init_mint_ix = spl_token_2022_interface::instruction::initialize_mint(
+        token_program_id,
+        &mint.pubkey(),
+        &authority.pubkey(),
+        freeze_authority,
+        decimals,
+    )?;
+
+    // Send transaction
+    let tx = Transaction::new_signed_with_payer(
+        &[create_account_ix, init_mint_ix],
+        Some(&authority.pubkey()),
+        &[authority, &mint],
+        svm.latest_blockhash(),
+    );
+
+    svm.send_transaction(tx)
+        .map_err(|e| format!("Failed to create mint: {:?}", e.err))?;
+    Ok(mint)
+}
+
+/// Read the token program, mint and mint decimals of a token account
+fn token_account_mint(svm: &LiteSVM, token_account: &Pubkey) -> Result<(Pubkey, Pubkey, u8), Box<dyn Error>> {
+    let account = svm
+        .get_account(token_account)
+        .ok_or_else(|| format!("Token account {} not found", token_account))?;
+    // Token-2022 accounts share the base layout and append extensions after it
+    let token = account
+        .data
+        .get(..spl_token::state::Account::LEN)
+        .and_then(|data| spl_token::state::Account::unpack_from_slice(data).ok())
+        .ok_or_else(|| format!("Failed to unpack token account {}", token_account))?;
+
+    let mint = svm
+        .get_account(&token.mint)
+        .ok_or_else(|| format!("Mint {} not found", token.mint))?;
+    let decimals = mint
+        .data
+        .get(..spl_token::state::Mint::LEN)
+        .and_then(|data| spl_token::state::Mint::unpack_from_slice(data).ok())
+        .ok_or_else(|| format!("Failed to unpack mint {}", token.mint))?
+        .decimals;
+    Ok((account.owner, token.mint, decimals))
+}
+
+/// Send a single instruction signed and paid for by `signer`
+fn send_single_instruction(
+    svm: &mut LiteSVM,
+    instruction: Instruction,
+    signer: &Keypair,
+    action: &str,
+) -> Result<(), Box<dyn Error>> {
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&signer.pubkey()),
+        &[signer],
+        svm.latest_blockhash(),
+    );
+
+    svm.send_transaction(tx)
+        .map_err(|e| format!("Failed to {}: {:?}", action, e.err))?;
+    Ok(())
+}
+
+/// Send a single token instruction signed by multisig signers, the first of which pays
+fn send_multisig_instruction(
+    svm: &mut LiteSVM,
+    instruction: Instruction,
+    signers: &[&Keypair],
+    action: &str,
+) -> Result<(), Box<dyn Error>> {
+    let payer = signers.first().ok_or("At least one multisig signer is required")?;
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        signers,
+        svm.latest_blockhash(),
+    );
+
+    svm.send_transaction(tx)
+        .map_err(|e| format!("Failed to {}: {:?}", action, e.err))?;
+    Ok(())
+}
+
+/// Create a Token-2022 mint sized for `extensions`, running `extension_ixs` before `InitializeMint2`
+fn create_token2022_mint_with(
+    svm: &mut LiteSVM,
+    authority: &Keypair,
+    mint: Keypair,
+    decimals: u8,
+    extensions: &[ExtensionType],
+    extension_ixs: Vec<Instruction>,
+) -> Result<Keypair, Box<dyn Error>> {
+    let token_program = spl_token_2022_interface::id();
+    let space = ExtensionType::try_calculate_account_len::<Token2022Mint>(extensions)
+        .map_err(|e| format!("Invalid mint extensions: {:?}", e))?;
+    let rent = svm.minimum_balance_for_rent_exemption(space);
+
+    let mut instructions = vec![solana_system_interface::instruction::create_account(
+        &authority.pubkey(),
+        &mint.pubkey(),
+        rent,
+        space as u64,
+        &token_program,
+    )];
+    instructions.extend(extension_ixs);
+    instructions.push(spl_token_2022_interface::instruction::initialize_mint2(
+        &token_program,
+        &mint.pubkey(),
+        &authority.pubkey(),
+        None,
+        decimals,
+    )?);
+
+    let tx = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&authority.pubkey()),
+        &[authority, &mint],
+        svm.latest_blockhash(),
+    );
+
+    svm.send_transaction(tx)
+        .map_err(|e| format!("Failed to create mint: {:?}", e.err))?;
+    Ok(mint)
+}
 
-    fn advance_slot(&mut self, slots: u64) {
-        let current_slot = self.get_sysvar::<solana_program::clock::Clock>().slot;
-        for i in 0..slots {
-            self.warp_to_slot(current_slot + i + 1);
+/// Instruction initializing a mint extension with default configuration
+fn extension_init_instruction(
+    extension: ExtensionType,
+    mint: &Pubkey,
+    authority: &Pubkey,
+) -> Result<Instruction, Box<dyn Error>> {
+    use spl_token_2022_interface::extension::*;
+
+    let program = &spl_token_2022_interface::id();
+    let ix = match extension {
+        ExtensionType::TransferFeeConfig => transfer_fee::instruction::initialize_transfer_fee_config(
+            program,
+            mint,
+            Some(authority),
+            Some(authority),
+            0,
+            0,
+        )?,
+        ExtensionType::MintCloseAuthority => {
+            spl_token_2022_interface::instruction::initialize_mint_close_authority(program, mint, Some(authority))?
+        }
+        ExtensionType::DefaultAccountState => default_account_state::instruction::initialize_default_account_state(
+            program,
+            mint,
+            &spl_token_2022_interface::state::AccountState::Initialized,
+        )?,
+        ExtensionType::NonTransferable => {
+            spl_token_2022_interface::instruction::initialize_non_transferable_mint(program, mint)?
+        }
+        ExtensionType::InterestBearingConfig => {
+            interest_bearing_mint::instruction::initialize(program, mint, Some(*authority), 0)?
+        }
+        ExtensionType::PermanentDelegate => {
+            spl_token_2022_interface::instruction::initialize_permanent_delegate(program, mint, authority)?
+        }
+        ExtensionType::TransferHook => transfer_hook::instruction::initialize(program, mint, Some(*authority), None)?,
+        ExtensionType::MetadataPointer => {
+            metadata_pointer::instruction::initialize(program, mint, Some(*authority), Some(*mint))?
+        }
+        ExtensionType::GroupPointer => group_pointer::instruction::initialize(program, mint, Some(*authority), Some(*mint))?,
+        ExtensionType::GroupMemberPointer => {
+            group_member_pointer::instruction::initialize(program, mint, Some(*authority), Some(*mint))?
         }
+        ExtensionType::ScaledUiAmount => scaled_ui_amount::instruction::initialize(program, mint, Some(*authority), 1.0)?,
+        ExtensionType::Pausable => pausable::instruction::initialize(program, mint, authority)?,
+        other => return Err(format!("Unsupported mint extension: {:?}", other).into()),
+    };
+    Ok(ix)
+}
+
+/// Read the current durable nonce stored in a nonce account
+///
+/// Returns None if the account doesn't exist or isn't an initialized nonce account.
+pub(crate) fn durable_nonce(svm: &LiteSVM, nonce_account: &Pubkey) -> Option<Hash> {
+    let account = svm.get_account(nonce_account)?;
+    let versions: NonceVersions = bincode::deserialize(&account.data).ok()?;
+    match versions.state() {
+        NonceState::Initialized(data) => Some(data.blockhash()),
+        NonceState::Uninitialized => None,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use spl_associated_token_account::get_associated_token_address;
     use solana_program_pack::Pack;
     use solana_sdk::signature::Signer;
 
@@ -393,6 +2153,60 @@ mod tests {
         assert_eq!(pubkeys.len(), count);
     }
 
+    #[test]
+    #[ignore = "timing comparison, run with --ignored --nocapture"]
+    fn test_bulk_create_funded_accounts_timing() {
+        let count = 2_000;
+        let lamports = 1_000_000;
+
+        let mut svm = LiteSVM::new();
+        let start = std::time::Instant::now();
+        svm.create_funded_accounts(count, lamports).unwrap();
+        let airdropped = start.elapsed();
+
+        let mut svm = LiteSVM::new();
+        let start = std::time::Instant::now();
+        svm.bulk_create_funded_accounts(count, lamports);
+        let bulk = start.elapsed();
+
+        println!(
+            "{} accounts: create_funded_accounts {:?}, bulk_create_funded_accounts {:?}",
+            count, airdropped, bulk
+        );
+        assert!(bulk < airdropped, "bulk creation should be faster than airdrops");
+    }
+
+    #[test]
+    fn test_bulk_create_funded_accounts() {
+        let mut svm = LiteSVM::new();
+        let lamports = 2_000_000;
+
+        let accounts = svm.bulk_create_funded_accounts(1_000, lamports);
+        assert_eq!(accounts.len(), 1_000);
+
+        for account in &accounts {
+            let stored = svm.get_account(&account.pubkey()).unwrap();
+            assert_eq!(stored.lamports, lamports);
+            assert_eq!(stored.owner, solana_system_interface::program::id());
+        }
+
+        // Accounts are usable as regular fee payers
+        let recipient = Keypair::new();
+        let ix = solana_system_interface::instruction::transfer(
+            &accounts[0].pubkey(),
+            &recipient.pubkey(),
+            1_000_000,
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&accounts[0].pubkey()),
+            &[&accounts[0]],
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(svm.get_balance(&recipient.pubkey()).unwrap(), 1_000_000);
+    }
+
     #[test]
     fn test_create_token_mint() {
         let mut svm = LiteSVM::new();
@@ -412,6 +2226,21 @@ mod tests {
         assert_eq!(mint_data.supply, 0);
     }
 
+    #[test]
+    fn test_create_mint_with_pda_authority() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let pda = svm.get_pda(&[b"mint_authority"], &Pubkey::new_unique());
+
+        let mint = svm.create_mint_with_pda_authority(&payer, &pda, 6).unwrap();
+
+        let mint_account = svm.get_account(&mint.pubkey()).unwrap();
+        let mint_data = spl_token::state::Mint::unpack(&mint_account.data).unwrap();
+        assert_eq!(Option::<Pubkey>::from(mint_data.mint_authority), Some(pda));
+        assert_eq!(mint_data.decimals, 6);
+        assert!(mint_data.freeze_authority.is_none());
+    }
+
     #[test]
     fn test_create_token_account() {
         let mut svm = LiteSVM::new();
@@ -456,6 +2285,45 @@ mod tests {
         assert_eq!(token_data.amount, 0);
     }
 
+    #[test]
+    fn test_create_ata_for_pda_owner() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&payer, 9).unwrap();
+        let pda = svm.get_pda(&[b"vault"], &Pubkey::new_unique());
+
+        let ata = svm.create_ata_for(&payer, &pda, &mint.pubkey()).unwrap();
+
+        // Verify ATA is at expected address and owned by the PDA
+        assert_eq!(ata, get_associated_token_address(&pda, &mint.pubkey()));
+        let account = svm.get_account(&ata).unwrap();
+        let token_data = spl_token::state::Account::unpack(&account.data).unwrap();
+        assert_eq!(token_data.mint, mint.pubkey());
+        assert_eq!(token_data.owner, pda);
+    }
+
+    #[test]
+    fn test_create_ata_idempotent() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&payer, 9).unwrap();
+        let pda = svm.get_pda(&[b"vault"], &Pubkey::new_unique());
+
+        let ata = svm.create_ata_idempotent(&payer, &pda, &mint.pubkey()).unwrap();
+        assert_eq!(ata, get_associated_token_address(&pda, &mint.pubkey()));
+        svm.mint_to(&mint.pubkey(), &ata, &payer, 100).unwrap();
+
+        // A second call succeeds and leaves the existing account untouched
+        svm.expire_blockhash();
+        assert_eq!(svm.create_ata_idempotent(&payer, &pda, &mint.pubkey()).unwrap(), ata);
+        svm.assert_token_balance(&ata, 100);
+
+        // Token-2022 mints get a Token-2022 ATA
+        let mint_2022 = svm.create_token2022_mint(&payer, 9, &[]).unwrap();
+        let ata_2022 = svm.create_ata_idempotent(&payer, &pda, &mint_2022.pubkey()).unwrap();
+        assert_eq!(svm.get_account(&ata_2022).unwrap().owner, spl_token_2022_interface::id());
+    }
+
     #[test]
     fn test_mint_to() {
         let mut svm = LiteSVM::new();
@@ -503,6 +2371,525 @@ mod tests {
         assert_eq!(token_data.amount, 600_000);
     }
 
+    #[test]
+    fn test_create_token2022_mint_with_extensions() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let extensions = [ExtensionType::MintCloseAuthority, ExtensionType::TransferFeeConfig];
+
+        let mint = svm.create_token2022_mint(&authority, 6, &extensions).unwrap();
+
+        let mint_account = svm.get_account(&mint.pubkey()).unwrap();
+        assert_eq!(mint_account.owner, spl_token_2022_interface::id());
+        assert_eq!(
+            mint_account.data.len(),
+            ExtensionType::try_calculate_account_len::<Token2022Mint>(&extensions).unwrap()
+        );
+        let state = StateWithExtensions::<Token2022Mint>::unpack(&mint_account.data).unwrap();
+        assert_eq!(state.base.decimals, 6);
+        assert_eq!(state.base.mint_authority, Some(authority.pubkey()).into());
+        assert_eq!(state.get_extension_types().unwrap(), extensions);
+    }
+
+    #[test]
+    fn test_create_token2022_mint_unsupported_extension() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let result = svm.create_token2022_mint(&authority, 6, &[ExtensionType::ImmutableOwner]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_token2022_ata_and_mint_to() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token2022_mint(&authority, 9, &[]).unwrap();
+
+        let ata = svm.create_token2022_ata(&mint.pubkey(), &authority).unwrap();
+        assert_eq!(
+            ata,
+            get_associated_token_address_with_program_id(
+                &authority.pubkey(),
+                &mint.pubkey(),
+                &spl_token_2022_interface::id()
+            )
+        );
+        svm.mint_to_token2022(&mint.pubkey(), &ata, &authority, 500).unwrap();
+
+        let account = svm.get_account(&ata).unwrap();
+        assert_eq!(account.owner, spl_token_2022_interface::id());
+        let state = StateWithExtensions::<Token2022Account>::unpack(&account.data).unwrap();
+        assert_eq!(state.base.mint, mint.pubkey());
+        assert_eq!(state.base.amount, 500);
+        svm.assert_token_balance(&ata, 500);
+    }
+
+    #[test]
+    fn test_transfer_fee_withheld() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient = svm.create_funded_account(10_000_000_000).unwrap();
+        // 1% fee capped at 50
+        let mint = svm.create_mint_with_transfer_fee(&authority, 6, 100, 50).unwrap();
+        let source = svm.create_token2022_ata(&mint.pubkey(), &authority).unwrap();
+        let destination = svm.create_token2022_ata(&mint.pubkey(), &recipient).unwrap();
+        svm.mint_to_token2022(&mint.pubkey(), &source, &authority, 10_000).unwrap();
+
+        let transfer_ix = spl_token_2022_interface::instruction::transfer_checked(
+            &spl_token_2022_interface::id(),
+            &source,
+            &mint.pubkey(),
+            &destination,
+            &authority.pubkey(),
+            &[],
+            1_000,
+            6,
+        )
+        .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[transfer_ix],
+            Some(&authority.pubkey()),
+            &[&authority],
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(tx).unwrap();
+
+        // 1% of 1_000 is withheld in the recipient account
+        assert_eq!(svm.get_withheld_fees(&destination).unwrap(), 10);
+        assert_eq!(svm.get_withheld_fees(&source).unwrap(), 0);
+        svm.assert_token_balance(&destination, 990);
+    }
+
+    #[test]
+    fn test_get_withheld_fees_without_extension() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token2022_mint(&authority, 6, &[]).unwrap();
+        let ata = svm.create_token2022_ata(&mint.pubkey(), &authority).unwrap();
+
+        assert!(svm.get_withheld_fees(&ata).is_err());
+        assert!(svm.get_withheld_fees(&Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_interest_bearing_mint_accrues() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_interest_bearing_mint(&authority, 6, 500).unwrap();
+        let ata = svm.create_token2022_ata(&mint.pubkey(), &authority).unwrap();
+        svm.mint_to_token2022(&mint.pubkey(), &ata, &authority, 1_000_000).unwrap();
+
+        assert_eq!(svm.get_accrued_ui_amount(&ata).unwrap(), "1");
+
+        // 5% continuously compounded over a year
+        let ui_amount = svm.accrued_ui_amount_after(&ata, 31_556_736).unwrap();
+        assert_eq!(ui_amount, "1.051271");
+        // The raw balance doesn't change
+        svm.assert_token_balance(&ata, 1_000_000);
+    }
+
+    #[test]
+    fn test_get_accrued_ui_amount_without_extension() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token2022_mint(&authority, 6, &[]).unwrap();
+        let ata = svm.create_token2022_ata(&mint.pubkey(), &authority).unwrap();
+
+        assert!(svm.get_accrued_ui_amount(&ata).is_err());
+    }
+
+    #[test]
+    fn test_token_metadata_initialize_instruction_fails() {
+        // Documents why create_mint_with_metadata writes the metadata itself: the
+        // bundled Token-2022 program can't realloc the mint under LiteSVM's default
+        // feature set, even when the mint is pre-funded for the larger size
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = Keypair::new();
+        let init_pointer_ix = metadata_pointer::instruction::initialize(
+            &spl_token_2022_interface::id(),
+            &mint.pubkey(),
+            Some(authority.pubkey()),
+            Some(mint.pubkey()),
+        )
+        .unwrap();
+        let mint = create_token2022_mint_with(
+            &mut svm,
+            &authority,
+            mint,
+            0,
+            &[ExtensionType::MetadataPointer],
+            vec![init_pointer_ix],
+        )
+        .unwrap();
+
+        let account = svm.get_account(&mint.pubkey()).unwrap();
+        let instructions = [
+            solana_system_interface::instruction::transfer(&authority.pubkey(), &mint.pubkey(), 1_000_000_000),
+            spl_token_metadata_interface::instruction::initialize(
+                &spl_token_2022_interface::id(),
+                &mint.pubkey(),
+                &authority.pubkey(),
+                &mint.pubkey(),
+                &authority.pubkey(),
+                "Test NFT".to_string(),
+                "TNFT".to_string(),
+                "https://example.com/nft.json".to_string(),
+            ),
+        ];
+        let tx = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&authority.pubkey()),
+            &[&authority],
+            svm.latest_blockhash(),
+        );
+
+        let err = svm.send_transaction(tx).unwrap_err();
+        assert_eq!(
+            err.err,
+            solana_sdk::transaction::TransactionError::InstructionError(
+                1,
+                solana_sdk::instruction::InstructionError::InvalidRealloc
+            )
+        );
+        assert_eq!(svm.get_account(&mint.pubkey()).unwrap().data, account.data);
+    }
+
+    #[test]
+    fn test_create_mint_with_metadata() {
+        use spl_token_2022_interface::extension::metadata_pointer::MetadataPointer;
+
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let mint = svm
+            .create_mint_with_metadata(&authority, 0, "Test NFT", "TNFT", "https://example.com/nft.json")
+            .unwrap();
+
+        let metadata = svm.get_token_metadata(&mint.pubkey()).unwrap();
+        assert_eq!(metadata.mint, mint.pubkey());
+        assert_eq!(metadata.name, "Test NFT");
+        assert_eq!(metadata.symbol, "TNFT");
+        assert_eq!(metadata.uri, "https://example.com/nft.json");
+        assert_eq!(Option::<Pubkey>::from(metadata.update_authority), Some(authority.pubkey()));
+
+        // The pointer references the mint itself and the account stays rent exempt
+        let account = svm.get_account(&mint.pubkey()).unwrap();
+        let state = StateWithExtensions::<Token2022Mint>::unpack(&account.data).unwrap();
+        let pointer = state.get_extension::<MetadataPointer>().unwrap();
+        assert_eq!(Option::<Pubkey>::from(pointer.metadata_address), Some(mint.pubkey()));
+        assert!(account.lamports >= svm.minimum_balance_for_rent_exemption(account.data.len()));
+
+        // The token program accepts the metadata for updates
+        let update_ix = spl_token_metadata_interface::instruction::update_field(
+            &spl_token_2022_interface::id(),
+            &mint.pubkey(),
+            &authority.pubkey(),
+            spl_token_metadata_interface::state::Field::Name,
+            "Renamed".to_string(),
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[update_ix],
+            Some(&authority.pubkey()),
+            &[&authority],
+            svm.latest_blockhash(),
+        );
+        svm.send_transaction(tx).unwrap();
+        assert_eq!(svm.get_token_metadata(&mint.pubkey()).unwrap().name, "Renamed");
+    }
+
+    #[test]
+    fn test_get_token_metadata_without_extension() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token2022_mint(&authority, 6, &[ExtensionType::MetadataPointer]).unwrap();
+
+        assert!(svm.get_token_metadata(&mint.pubkey()).is_err());
+    }
+
+    #[test]
+    fn test_token_helpers_with_program() {
+        for token_program in [spl_token::id(), spl_token_2022_interface::id()] {
+            let mut svm = LiteSVM::new();
+            let authority = svm.create_funded_account(10_000_000_000).unwrap();
+            let mint = svm
+                .create_token_mint_with_program(&token_program, &authority, 6)
+                .unwrap();
+            assert_eq!(svm.get_account(&mint.pubkey()).unwrap().owner, token_program);
+
+            let ata = svm
+                .create_associated_token_account_with_program(&token_program, &mint.pubkey(), &authority)
+                .unwrap();
+            assert_eq!(
+                ata,
+                get_associated_token_address_with_program_id(&authority.pubkey(), &mint.pubkey(), &token_program)
+            );
+            let pda = svm.get_pda(&[b"vault"], &Pubkey::new_unique());
+            let vault = svm
+                .create_ata_for_with_program(&token_program, &authority, &pda, &mint.pubkey())
+                .unwrap();
+
+            svm.mint_to_with_program(&token_program, &mint.pubkey(), &ata, &authority, 1_000)
+                .unwrap();
+            svm.mint_to_with_program(&token_program, &mint.pubkey(), &vault, &authority, 500)
+                .unwrap();
+            svm.assert_token_balance(&ata, 1_000);
+            svm.assert_token_balance(&vault, 500);
+            svm.assert_mint_supply(&mint.pubkey(), 1_500);
+        }
+    }
+
+    #[test]
+    fn test_transfer_burn_and_delegate() {
+        let mut svm = LiteSVM::new();
+        let owner = svm.create_funded_account(10_000_000_000).unwrap();
+        let delegate = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&owner, 6).unwrap();
+        let source = svm.create_associated_token_account(&mint.pubkey(), &owner).unwrap();
+        let destination = svm.create_associated_token_account(&mint.pubkey(), &delegate).unwrap();
+        svm.mint_to(&mint.pubkey(), &source, &owner, 1_000).unwrap();
+
+        svm.transfer_tokens(&source, &destination, &owner, 300).unwrap();
+        svm.burn_tokens(&source, &owner, 200).unwrap();
+        svm.assert_token_balance(&source, 500);
+        svm.assert_token_balance(&destination, 300);
+        svm.assert_mint_supply(&mint.pubkey(), 800);
+
+        // The delegate can move up to the approved amount
+        svm.approve_delegate(&source, &delegate.pubkey(), &owner, 100).unwrap();
+        svm.transfer_tokens(&source, &destination, &delegate, 60).unwrap();
+        assert!(svm.transfer_tokens(&source, &destination, &delegate, 60).is_err());
+
+        svm.revoke_delegate(&source, &owner).unwrap();
+        assert!(svm.burn_tokens(&source, &delegate, 1).is_err());
+        svm.assert_token_balance(&source, 440);
+        svm.assert_token_balance(&destination, 360);
+    }
+
+    #[test]
+    fn test_transfer_tokens_with_transfer_fee() {
+        let mut svm = LiteSVM::new();
+        let owner = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_mint_with_transfer_fee(&owner, 6, 100, 1_000).unwrap();
+        let source = svm.create_token2022_ata(&mint.pubkey(), &owner).unwrap();
+        let receiver = Pubkey::new_unique();
+        let destination = svm
+            .create_ata_for_with_program(&spl_token_2022_interface::id(), &owner, &receiver, &mint.pubkey())
+            .unwrap();
+        svm.mint_to_token2022(&mint.pubkey(), &source, &owner, 10_000).unwrap();
+
+        svm.transfer_tokens(&source, &destination, &owner, 10_000).unwrap();
+        svm.assert_token_balance(&destination, 9_900);
+        assert_eq!(svm.get_withheld_fees(&destination).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_freeze_and_thaw_token_account() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm
+            .create_token_mint_with_freeze_authority(&authority, 6, &authority.pubkey())
+            .unwrap();
+        let mint_data = spl_token::state::Mint::unpack(&svm.get_account(&mint.pubkey()).unwrap().data).unwrap();
+        assert_eq!(Option::<Pubkey>::from(mint_data.freeze_authority), Some(authority.pubkey()));
+
+        let source = svm.create_associated_token_account(&mint.pubkey(), &authority).unwrap();
+        let destination = svm
+            .create_ata_for(&authority, &Pubkey::new_unique(), &mint.pubkey())
+            .unwrap();
+        svm.mint_to(&mint.pubkey(), &source, &authority, 100).unwrap();
+
+        svm.freeze_token_account(&source, &authority).unwrap();
+        let token_data = spl_token::state::Account::unpack(&svm.get_account(&source).unwrap().data).unwrap();
+        assert!(token_data.is_frozen());
+        assert!(svm.transfer_tokens(&source, &destination, &authority, 5).is_err());
+
+        svm.thaw_token_account(&source, &authority).unwrap();
+        svm.transfer_tokens(&source, &destination, &authority, 10).unwrap();
+        svm.assert_token_balance(&destination, 10);
+    }
+
+    #[test]
+    fn test_freeze_without_freeze_authority_fails() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&authority, 6).unwrap();
+        let ata = svm.create_associated_token_account(&mint.pubkey(), &authority).unwrap();
+
+        assert!(svm.freeze_token_account(&ata, &authority).is_err());
+    }
+
+    #[test]
+    fn test_set_token_authority() {
+        use spl_token::instruction::AuthorityType;
+
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let new_owner = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&authority, 6).unwrap();
+        let ata = svm.create_associated_token_account(&mint.pubkey(), &authority).unwrap();
+
+        // Transfer ownership of the token account
+        svm.set_token_authority(&ata, AuthorityType::AccountOwner, &authority, Some(&new_owner.pubkey()))
+            .unwrap();
+        let token_data = spl_token::state::Account::unpack(&svm.get_account(&ata).unwrap().data).unwrap();
+        assert_eq!(token_data.owner, new_owner.pubkey());
+
+        // Burn the mint authority so no more tokens can be minted
+        svm.set_token_authority(&mint.pubkey(), AuthorityType::MintTokens, &authority, None)
+            .unwrap();
+        let mint_data = spl_token::state::Mint::unpack(&svm.get_account(&mint.pubkey()).unwrap().data).unwrap();
+        assert!(mint_data.mint_authority.is_none());
+        assert!(svm.mint_to(&mint.pubkey(), &ata, &authority, 1).is_err());
+    }
+
+    #[test]
+    fn test_set_token_authority_token2022() {
+        use spl_token::instruction::AuthorityType;
+
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token2022_mint(&authority, 6, &[]).unwrap();
+        let new_authority = Pubkey::new_unique();
+
+        svm.set_token_authority(&mint.pubkey(), AuthorityType::MintTokens, &authority, Some(&new_authority))
+            .unwrap();
+        let account = svm.get_account(&mint.pubkey()).unwrap();
+        let state = StateWithExtensions::<Token2022Mint>::unpack(&account.data).unwrap();
+        assert_eq!(Option::<Pubkey>::from(state.base.mint_authority), Some(new_authority));
+    }
+
+    #[test]
+    fn test_token_multisig() {
+        use spl_token::instruction::AuthorityType;
+
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let signers = svm.create_funded_accounts(3, 1_000_000_000).unwrap();
+        let signer_pubkeys: Vec<Pubkey> = signers.iter().map(|s| s.pubkey()).collect();
+
+        let multisig = svm.create_token_multisig(&payer, &signer_pubkeys, 2).unwrap();
+        let multisig_data =
+            spl_token::state::Multisig::unpack(&svm.get_account(&multisig.pubkey()).unwrap().data).unwrap();
+        assert_eq!(multisig_data.m, 2);
+        assert_eq!(multisig_data.n, 3);
+
+        // Hand the mint authority to the multisig and mint into a multisig-owned account
+        let mint = svm.create_token_mint(&payer, 6).unwrap();
+        svm.set_token_authority(&mint.pubkey(), AuthorityType::MintTokens, &payer, Some(&multisig.pubkey()))
+            .unwrap();
+        let vault = svm.create_ata_for(&payer, &multisig.pubkey(), &mint.pubkey()).unwrap();
+        let destination = svm.create_ata_for(&payer, &Pubkey::new_unique(), &mint.pubkey()).unwrap();
+
+        // One signer is not enough
+        assert!(svm
+            .mint_to_multisig(&mint.pubkey(), &vault, &multisig.pubkey(), &[&signers[0]], 1_000)
+            .is_err());
+        svm.mint_to_multisig(&mint.pubkey(), &vault, &multisig.pubkey(), &[&signers[0], &signers[2]], 1_000)
+            .unwrap();
+        svm.transfer_multisig(&vault, &destination, &multisig.pubkey(), &[&signers[1], &signers[2]], 400)
+            .unwrap();
+
+        svm.assert_token_balance(&vault, 600);
+        svm.assert_token_balance(&destination, 400);
+    }
+
+    #[test]
+    fn test_assert_transfer_works() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&authority, 6).unwrap();
+
+        svm.assert_transfer_works(&mint.pubkey(), &authority, 1_000_000);
+
+        // Only the transferred amount was ever minted
+        let mint_account = svm.get_account(&mint.pubkey()).unwrap();
+        let mint_data = spl_token::state::Mint::unpack(&mint_account.data).unwrap();
+        assert_eq!(mint_data.supply, 1_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Failed to mint tokens to sender")]
+    fn test_assert_transfer_works_wrong_authority() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let other = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&authority, 6).unwrap();
+
+        svm.assert_transfer_works(&mint.pubkey(), &other, 1_000_000);
+    }
+
+    #[test]
+    fn test_stake_lifecycle() {
+        let mut svm = LiteSVM::new();
+        let staker = svm.create_funded_account(10_000_000_000).unwrap();
+        let validator = Keypair::new();
+
+        let vote_account = svm.create_vote_account(&validator.pubkey()).unwrap();
+        let stake = svm.create_stake_account(&staker, 2_000_000_000).unwrap();
+        assert!(matches!(
+            svm.get_stake_state(&stake.pubkey()).unwrap(),
+            StakeStateV2::Initialized(meta) if meta.authorized.staker == staker.pubkey()
+        ));
+
+        svm.delegate_stake(&stake.pubkey(), &vote_account, &staker).unwrap();
+        let StakeStateV2::Stake(_, delegated, _) = svm.get_stake_state(&stake.pubkey()).unwrap() else {
+            panic!("Stake account is not delegated");
+        };
+        assert_eq!(delegated.delegation.voter_pubkey, vote_account);
+        assert_eq!(delegated.delegation.deactivation_epoch, u64::MAX);
+
+        svm.deactivate_stake(&stake.pubkey(), &staker).unwrap();
+        let StakeStateV2::Stake(_, deactivated, _) = svm.get_stake_state(&stake.pubkey()).unwrap() else {
+            panic!("Stake account is not delegated");
+        };
+        assert_ne!(deactivated.delegation.deactivation_epoch, u64::MAX);
+    }
+
+    #[test]
+    fn test_delegate_stake_requires_vote_account() {
+        let mut svm = LiteSVM::new();
+        let staker = svm.create_funded_account(10_000_000_000).unwrap();
+        let stake = svm.create_stake_account(&staker, 2_000_000_000).unwrap();
+
+        let err = svm.delegate_stake(&stake.pubkey(), &Pubkey::new_unique(), &staker).unwrap_err();
+        assert!(err.to_string().contains("Failed to delegate stake"));
+        assert!(svm.get_stake_state(&staker.pubkey()).is_err());
+    }
+
+    #[test]
+    fn test_advance_nonce_account() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let (nonce_account, nonce) = svm.create_nonce_account(&authority).unwrap();
+
+        let advanced = svm.advance_nonce_account(&nonce_account.pubkey(), &authority).unwrap();
+        assert_ne!(advanced, nonce);
+        assert_eq!(svm.get_durable_nonce(&nonce_account.pubkey()).unwrap(), advanced);
+
+        // Advancing with the wrong authority fails
+        let other = svm.create_funded_account(1_000_000_000).unwrap();
+        assert!(svm.advance_nonce_account(&nonce_account.pubkey(), &other).is_err());
+        assert!(svm.get_durable_nonce(&authority.pubkey()).is_err());
+    }
+
+    #[test]
+    fn test_create_nonce_account() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let (nonce_account, nonce) = svm.create_nonce_account(&authority).unwrap();
+
+        // Verify the account is owned by the system program and holds the nonce
+        let account = svm.get_account(&nonce_account.pubkey()).unwrap();
+        assert_eq!(account.owner, solana_system_interface::program::id());
+        assert_eq!(account.data.len(), NonceState::size());
+        assert_eq!(durable_nonce(&svm, &nonce_account.pubkey()), Some(nonce));
+        assert_ne!(nonce, svm.latest_blockhash());
+        assert_eq!(svm.get_durable_nonce(&nonce_account.pubkey()).unwrap(), nonce);
+    }
+
     #[test]
     fn test_derive_pda() {
         let svm = LiteSVM::new();
@@ -583,4 +2970,51 @@ mod tests {
         svm.advance_slot(5);
         assert_eq!(svm.get_current_slot(), 40);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_set_unix_timestamp_and_advance_time() {
+        let mut svm = LiteSVM::new();
+        let start = svm.get_sysvar::<solana_program::clock::Clock>();
+
+        svm.advance_time(Duration::from_secs(60));
+        let after = svm.get_sysvar::<solana_program::clock::Clock>();
+        assert_eq!(after.unix_timestamp, start.unix_timestamp + 60);
+        assert_eq!(after.slot, start.slot + 150);
+
+        svm.set_unix_timestamp(after.unix_timestamp + 4);
+        let later = svm.get_sysvar::<solana_program::clock::Clock>();
+        assert_eq!(later.unix_timestamp, after.unix_timestamp + 4);
+        assert_eq!(later.slot, after.slot + 10);
+
+        // Moving backward keeps the slot
+        svm.set_unix_timestamp(start.unix_timestamp);
+        let rewound = svm.get_sysvar::<solana_program::clock::Clock>();
+        assert_eq!(rewound.unix_timestamp, start.unix_timestamp);
+        assert_eq!(rewound.slot, later.slot);
+    }
+
+    #[test]
+    fn test_warp_to_epoch() {
+        use solana_program::clock::Clock;
+        use solana_program::epoch_schedule::EpochSchedule;
+
+        let mut svm = LiteSVM::new();
+        let schedule = svm.get_sysvar::<EpochSchedule>();
+        svm.warp_to_epoch(20);
+        let clock = svm.get_sysvar::<Clock>();
+        assert_eq!(clock.slot, schedule.get_first_slot_in_epoch(20));
+        assert_eq!(clock.epoch, 20);
+        assert_eq!(schedule.get_epoch(clock.slot), 20);
+        assert_eq!(clock.leader_schedule_epoch, 21);
+        assert_eq!(clock.epoch_start_timestamp, clock.unix_timestamp);
+        assert!(clock.unix_timestamp > 0);
+
+        // Custom schedules are respected
+        let mut svm = LiteSVM::new();
+        svm.set_sysvar(&EpochSchedule::custom(100, 100, false));
+        svm.warp_to_epoch(3);
+        let clock = svm.get_sysvar::<Clock>();
+        assert_eq!((clock.slot, clock.epoch), (300, 3));
+        assert_eq!(svm.get_current_slot(), 300);
+    }
+}
@@ -3,11 +3,20 @@
 //! This module provides convenient wrappers for executing transactions
 //! and handling their results in tests.
 
+use crate::cu_baseline::{check_baseline, DEFAULT_CU_BASELINE_PATH, UPDATE_CU_BASELINE_ENV};
+use crate::test_helpers::durable_nonce;
 use litesvm::types::TransactionMetadata;
+use solana_sdk::inner_instruction::InnerInstructionsList;
 use litesvm::LiteSVM;
 use solana_program::instruction::Instruction;
+use solana_program::pubkey::Pubkey;
+use solana_sdk::hash::Hash;
 use solana_sdk::signature::{Keypair, Signer};
-use solana_sdk::transaction::Transaction;
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_sdk::message::compiled_instruction::CompiledInstruction;
+use solana_sdk::message::{v0, AddressLookupTableAccount, Message, VersionedMessage};
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::collections::HashMap;
 use std::fmt;
 use thiserror::Error;
 
@@ -21,6 +30,369 @@ pub enum TransactionError {
 
     #[error("Assertion failed: {0}")]
     AssertionFailed(String),
+
+    #[error(
+        "Transaction too large: {size} bytes exceeds the {limit}-byte limit with {account_count} accounts. \
+         Split the instructions or move accounts into an address lookup table"
+    )]
+    TooLarge {
+        size: usize,
+        limit: usize,
+        account_count: usize,
+    },
+}
+
+/// Lamports charged per signature by LiteSVM's default fee structure
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Calculate the fee LiteSVM charges the fee payer for a transaction
+///
+/// LiteSVM charges a flat fee per signature and does not charge prioritization
+/// fees. Like the runtime, it counts the signatures verified by ed25519,
+/// secp256k1 and secp256r1 precompile instructions along with the required
+/// transaction signatures.
+///
+/// # Example
+///
+/// ```ignore
+/// let fee = transaction_fee(&tx);
+/// assert_eq!(fee, 5_000); // single signer
+/// ```
+pub fn transaction_fee(transaction: &Transaction) -> u64 {
+    let message = &transaction.message;
+    signature_fee(message.header.num_required_signatures, &message.account_keys, &message.instructions)
+}
+
+/// Fee for the transaction signatures plus the precompile signatures of the instructions
+fn signature_fee(
+    num_required_signatures: u8,
+    account_keys: &[Pubkey],
+    instructions: &[CompiledInstruction],
+) -> u64 {
+    let precompile_signatures: u64 = instructions
+        .iter()
+        .filter(|ix| {
+            account_keys.get(ix.program_id_index as usize).is_some_and(|program_id| {
+                *program_id == solana_sdk_ids::ed25519_program::ID
+                    || *program_id == solana_sdk_ids::secp256k1_program::ID
+                    || *program_id == solana_sdk_ids::secp256r1_program::ID
+            })
+        })
+        // The first byte of a precompile instruction is its signature count
+        .filter_map(|ix| ix.data.first().map(|count| *count as u64))
+        .sum();
+    (num_required_signatures as u64 + precompile_signatures) * LAMPORTS_PER_SIGNATURE
+}
+
+/// Parse an account count from a `processed N account(s)` or `accounts: N` program log
+fn processed_account_count(log: &str) -> Option<usize> {
+    let message = log.strip_prefix("Program log: ")?.to_lowercase();
+    if let Some((_, rest)) = message.split_once("processed ") {
+        let mut words = rest.split_whitespace();
+        let count = words.next()?.parse().ok()?;
+        let noun = words.next()?.trim_end_matches(|c: char| c.is_ascii_punctuation());
+        return (noun == "account" || noun == "accounts").then_some(count);
+    }
+    message.strip_prefix("accounts: ")?.trim().parse().ok()
+}
+
+/// Maximum size of a serialized transaction, the packet data limit
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Serialized size of a legacy transaction containing the given instructions
+///
+/// Includes one signature per required signer. Compare against
+/// [`MAX_TRANSACTION_SIZE`], leaving 64 bytes per signature a wallet will add.
+///
+/// # Example
+///
+/// ```ignore
+/// // Leave room for the wallet's co-signature
+/// assert!(tx_size(&ixs, &user.pubkey()) + 64 <= MAX_TRANSACTION_SIZE);
+/// ```
+pub fn tx_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction.signatures = vec![Default::default(); transaction.message.header.num_required_signatures as usize];
+    bincode::serialized_size(&transaction).expect("Failed to measure transaction") as usize
+}
+
+/// Check a transaction against [`MAX_TRANSACTION_SIZE`] before sending it
+///
+/// LiteSVM processes oversized transactions, but a cluster rejects them, so
+/// they are caught here with the numbers needed to fix them.
+///
+/// # Errors
+///
+/// Returns [`TransactionError::TooLarge`] if the serialized transaction exceeds the limit
+pub fn check_transaction_size(transaction: &Transaction) -> Result<(), TransactionError> {
+    let size = bincode::serialized_size(transaction).expect("Failed to measure transaction") as usize;
+    size_within_limit(size, transaction.message.account_keys.len())
+}
+
+/// Return [`TransactionError::TooLarge`] if `size` exceeds [`MAX_TRANSACTION_SIZE`]
+fn size_within_limit(size: usize, account_count: usize) -> Result<(), TransactionError> {
+    if size > MAX_TRANSACTION_SIZE {
+        return Err(TransactionError::TooLarge {
+            size,
+            limit: MAX_TRANSACTION_SIZE,
+            account_count,
+        });
+    }
+    Ok(())
+}
+
+/// Environment variable that makes golden log assertions rewrite their snapshot files
+pub const UPDATE_GOLDEN_ENV: &str = "UPDATE_GOLDEN";
+
+/// Replace the exact numbers in compute unit log lines with a placeholder
+///
+/// This is the default normalization for [`TransactionResult::assert_logs_match_golden`],
+/// since compute usage shifts with toolchain and dependency versions.
+///
+/// # Example
+///
+/// ```ignore
+/// assert_eq!(
+///     normalize_compute_units("Program X consumed 1234 of 200000 compute units"),
+///     "Program X consumed <N> of <N> compute units"
+/// );
+/// ```
+pub fn normalize_compute_units(log: &str) -> String {
+    if !log.contains("compute units") {
+        return log.to_string();
+    }
+    let words: Vec<&str> = log.split(' ').collect();
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            // Only numbers in "<used> of <limit> compute units", not numeric addresses
+            let is_number = !word.is_empty() && word.chars().all(|c| c.is_ascii_digit());
+            let next = words.get(i + 1).copied();
+            if is_number && matches!(next, Some("of") | Some("compute")) {
+                "<N>"
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Replace every base58 account address in a log line with a placeholder
+///
+/// Useful with [`TransactionResult::assert_logs_match_golden_with`] when logs contain
+/// addresses that change between runs, such as freshly generated keypairs.
+///
+/// # Example
+///
+/// ```ignore
+/// result.assert_logs_match_golden_with("tests/golden/init.log", |log| {
+///     normalize_addresses(&normalize_compute_units(log))
+/// });
+/// ```
+pub fn normalize_addresses(log: &str) -> String {
+    log.split(' ')
+        .map(|word| {
+            let trimmed = word.trim_end_matches([',', ':', ')', ']']);
+            if trimmed.len() >= 32 && trimmed.parse::<Pubkey>().is_ok() {
+                word.replacen(trimmed, "<address>", 1)
+            } else {
+                word.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Replace every base58 account address that has a label with that label
+///
+/// Addresses without a label are left untouched.
+///
+/// # Example
+///
+/// ```ignore
+/// let labels = HashMap::from([(vault, "vault".to_string())]);
+/// assert_eq!(label_addresses(&format!("Program log: from {}", vault), &labels), "Program log: from vault");
+/// ```
+pub fn label_addresses(text: &str, labels: &HashMap<Pubkey, String>) -> String {
+    if labels.is_empty() {
+        return text.to_string();
+    }
+    text.split(' ')
+        .map(|word| {
+            let trimmed = word.trim_end_matches([',', ':', ')', ']']).trim_start_matches(['(', '[']);
+            match trimmed.parse::<Pubkey>().ok().and_then(|address| labels.get(&address)) {
+                Some(label) if trimmed.len() >= 32 => word.replacen(trimmed, label, 1),
+                _ => word.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The change to a single account made by a transaction
+///
+/// Missing accounts (before creation or after closing) are reported with zero
+/// lamports, zero data length and the default owner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountChange {
+    /// The account address
+    pub address: Pubkey,
+    /// Lamports before the transaction
+    pub lamports_before: u64,
+    /// Lamports after the transaction
+    pub lamports_after: u64,
+    /// Data length before the transaction
+    pub data_len_before: usize,
+    /// Data length after the transaction
+    pub data_len_after: usize,
+    /// Owner before the transaction
+    pub owner_before: Pubkey,
+    /// Owner after the transaction
+    pub owner_after: Pubkey,
+    /// Whether the account data bytes changed
+    pub data_changed: bool,
+}
+
+impl AccountChange {
+    /// Change in lamports (positive if the account gained lamports)
+    pub fn lamport_delta(&self) -> i128 {
+        self.lamports_after as i128 - self.lamports_before as i128
+    }
+
+    /// Change in data length in bytes
+    pub fn data_len_delta(&self) -> i64 {
+        self.data_len_after as i64 - self.data_len_before as i64
+    }
+
+    /// Whether the account was assigned to a different owner
+    pub fn owner_changed(&self) -> bool {
+        self.owner_before != self.owner_after
+    }
+}
+
+/// A single program invocation parsed from transaction logs
+///
+/// Built by [`parse_call_graph`] from the runtime's `invoke`, `consumed`,
+/// `success` and `failed` log lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    /// The invoked program
+    pub program_id: Pubkey,
+    /// Invocation depth as logged by the runtime (1 for top-level instructions)
+    pub depth: usize,
+    /// Compute units consumed, including nested invocations
+    ///
+    /// None for builtin programs, which don't log their usage.
+    pub compute_units: Option<u64>,
+    /// Whether the invocation logged `success`
+    pub success: bool,
+    /// The error logged when the invocation failed
+    pub error: Option<String>,
+    /// `Program log:` messages emitted directly by this invocation
+    pub logs: Vec<String>,
+    /// Invocations made by this program through CPI
+    pub children: Vec<Invocation>,
+}
+
+impl Invocation {
+    /// Iterate over this invocation and all nested invocations, depth first
+    pub fn iter(&self) -> Box<dyn Iterator<Item = &Invocation> + '_> {
+        Box::new(std::iter::once(self).chain(self.children.iter().flat_map(|child| child.iter())))
+    }
+}
+
+/// Parse transaction logs into a tree of program invocations
+///
+/// Returns one root per top-level instruction. Invocations that never logged a
+/// result (for example when logs were truncated) are reported as unsuccessful
+/// without an error.
+///
+/// # Example
+///
+/// ```ignore
+/// let graph = parse_call_graph(result.logs());
+/// assert_eq!(graph[0].children[0].program_id, spl_token::id());
+/// ```
+pub fn parse_call_graph(logs: &[String]) -> Vec<Invocation> {
+    let mut roots = Vec::new();
+    let mut stack: Vec<Invocation> = Vec::new();
+
+    for log in logs {
+        let Some(rest) = log.strip_prefix("Program ") else {
+            continue;
+        };
+        if let Some(message) = rest.strip_prefix("log: ") {
+            if let Some(current) = stack.last_mut() {
+                current.logs.push(message.to_string());
+            }
+            continue;
+        }
+
+        let (program, event) = rest.split_once(' ').unwrap_or((rest, ""));
+        let Ok(program_id) = program.parse::<Pubkey>() else {
+            continue;
+        };
+        if let Some(depth) = event.strip_prefix("invoke [").and_then(|d| d.strip_suffix(']')) {
+            stack.push(Invocation {
+                program_id,
+                depth: depth.parse().unwrap_or(stack.len() + 1),
+                compute_units: None,
+                success: false,
+                error: None,
+                logs: Vec::new(),
+                children: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(current) = stack.last_mut().filter(|current| current.program_id == program_id) else {
+            continue;
+        };
+        if let Some(consumed) = event.strip_prefix("consumed ") {
+            current.compute_units = consumed.split(' ').next().and_then(|n| n.parse().ok());
+            continue;
+        }
+        if event == "success" {
+            current.success = true;
+        } else if let Some(error) = event.strip_prefix("failed: ") {
+            current.error = Some(error.to_string());
+        } else {
+            continue;
+        }
+
+        let finished = stack.pop().expect("stack is non-empty");
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(finished),
+            None => roots.push(finished),
+        }
+    }
+
+    // Unwind invocations left open by truncated logs
+    while let Some(unfinished) = stack.pop() {
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(unfinished),
+            None => roots.push(unfinished),
+        }
+    }
+    roots
+}
+
+/// An instruction invoked through CPI during a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpiInstruction {
+    /// Index of the top-level instruction that made the invocation
+    pub instruction_index: usize,
+    /// Invocation stack height (2 for a CPI made directly by a top-level instruction)
+    pub stack_height: u8,
+    /// The invoked program
+    pub program_id: Pubkey,
+    /// Accounts passed to the invoked program
+    pub accounts: Vec<Pubkey>,
+    /// Instruction data
+    pub data: Vec<u8>,
 }
 
 /// Wrapper around LiteSVM's TransactionMetadata with helper methods for testing
@@ -36,10 +408,16 @@ pub enum TransactionError {
 /// assert!(result.has_log("Transfer complete"));
 /// println!("Used {} compute units", result.compute_units());
 /// ```
+#[derive(Clone)]
 pub struct TransactionResult {
     inner: TransactionMetadata,
     instruction_name: Option<String>,
     error: Option<String>,
+    pub(crate) fee: Option<u64>,
+    error_name: Option<String>,
+    account_changes: Option<Vec<AccountChange>>,
+    account_keys: Option<Vec<Pubkey>>,
+    labels: HashMap<Pubkey, String>,
 }
 
 impl TransactionResult {
@@ -54,6 +432,11 @@ impl TransactionResult {
             inner: result,
             instruction_name,
             error: None,
+            fee: None,
+            error_name: None,
+            account_changes: None,
+            account_keys: None,
+            labels: HashMap::new(),
         }
     }
 
@@ -69,216 +452,297 @@ impl TransactionResult {
             inner: result,
             instruction_name,
             error: Some(error),
+            fee: None,
+            error_name: None,
+            account_changes: None,
+            account_keys: None,
+            labels: HashMap::new(),
         }
     }
 
-    /// Assert that the transaction succeeded, panic with logs if it failed
+    /// Attach the fee for the transaction
+    ///
+    /// LiteSVM's metadata doesn't include the fee, so callers that built the
+    /// transaction can record it here (see [`transaction_fee`]).
     ///
     /// # Returns
     ///
     /// Returns self for chaining
-    ///
-    /// # Example
-    ///
-    /// ```ignore
-    /// result.assert_success();
-    /// ```
-    pub fn assert_success(&self) -> &Self {
-        assert!(
-            self.error.is_none(),
-            "Transaction failed: {}\nLogs:\n{}",
-            self.error.as_ref().unwrap_or(&"Unknown error".to_string()),
-            self.logs().join("\n")
-        );
+    pub fn with_fee(mut self, fee: u64) -> Self {
+        self.fee = Some(fee);
         self
     }
 
-    /// Check if the transaction succeeded
-    ///
-    /// # Returns
+    /// Get the fee charged to the fee payer
     ///
-    /// true if the transaction succeeded, false otherwise
-    pub fn is_success(&self) -> bool {
-        self.error.is_none()
-    }
-
-    /// Get the error message if the transaction failed
+    /// Computed from the signature count when the transaction was sent, including
+    /// the signatures verified by ed25519, secp256k1 and secp256r1 precompile
+    /// instructions (see [`transaction_fee`]). LiteSVM charges the fee even when the
+    /// transaction fails during execution, and doesn't charge prioritization fees,
+    /// so compute unit price instructions don't change it.
     ///
     /// # Returns
     ///
-    /// The error message if the transaction failed, None otherwise
-    pub fn error(&self) -> Option<&String> {
-        self.error.as_ref()
+    /// The fee in lamports, or None if it wasn't recorded (see [`with_fee`](Self::with_fee))
+    pub fn fee_paid(&self) -> Option<u64> {
+        self.fee
     }
 
-    /// Get the transaction logs
+    /// Get the account that paid the transaction fee
     ///
     /// # Returns
     ///
-    /// A slice of log messages
-    pub fn logs(&self) -> &[String] {
-        &self.inner.logs
+    /// The first account key, or None if account keys weren't recorded
+    pub fn fee_payer(&self) -> Option<&Pubkey> {
+        self.account_keys.as_ref().and_then(|keys| keys.first())
     }
 
-    /// Check if the logs contain a specific message
+    /// Assert the fee charged for the transaction
     ///
-    /// # Arguments
+    /// # Panics
     ///
-    /// * `message` - The message to search for
+    /// Panics if the fee wasn't recorded or differs from `expected`
     ///
     /// # Returns
     ///
-    /// true if the message is found in the logs, false otherwise
-    pub fn has_log(&self, message: &str) -> bool {
-        self.inner.logs.iter().any(|log| log.contains(message))
-    }
-
-    /// Find a log entry containing the specified text
+    /// Returns self for chaining
     ///
-    /// # Arguments
+    /// # Example
     ///
-    /// * `pattern` - The pattern to search for
+    /// ```ignore
+    /// // Two signers: user and relayer
+    /// result.assert_fee_paid(2 * LAMPORTS_PER_SIGNATURE);
+    /// assert_eq!(result.fee_payer(), Some(&relayer.pubkey()));
+    /// ```
+    pub fn assert_fee_paid(&self, expected: u64) -> &Self {
+        let actual = self
+            .fee
+            .expect("Fee was not recorded for this transaction; use with_fee()");
+        assert_eq!(
+            actual, expected,
+            "Fee mismatch. Expected: {}, Actual: {}{}",
+            expected,
+            actual,
+            self.fee_payer()
+                .map(|payer| format!(" (paid by {})", self.address_text(payer)))
+                .unwrap_or_default()
+        );
+        self
+    }
+
+    /// Attach the accounts changed by the transaction
     ///
     /// # Returns
     ///
-    /// The first matching log entry, or None
-    pub fn find_log(&self, pattern: &str) -> Option<&String> {
-        self.inner.logs.iter().find(|log| log.contains(pattern))
+    /// Returns self for chaining
+    pub fn with_account_changes(mut self, changes: Vec<AccountChange>) -> Self {
+        self.account_changes = Some(changes);
+        self
     }
 
-    /// Get the compute units consumed
+    /// Attach the transaction's account keys
+    ///
+    /// Inner instructions reference programs and accounts by index, so the keys
+    /// are needed to resolve them (see [`cpis`](Self::cpis)). For v0 transactions
+    /// these are the static keys followed by the writable and then readonly
+    /// addresses loaded from lookup tables.
     ///
     /// # Returns
     ///
-    /// The number of compute units consumed
-    pub fn compute_units(&self) -> u64 {
-        self.inner.compute_units_consumed
+    /// Returns self for chaining
+    pub fn with_account_keys(mut self, keys: Vec<Pubkey>) -> Self {
+        self.account_keys = Some(keys);
+        self
     }
 
-    /// Print the transaction logs
-    pub fn print_logs(&self) {
-        println!("=== Transaction Logs ===");
-        if let Some(name) = &self.instruction_name {
-            println!("Instruction: {}", name);
-        }
-        for log in &self.inner.logs {
-            println!("{}", log);
-        }
-        if let Some(err) = &self.error {
-            println!("Error: {}", err);
-        }
-        println!("Compute Units: {}", self.compute_units());
-        println!("========================");
+    /// Get the transaction's account keys, if they were recorded
+    pub fn account_keys(&self) -> Option<&[Pubkey]> {
+        self.account_keys.as_deref()
     }
 
-    /// Get the inner TransactionMetadata for direct access
-    pub fn inner(&self) -> &TransactionMetadata {
-        &self.inner
+    /// Get the raw inner instructions, grouped by top-level instruction
+    pub fn inner_instructions(&self) -> &InnerInstructionsList {
+        &self.inner.inner_instructions
     }
 
-    /// Assert that the transaction failed
+    /// Get all instructions invoked through CPI, with program IDs and accounts resolved
     ///
     /// # Panics
     ///
-    /// Panics if the transaction succeeded
+    /// Panics if the result was created without account keys
     ///
-    /// # Returns
+    /// # Example
     ///
-    /// Returns self for chaining
+    /// ```ignore
+    /// let result = svm.send_instruction(ix, &[&user])?;
+    /// for cpi in result.cpis() {
+    ///     println!("{} invoked {}", cpi.instruction_index, cpi.program_id);
+    /// }
+    /// ```
+    pub fn cpis(&self) -> Vec<CpiInstruction> {
+        let keys = self
+            .account_keys
+            .as_ref()
+            .expect("Account keys were not recorded for this transaction; use with_account_keys()");
+        let key = |index: u8| keys.get(index as usize).copied().unwrap_or_default();
+
+        self.inner
+            .inner_instructions
+            .iter()
+            .enumerate()
+            .flat_map(|(instruction_index, inner)| {
+                inner.iter().map(move |ix| CpiInstruction {
+                    instruction_index,
+                    stack_height: ix.stack_height,
+                    program_id: key(ix.instruction.program_id_index),
+                    accounts: ix.instruction.accounts.iter().map(|i| key(*i)).collect(),
+                    data: ix.instruction.data.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Get the total number of CPIs made by the transaction
+    pub fn cpi_count(&self) -> usize {
+        self.inner.inner_instructions.iter().map(Vec::len).sum()
+    }
+
+    /// Get the number of CPIs made to a specific program
     ///
     /// # Example
     ///
     /// ```ignore
-    /// result.assert_failure();
+    /// assert_eq!(result.cpi_count_to(&spl_token::id()), 1);
     /// ```
-    pub fn assert_failure(&self) -> &Self {
+    pub fn cpi_count_to(&self, program_id: &Pubkey) -> usize {
+        self.cpis().iter().filter(|cpi| cpi.program_id == *program_id).count()
+    }
+
+    /// Assert that the transaction invoked a program through CPI
+    ///
+    /// # Panics
+    ///
+    /// Panics if no CPI to `program_id` was made
+    pub fn assert_cpi_to(&self, program_id: &Pubkey) -> &Self {
+        let cpis = self.cpis();
         assert!(
-            self.error.is_some(),
-            "Expected transaction to fail, but it succeeded.\nLogs:\n{}",
-            self.logs().join("\n")
+            cpis.iter().any(|cpi| cpi.program_id == *program_id),
+            "Expected a CPI to {}, but the transaction invoked: {:?}\nLogs:\n{}",
+            self.address_text(program_id),
+            cpis.iter().map(|cpi| self.address_text(&cpi.program_id)).collect::<Vec<_>>(),
+            self.logs_text()
         );
         self
     }
 
-    /// Assert that the transaction failed with a specific error message
+    /// Parse the logs into a tree of program invocations
     ///
-    /// # Arguments
+    /// See [`parse_call_graph`].
     ///
-    /// * `expected_error` - The expected error message (substring match)
+    /// # Example
     ///
-    /// # Panics
+    /// ```ignore
+    /// let graph = result.call_graph();
+    /// let token_cpi = &graph[0].children[0];
+    /// assert_eq!(token_cpi.program_id, spl_token::id());
+    /// assert!(token_cpi.success);
+    /// assert!(token_cpi.compute_units.unwrap() < 5_000);
+    /// ```
+    pub fn call_graph(&self) -> Vec<Invocation> {
+        parse_call_graph(&self.inner.logs)
+    }
+
+    /// Get the accounts changed by the transaction
     ///
-    /// Panics if the transaction succeeded or failed with a different error
+    /// Only accounts whose lamports, data or owner changed are included, so the
+    /// fee payer always appears.
     ///
-    /// # Returns
+    /// # Panics
     ///
-    /// Returns self for chaining
+    /// Panics if account changes were not recorded for this transaction
     ///
     /// # Example
     ///
     /// ```ignore
-    /// result.assert_error("insufficient funds");
+    /// let changed: Vec<_> = result.account_changes().iter().map(|c| c.address).collect();
+    /// assert_eq!(changed, vec![payer.pubkey(), vault]);
     /// ```
-    pub fn assert_error(&self, expected_error: &str) -> &Self {
-        match &self.error {
-            Some(error) => {
-                assert!(
-                    error.contains(expected_error),
-                    "Transaction failed with unexpected error.\nExpected substring: {}\nActual error: {}\nLogs:\n{}",
-                    expected_error,
-                    error,
-                    self.logs().join("\n")
-                );
-            }
-            None => {
-                panic!(
-                    "Expected transaction to fail with error containing '{}', but it succeeded.\nLogs:\n{}",
-                    expected_error,
-                    self.logs().join("\n")
-                );
-            }
-        }
-        self
+    pub fn account_changes(&self) -> &[AccountChange] {
+        self.account_changes
+            .as_deref()
+            .expect("Account changes were not recorded. Enable account diffs on the context first.")
     }
 
-    /// Assert that the transaction failed with a specific error code
+    /// Get the change to a specific account, or None if it didn't change
     ///
-    /// This is useful for asserting Anchor custom errors.
+    /// # Panics
     ///
-    /// # Arguments
+    /// Panics if account changes were not recorded for this transaction
+    pub fn account_change(&self, address: &Pubkey) -> Option<&AccountChange> {
+        self.account_changes().iter().find(|change| change.address == *address)
+    }
+
+    /// Resolve the custom error code of a failed transaction to a name
     ///
-    /// * `error_code` - The expected error code number
+    /// The name is shown alongside the raw error by `assert_success`, `assert_error`
+    /// and `print_logs`, and `assert_error` also matches against it.
     ///
-    /// # Panics
+    /// # Arguments
     ///
-    /// Panics if the transaction succeeded or failed with a different error code
+    /// * `names` - Error names by custom error code, e.g. from an Anchor IDL
     ///
     /// # Returns
     ///
     /// Returns self for chaining
+    pub fn with_error_names(mut self, names: &HashMap<u32, String>) -> Self {
+        self.error_name = self.anchor_error_code().and_then(|code| names.get(&code).cloned());
+        self
+    }
+
+    /// Get the name of the custom error the transaction failed with, if known
     ///
-    /// # Example
-    ///
-    /// ```ignore
-    /// // Assert that transaction failed with custom error code 6000
-    /// result.assert_error_code(6000);
-    /// ```
-    pub fn assert_error_code(&self, error_code: u32) -> &Self {
-        let error_code_str = format!("custom program error: 0x{:x}", error_code);
-        self.assert_error(&error_code_str)
+    /// See [`TransactionResult::with_error_names`].
+    pub fn error_name(&self) -> Option<&str> {
+        self.error_name.as_deref()
     }
 
-    /// Assert that the transaction failed with a specific Anchor error
+    /// Attach human-readable names for account addresses
     ///
-    /// This checks for Anchor's error code format in the logs.
+    /// Labeled addresses are shown by name in `print_logs`, `print_account_changes`
+    /// and assertion panic messages.
     ///
     /// # Arguments
     ///
-    /// * `error_name` - The name of the Anchor error
-    ///
-    /// # Panics
-    ///
-    /// Panics if the transaction succeeded or the error wasn't found in logs
+    /// * `labels` - Names by account address
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    pub fn with_labels(mut self, labels: &HashMap<Pubkey, String>) -> Self {
+        self.labels = labels.clone();
+        self
+    }
+
+    /// The error message, followed by the resolved error name if known
+    fn describe_error(&self) -> Option<String> {
+        let error = self.error.as_ref()?;
+        Some(match &self.error_name {
+            Some(name) => format!("{} ({})", label_addresses(error, &self.labels), name),
+            None => label_addresses(error, &self.labels),
+        })
+    }
+
+    /// The logs joined into one string with labeled addresses substituted, for panic messages
+    fn logs_text(&self) -> String {
+        label_addresses(&self.logs().join("\n"), &self.labels)
+    }
+
+    /// An address, or its label if one was attached
+    fn address_text(&self, address: &Pubkey) -> String {
+        self.labels.get(address).cloned().unwrap_or_else(|| address.to_string())
+    }
+
+    /// Assert that the transaction succeeded, panic with logs if it failed
     ///
     /// # Returns
     ///
@@ -287,239 +751,1784 @@ impl TransactionResult {
     /// # Example
     ///
     /// ```ignore
-    /// // Assert that transaction failed with Anchor error
-    /// result.assert_anchor_error("InsufficientFunds");
+    /// result.assert_success();
     /// ```
-    pub fn assert_anchor_error(&self, error_name: &str) -> &Self {
-        self.assert_failure();
-
-        // Check if error name appears in logs
-        let found_in_logs = self.logs().iter().any(|log| log.contains(error_name));
-
-        // Also check the error message
-        let found_in_error = self.error
-            .as_ref()
-            .map(|e| e.contains(error_name))
-            .unwrap_or(false);
-
+    pub fn assert_success(&self) -> &Self {
         assert!(
-            found_in_logs || found_in_error,
-            "Expected Anchor error '{}' not found in transaction logs or error message.\nError: {:?}\nLogs:\n{}",
-            error_name,
-            self.error,
-            self.logs().join("\n")
+            self.error.is_none(),
+            "Transaction failed{}: {}\nLogs:\n{}",
+            self.instruction_name.as_ref().map(|name| format!(" ({})", name)).unwrap_or_default(),
+            self.describe_error().unwrap_or_else(|| "Unknown error".to_string()),
+            self.logs_text()
         );
         self
     }
 
-    /// Assert that the logs contain a specific error message
+    /// Get the name of the instruction this result belongs to, if one was given
+    pub fn instruction_name(&self) -> Option<&str> {
+        self.instruction_name.as_deref()
+    }
+
+    /// Check if the transaction succeeded
     ///
-    /// Unlike `assert_error`, this only checks the logs, not the error field.
+    /// # Returns
     ///
-    /// # Arguments
+    /// true if the transaction succeeded, false otherwise
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Get the error message if the transaction failed
     ///
-    /// * `error_message` - The expected error message in logs
+    /// # Returns
     ///
-    /// # Panics
+    /// The error message if the transaction failed, None otherwise
+    pub fn error(&self) -> Option<&String> {
+        self.error.as_ref()
+    }
+
+    /// Get the custom program error code if the transaction failed with one
     ///
-    /// Panics if the error message is not found in logs
+    /// Anchor programs report their `#[error_code]` variants as custom errors
+    /// starting at 6000, so the returned value can be matched against the
+    /// `u32` values of an error enum generated by `declare_program!`.
     ///
     /// # Returns
     ///
-    /// Returns self for chaining
+    /// The custom error code, or None if the transaction succeeded or failed
+    /// with a non-custom error
     ///
     /// # Example
     ///
     /// ```ignore
-    /// result.assert_log_error("Transfer amount exceeds balance");
+    /// // First variant of the program's #[error_code] enum
+    /// assert_eq!(result.anchor_error_code(), Some(6000));
     /// ```
-    pub fn assert_log_error(&self, error_message: &str) -> &Self {
-        assert!(
-            self.has_log(error_message),
-            "Expected error message '{}' not found in logs.\nLogs:\n{}",
-            error_message,
-            self.logs().join("\n")
-        );
-        self
+    pub fn anchor_error_code(&self) -> Option<u32> {
+        let error = self.error.as_ref()?;
+
+        // Errors are formatted from TransactionError, e.g. "InstructionError(0, Custom(6000))"
+        if let Some(code) = error
+            .split("Custom(")
+            .nth(1)
+            .and_then(|rest| rest.split(')').next())
+            .and_then(|code| code.parse().ok())
+        {
+            return Some(code);
+        }
+
+        // Fall back to the runtime log line, e.g. "custom program error: 0x1770"
+        self.logs().iter().find_map(|log| {
+            let hex = log.split("custom program error: 0x").nth(1)?;
+            u32::from_str_radix(hex.trim(), 16).ok()
+        })
     }
-}
 
-impl fmt::Debug for TransactionResult {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("TransactionResult")
-            .field("instruction", &self.instruction_name)
-            .field("success", &self.is_success())
-            .field("error", &self.error())
-            .field("compute_units", &self.compute_units())
-            .field("log_count", &self.logs().len())
-            .finish()
+    /// Get the transaction logs
+    ///
+    /// # Returns
+    ///
+    /// A slice of log messages
+    pub fn logs(&self) -> &[String] {
+        &self.inner.logs
     }
-}
 
-/// Transaction helper methods for LiteSVM
-pub trait TransactionHelpers {
-    /// Send a single instruction and return a wrapped result
+    /// Check if the logs contain a specific message
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message to search for
+    ///
+    /// # Returns
+    ///
+    /// true if the message is found in the logs, false otherwise
+    pub fn has_log(&self, message: &str) -> bool {
+        self.inner.logs.iter().any(|log| log.contains(message))
+    }
+
+    /// Find a log entry containing the specified text
+    ///
+    /// # Arguments
+    ///
+    /// * `pattern` - The pattern to search for
+    ///
+    /// # Returns
+    ///
+    /// The first matching log entry, or None
+    pub fn find_log(&self, pattern: &str) -> Option<&String> {
+        self.inner.logs.iter().find(|log| log.contains(pattern))
+    }
+
+    /// Check if any log line matches a regular expression
+    ///
+    /// Useful when logs embed values that vary between runs, such as amounts or
+    /// addresses.
     ///
     /// # Example
-    /// ```no_run
-    /// # use litesvm_utils::TransactionHelpers;
-    /// # use litesvm::LiteSVM;
-    /// # use solana_program::instruction::Instruction;
-    /// # use solana_sdk::signature::Keypair;
-    /// # let mut svm = LiteSVM::new();
-    /// # let ix = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
-    /// # let signer = Keypair::new();
-    /// let result = svm.send_instruction(ix, &[&signer]).unwrap();
-    /// result.assert_success();
+    ///
+    /// ```ignore
+    /// let re = Regex::new(r"Deposited \d+ lamports").unwrap();
+    /// assert!(result.has_log_matching(&re));
     /// ```
-    fn send_instruction(
-        &mut self,
-        instruction: Instruction,
-        signers: &[&Keypair],
-    ) -> Result<TransactionResult, TransactionError>;
+    #[cfg(feature = "regex")]
+    pub fn has_log_matching(&self, regex: &regex::Regex) -> bool {
+        self.inner.logs.iter().any(|log| regex.is_match(log))
+    }
 
-    /// Send multiple instructions in a single transaction
+    /// Find all log lines matching a regular expression
+    ///
+    /// # Returns
+    ///
+    /// The matching log lines, in order
     ///
     /// # Example
-    /// ```no_run
-    /// # use litesvm_utils::TransactionHelpers;
-    /// # use litesvm::LiteSVM;
-    /// # use solana_program::instruction::Instruction;
-    /// # use solana_sdk::signature::Keypair;
-    /// # let mut svm = LiteSVM::new();
-    /// # let ix1 = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
-    /// # let ix2 = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
-    /// # let signer = Keypair::new();
-    /// let result = svm.send_instructions(&[ix1, ix2], &[&signer]).unwrap();
-    /// result.assert_success();
+    ///
+    /// ```ignore
+    /// let re = Regex::new(r"fee: (\d+)").unwrap();
+    /// let fees: Vec<u64> = result
+    ///     .find_logs_matching(&re)
+    ///     .iter()
+    ///     .map(|log| re.captures(log).unwrap()[1].parse().unwrap())
+    ///     .collect();
     /// ```
-    fn send_instructions(
-        &mut self,
-        instructions: &[Instruction],
-        signers: &[&Keypair],
-    ) -> Result<TransactionResult, TransactionError>;
+    #[cfg(feature = "regex")]
+    pub fn find_logs_matching(&self, regex: &regex::Regex) -> Vec<&String> {
+        self.inner.logs.iter().filter(|log| regex.is_match(log)).collect()
+    }
 
-    /// Send a transaction and return a wrapped result
+    /// Get the log lines emitted by a specific program
+    ///
+    /// Only lines logged while `program_id` was the executing program are
+    /// returned, so text logged by programs it invokes (such as the token
+    /// program) is excluded. The runtime's invoke, consumed and result markers
+    /// are excluded as well. Lines from every invocation of the program are
+    /// included, in order.
     ///
     /// # Example
-    /// ```no_run
-    /// # use litesvm_utils::TransactionHelpers;
-    /// # use litesvm::LiteSVM;
-    /// # use solana_sdk::transaction::Transaction;
-    /// # use solana_program::instruction::Instruction;
-    /// # use solana_sdk::signature::{Keypair, Signer};
-    /// # let mut svm = LiteSVM::new();
-    /// # let ix = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
-    /// # let signer = Keypair::new();
-    /// let tx = Transaction::new_signed_with_payer(
-    ///     &[ix],
-    ///     Some(&signer.pubkey()),
-    ///     &[&signer],
-    ///     svm.latest_blockhash(),
-    /// );
-    /// let result = svm.send_transaction_result(tx).unwrap();
-    /// result.assert_success();
+    ///
+    /// ```ignore
+    /// let logs = result.logs_for(&program_id);
+    /// assert!(logs.iter().any(|log| log.contains("Transfer")));
     /// ```
-    fn send_transaction_result(
-        &mut self,
-        transaction: Transaction,
-    ) -> Result<TransactionResult, TransactionError>;
-}
+    pub fn logs_for(&self, program_id: &Pubkey) -> Vec<&String> {
+        self.scoped_logs(|stack| stack.last() == Some(program_id))
+    }
 
-impl TransactionHelpers for LiteSVM {
-    fn send_instruction(
-        &mut self,
-        instruction: Instruction,
-        signers: &[&Keypair],
-    ) -> Result<TransactionResult, TransactionError> {
-        if signers.is_empty() {
-            return Err(TransactionError::BuildError("No signers provided".to_string()));
-        }
+    /// Get the logs emitted while the invocation stack satisfies `include`, without invoke markers
+    fn scoped_logs(&self, include: impl Fn(&[Pubkey]) -> bool) -> Vec<&String> {
+        let mut stack: Vec<Pubkey> = Vec::new();
+        let mut logs = Vec::new();
 
-        let tx = Transaction::new_signed_with_payer(
-            &[instruction],
-            Some(&signers[0].pubkey()),
-            signers,
-            self.latest_blockhash(),
-        );
+        for log in &self.inner.logs {
+            let marker = log
+                .strip_prefix("Program ")
+                .and_then(|rest| rest.split_once(' '))
+                .and_then(|(program, event)| Some((program.parse::<Pubkey>().ok()?, event)));
+            if let Some((program, event)) = marker {
+                if event.starts_with("invoke [") {
+                    stack.push(program);
+                    continue;
+                }
+                if event == "success" || event.starts_with("failed") {
+                    stack.pop();
+                    continue;
+                }
+                if event.starts_with("consumed ") {
+                    continue;
+                }
+            }
+            if include(&stack) {
+                logs.push(log);
+            }
+        }
+        logs
+    }
 
-        self.send_transaction_result(tx)
+    /// Get the compute units consumed
+    ///
+    /// # Returns
+    ///
+    /// The number of compute units consumed
+    pub fn compute_units(&self) -> u64 {
+        self.inner.compute_units_consumed
     }
 
-    fn send_instructions(
-        &mut self,
-        instructions: &[Instruction],
-        signers: &[&Keypair],
-    ) -> Result<TransactionResult, TransactionError> {
-        if signers.is_empty() {
-            return Err(TransactionError::BuildError("No signers provided".to_string()));
-        }
+    /// Get the compute units consumed by each top-level instruction
+    ///
+    /// Parsed from the `consumed` log lines, in instruction order. Builtin
+    /// programs such as the system program don't log their usage and are
+    /// reported as 0, as are instructions that never ran because an earlier one
+    /// failed.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let result = svm.send_instructions(&[setup_ix, swap_ix], &[&user])?;
+    /// let per_ix = result.compute_units_per_instruction();
+    /// assert!(per_ix[1] < 50_000, "swap used {} CU", per_ix[1]);
+    /// ```
+    pub fn compute_units_per_instruction(&self) -> Vec<u64> {
+        let roots = self.call_graph();
+        let count = self.inner.inner_instructions.len().max(roots.len());
+        (0..count)
+            .map(|i| roots.get(i).and_then(|root| root.compute_units).unwrap_or(0))
+            .collect()
+    }
 
-        let tx = Transaction::new_signed_with_payer(
-            instructions,
-            Some(&signers[0].pubkey()),
-            signers,
-            self.latest_blockhash(),
-        );
+    /// Get the data returned by the transaction via `set_return_data`
+    ///
+    /// # Returns
+    ///
+    /// The data set by the last program to call `set_return_data`, or None if no
+    /// data was returned
+    pub fn return_data(&self) -> Option<&[u8]> {
+        let return_data = &self.inner.return_data;
+        (!return_data.data.is_empty()).then_some(return_data.data.as_slice())
+    }
 
-        self.send_transaction_result(tx)
+    /// Get the program that set the transaction's return data
+    pub fn return_data_program(&self) -> Option<&Pubkey> {
+        self.return_data().map(|_| &self.inner.return_data.program_id)
     }
 
-    fn send_transaction_result(
-        &mut self,
-        transaction: Transaction,
-    ) -> Result<TransactionResult, TransactionError> {
-        match self.send_transaction(transaction) {
-            Ok(result) => Ok(TransactionResult::new(result, None)),
-            Err(failed) => {
-                // Return a failed transaction result with metadata
-                Ok(TransactionResult::new_failed(
-                    format!("{:?}", failed.err),
-                    failed.meta,
-                    None,
-                ))
-            }
+    /// Print the transaction logs
+    pub fn print_logs(&self) {
+        println!("=== Transaction Logs ===");
+        if let Some(name) = &self.instruction_name {
+            println!("Instruction: {}", name);
+        }
+        for log in &self.inner.logs {
+            println!("{}", label_addresses(log, &self.labels));
         }
+        if let Some(err) = self.describe_error() {
+            println!("Error: {}", err);
+        }
+        println!("Compute Units: {}", self.compute_units());
+        println!("========================");
+    }
+
+    /// Print the accounts changed by the transaction
+    ///
+    /// # Panics
+    ///
+    /// Panics if account changes were not recorded for this transaction
+    pub fn print_account_changes(&self) {
+        println!("=== Account Changes ===");
+        for line in self.account_changes_text() {
+            println!("{}", line);
+        }
+        println!("=======================");
+    }
+
+    /// One line per changed account, with labeled addresses substituted
+    fn account_changes_text(&self) -> Vec<String> {
+        self.account_changes()
+            .iter()
+            .map(|change| {
+                let mut line = format!(
+                    "{}: lamports {} -> {} ({:+})",
+                    self.address_text(&change.address),
+                    change.lamports_before,
+                    change.lamports_after,
+                    change.lamport_delta()
+                );
+                if change.data_len_before != change.data_len_after || change.data_changed {
+                    line.push_str(&format!(", data {} -> {} bytes", change.data_len_before, change.data_len_after));
+                }
+                if change.owner_before != change.owner_after {
+                    line.push_str(&format!(
+                        ", owner {} -> {}",
+                        self.address_text(&change.owner_before),
+                        self.address_text(&change.owner_after)
+                    ));
+                }
+                line
+            })
+            .collect()
+    }
+
+    /// Build a machine-readable report of the transaction for CI artifacts
+    ///
+    /// The report contains the instruction name, success flag, error, compute
+    /// units, fee, logs, inner instructions and account changes. Fields that
+    /// weren't recorded for this result (fee, inner instructions without
+    /// account keys, account changes) are `null`. Addresses are base58 and
+    /// instruction data is hex encoded.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let report = result.to_json();
+    /// std::fs::write("target/tx-report.json", report.to_string())?;
+    /// ```
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "instruction": self.instruction_name,
+            "success": self.is_success(),
+            "error": self.error,
+            "error_name": self.error_name,
+            "compute_units": self.compute_units(),
+            "fee": self.fee,
+            "logs": self.logs(),
+            "inner_instructions": self.account_keys.as_ref().map(|_| {
+                self.cpis()
+                    .iter()
+                    .map(|cpi| {
+                        serde_json::json!({
+                            "instruction_index": cpi.instruction_index,
+                            "stack_height": cpi.stack_height,
+                            "program_id": cpi.program_id.to_string(),
+                            "accounts": cpi.accounts.iter().map(Pubkey::to_string).collect::<Vec<_>>(),
+                            "data": cpi.data.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            }),
+            "account_changes": self.account_changes.as_ref().map(|changes| {
+                changes
+                    .iter()
+                    .map(|change| {
+                        serde_json::json!({
+                            "address": change.address.to_string(),
+                            "lamports_before": change.lamports_before,
+                            "lamports_after": change.lamports_after,
+                            "data_len_before": change.data_len_before,
+                            "data_len_after": change.data_len_after,
+                            "owner_before": change.owner_before.to_string(),
+                            "owner_after": change.owner_after.to_string(),
+                            "data_changed": change.data_changed,
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            }),
+        })
+    }
+
+    /// Get the inner TransactionMetadata for direct access
+    pub fn inner(&self) -> &TransactionMetadata {
+        &self.inner
+    }
+
+    /// Assert that the transaction failed
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction succeeded
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_failure();
+    /// ```
+    pub fn assert_failure(&self) -> &Self {
+        assert!(
+            self.error.is_some(),
+            "Expected transaction to fail, but it succeeded.\nLogs:\n{}",
+            self.logs_text()
+        );
+        self
+    }
+
+    /// Assert that the transaction failed with a specific error message
+    ///
+    /// # Arguments
+    ///
+    /// * `expected_error` - The expected error message (substring match)
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction succeeded or failed with a different error
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_error("insufficient funds");
+    /// ```
+    pub fn assert_error(&self, expected_error: &str) -> &Self {
+        match &self.error {
+            Some(error) => {
+                let name_matches = self.error_name.as_ref().is_some_and(|name| name.contains(expected_error));
+                assert!(
+                    error.contains(expected_error) || name_matches,
+                    "Transaction failed with unexpected error.\nExpected substring: {}\nActual error: {}\nLogs:\n{}",
+                    expected_error,
+                    self.describe_error().unwrap_or_default(),
+                    self.logs_text()
+                );
+            }
+            None => {
+                panic!(
+                    "Expected transaction to fail with error containing '{}', but it succeeded.\nLogs:\n{}",
+                    expected_error,
+                    self.logs_text()
+                );
+            }
+        }
+        self
+    }
+
+    /// Assert that the transaction failed with a specific error code
+    ///
+    /// This is useful for asserting Anchor custom errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `error_code` - The expected error code number
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction succeeded or failed with a different error code
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Assert that transaction failed with custom error code 6000
+    /// result.assert_error_code(6000);
+    /// ```
+    pub fn assert_error_code(&self, error_code: u32) -> &Self {
+        let error_code_str = format!("custom program error: 0x{:x}", error_code);
+        self.assert_error(&error_code_str)
+    }
+
+    /// Assert that the transaction failed with a specific Anchor error
+    ///
+    /// This checks for Anchor's error code format in the logs.
+    ///
+    /// # Arguments
+    ///
+    /// * `error_name` - The name of the Anchor error
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transaction succeeded or the error wasn't found in logs
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // Assert that transaction failed with Anchor error
+    /// result.assert_anchor_error("InsufficientFunds");
+    /// ```
+    pub fn assert_anchor_error(&self, error_name: &str) -> &Self {
+        self.assert_failure();
+
+        // Check if error name appears in logs
+        let found_in_logs = self.logs().iter().any(|log| log.contains(error_name));
+
+        // Also check the error message
+        let found_in_error = self.error
+            .as_ref()
+            .map(|e| e.contains(error_name))
+            .unwrap_or(false);
+
+        assert!(
+            found_in_logs || found_in_error,
+            "Expected Anchor error '{}' not found in transaction logs or error message.\nError: {:?}\nLogs:\n{}",
+            error_name,
+            self.error,
+            self.logs_text()
+        );
+        self
+    }
+
+    /// Assert that the logs contain a specific error message
+    ///
+    /// Unlike `assert_error`, this only checks the logs, not the error field.
+    ///
+    /// # Arguments
+    ///
+    /// * `error_message` - The expected error message in logs
+    ///
+    /// # Panics
+    ///
+    /// Panics if the error message is not found in logs
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_log_error("Transfer amount exceeds balance");
+    /// ```
+    pub fn assert_log_error(&self, error_message: &str) -> &Self {
+        assert!(
+            self.has_log(error_message),
+            "Expected error message '{}' not found in logs.\nLogs:\n{}",
+            error_message,
+            self.logs_text()
+        );
+        self
+    }
+
+    /// Assert that log messages appear in the given relative order
+    ///
+    /// Each message must be contained in a log line after the line matched by
+    /// the previous message. Other log lines may appear in between.
+    ///
+    /// # Arguments
+    ///
+    /// * `messages` - The expected messages, in order
+    ///
+    /// # Panics
+    ///
+    /// Panics if a message is missing or only appears before the previous one
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_logs_in_order(&["deposit recorded", "fee charged", "event emitted"]);
+    /// ```
+    pub fn assert_logs_in_order(&self, messages: &[&str]) -> &Self {
+        let logs = self.logs();
+        let mut next = 0;
+        for (i, message) in messages.iter().enumerate() {
+            match logs[next..].iter().position(|log| log.contains(message)) {
+                Some(offset) => next += offset + 1,
+                None if i > 0 && self.has_log(message) => panic!(
+                    "Log '{}' appears before '{}', expected it after.\nLogs:\n{}",
+                    message,
+                    messages[i - 1],
+                    self.logs_text()
+                ),
+                None => panic!(
+                    "Expected log '{}' not found.\nLogs:\n{}",
+                    message,
+                    self.logs_text()
+                ),
+            }
+        }
+        self
+    }
+
+    /// Assert that the program reported processing a specific number of accounts
+    ///
+    /// The count is read from the first top-level `Program log:` line of the form
+    /// `processed N account(s)` or `accounts: N`, case-insensitively, e.g.
+    /// `Program log: processed 3 accounts`. Logs from CPI callees are ignored; use
+    /// [`assert_processed_accounts_for`](Self::assert_processed_accounts_for) to
+    /// read a specific program's logs. This cross-checks that the program saw the
+    /// accounts the test intended to pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `expected` - The expected number of processed accounts
+    ///
+    /// # Panics
+    ///
+    /// Panics if no account count is logged or the logged count differs
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_processed_accounts(4);
+    /// ```
+    pub fn assert_processed_accounts(&self, expected: usize) -> &Self {
+        // Logs outside any invocation are kept for results built without invoke markers
+        let logs = self.scoped_logs(|stack| stack.len() <= 1);
+        self.check_processed_accounts(logs, expected)
+    }
+
+    /// Assert that a specific program reported processing a number of accounts
+    ///
+    /// Like [`assert_processed_accounts`](Self::assert_processed_accounts), but
+    /// only reads the logs emitted by `program_id`, which may be a CPI callee.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the program logged no account count or the logged count differs
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_processed_accounts_for(&vault_program_id, 2);
+    /// ```
+    pub fn assert_processed_accounts_for(&self, program_id: &Pubkey, expected: usize) -> &Self {
+        self.check_processed_accounts(self.logs_for(program_id), expected)
+    }
+
+    fn check_processed_accounts(&self, logs: Vec<&String>, expected: usize) -> &Self {
+        let actual = logs.into_iter().find_map(|log| processed_account_count(log));
+
+        match actual {
+            Some(actual) => assert_eq!(
+                actual, expected,
+                "Processed account count mismatch. Expected: {}, Actual: {}\nLogs:\n{}",
+                expected,
+                actual,
+                self.logs_text()
+            ),
+            None => panic!(
+                "No processed account count found in logs.\nLogs:\n{}",
+                self.logs_text()
+            ),
+        }
+        self
+    }
+
+    /// Assert that the transaction logs match a committed golden snapshot
+    ///
+    /// Logs are normalized with [`normalize_compute_units`] before comparing. Set the
+    /// `UPDATE_GOLDEN` environment variable to write the current logs to `path` instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the golden file is missing or the logs differ from it
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// // UPDATE_GOLDEN=1 cargo test to (re)write the snapshot
+    /// result.assert_logs_match_golden("tests/golden/initialize.log");
+    /// ```
+    pub fn assert_logs_match_golden(&self, path: &str) -> &Self {
+        self.assert_logs_match_golden_with(path, normalize_compute_units)
+    }
+
+    /// Assert that the transaction logs match a golden snapshot using a custom normalization
+    ///
+    /// Each log line is passed through `normalize` before comparing or writing, which
+    /// allows stripping any nondeterministic parts of the output.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the golden file is missing or the logs differ from it
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_logs_match_golden_with("tests/golden/initialize.log", |log| {
+    ///     normalize_addresses(&normalize_compute_units(log))
+    /// });
+    /// ```
+    pub fn assert_logs_match_golden_with(
+        &self,
+        path: &str,
+        normalize: impl Fn(&str) -> String,
+    ) -> &Self {
+        let update = std::env::var_os(UPDATE_GOLDEN_ENV).is_some_and(|v| !v.is_empty());
+        self.check_golden(path, normalize, update);
+        self
+    }
+
+    /// Assert that the transaction consumed fewer than `max` compute units
+    ///
+    /// # Panics
+    ///
+    /// Panics with the actual usage and the logs if `max` is reached or exceeded
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_success().assert_compute_under(200_000);
+    /// ```
+    pub fn assert_compute_under(&self, max: u64) -> &Self {
+        let actual = self.compute_units();
+        assert!(
+            actual < max,
+            "Compute units exceeded. Expected under: {}, Actual: {}\nLogs:\n{}",
+            max,
+            actual,
+            self.logs_text()
+        );
+        self
+    }
+
+    /// Assert that compute usage hasn't regressed against the checked-in baseline
+    ///
+    /// Compares against the entry `name` in [`DEFAULT_CU_BASELINE_PATH`], failing if
+    /// usage exceeds it by more than `tolerance_pct` percent. Missing entries are
+    /// recorded; run with `UPDATE_CU_BASELINE=1` to accept new values.
+    ///
+    /// # Panics
+    ///
+    /// Panics if compute usage regressed beyond the tolerance
+    ///
+    /// # Returns
+    ///
+    /// Returns self for chaining
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// result.assert_compute_within_baseline("test_swap", 5.0);
+    /// ```
+    pub fn assert_compute_within_baseline(&self, name: &str, tolerance_pct: f64) -> &Self {
+        self.assert_compute_within_baseline_at(DEFAULT_CU_BASELINE_PATH, name, tolerance_pct)
+    }
+
+    /// Assert that compute usage hasn't regressed against a baseline stored at `path`
+    ///
+    /// See [`assert_compute_within_baseline`](Self::assert_compute_within_baseline).
+    pub fn assert_compute_within_baseline_at(&self, path: &str, name: &str, tolerance_pct: f64) -> &Self {
+        let update = std::env::var_os(UPDATE_CU_BASELINE_ENV).is_some_and(|v| !v.is_empty());
+        check_baseline(std::path::Path::new(path), name, self.compute_units(), tolerance_pct, update);
+        self
+    }
+
+    /// Compare normalized logs against a golden file, or rewrite it when `update` is set
+    fn check_golden(&self, path: &str, normalize: impl Fn(&str) -> String, update: bool) {
+        let actual: Vec<String> = self.logs().iter().map(|log| normalize(log)).collect();
+
+        if update {
+            if let Some(parent) = std::path::Path::new(path).parent() {
+                std::fs::create_dir_all(parent)
+                    .unwrap_or_else(|e| panic!("Failed to create directory for {}: {}", path, e));
+            }
+            let mut contents = actual.join("\n");
+            contents.push('\n');
+            std::fs::write(path, contents)
+                .unwrap_or_else(|e| panic!("Failed to write golden file {}: {}", path, e));
+            return;
+        }
+
+        let golden = std::fs::read_to_string(path).unwrap_or_else(|e| {
+            panic!(
+                "Failed to read golden file {}: {}. Run with {}=1 to create it.",
+                path, e, UPDATE_GOLDEN_ENV
+            )
+        });
+        let expected: Vec<&str> = golden.lines().collect();
+
+        if let Some(line) = (0..actual.len().max(expected.len()))
+            .find(|&i| actual.get(i).map(String::as_str) != expected.get(i).copied())
+        {
+            panic!(
+                "Logs don't match golden file {} at line {}. Expected: {:?}, Actual: {:?}\nRun with {}=1 to update it.\nLogs:\n{}",
+                path,
+                line + 1,
+                expected.get(line).copied().unwrap_or("<end of file>"),
+                actual.get(line).map(String::as_str).unwrap_or("<end of logs>"),
+                UPDATE_GOLDEN_ENV,
+                actual.join("\n")
+            );
+        }
+    }
+}
+
+impl fmt::Debug for TransactionResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TransactionResult")
+            .field("instruction", &self.instruction_name)
+            .field("success", &self.is_success())
+            .field("error", &self.error())
+            .field("compute_units", &self.compute_units())
+            .field("log_count", &self.logs().len())
+            .finish()
+    }
+}
+
+/// Transaction helper methods for LiteSVM
+pub trait TransactionHelpers {
+    /// Send a single instruction and return a wrapped result
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TransactionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::instruction::Instruction;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let ix = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
+    /// # let signer = Keypair::new();
+    /// let result = svm.send_instruction(ix, &[&signer]).unwrap();
+    /// result.assert_success();
+    /// ```
+    fn send_instruction(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<TransactionResult, TransactionError>;
+
+    /// Send multiple instructions in a single transaction
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TransactionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::instruction::Instruction;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let ix1 = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
+    /// # let ix2 = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
+    /// # let signer = Keypair::new();
+    /// let result = svm.send_instructions(&[ix1, ix2], &[&signer]).unwrap();
+    /// result.assert_success();
+    /// ```
+    fn send_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<TransactionResult, TransactionError>;
+
+    /// Send instructions in a transaction signed against a specific blockhash
+    ///
+    /// Combined with `expire_blockhash`, this simulates a client sending with a
+    /// stale blockhash, which fails with `BlockhashNotFound`. Resending an identical
+    /// transaction fails with `AlreadyProcessed`. The first signer pays the fee.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TransactionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::instruction::Instruction;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let ix = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
+    /// # let signer = Keypair::new();
+    /// let stale = svm.latest_blockhash();
+    /// svm.expire_blockhash();
+    /// let result = svm.send_with_blockhash(&[ix], &[&signer], stale).unwrap();
+    /// result.assert_error("BlockhashNotFound");
+    /// ```
+    fn send_with_blockhash(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+        blockhash: Hash,
+    ) -> Result<TransactionResult, TransactionError>;
+
+    /// Send a transaction and return a wrapped result
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TransactionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_sdk::transaction::Transaction;
+    /// # use solana_program::instruction::Instruction;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let ix = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
+    /// # let signer = Keypair::new();
+    /// let tx = Transaction::new_signed_with_payer(
+    ///     &[ix],
+    ///     Some(&signer.pubkey()),
+    ///     &[&signer],
+    ///     svm.latest_blockhash(),
+    /// );
+    /// let result = svm.send_transaction_result(tx).unwrap();
+    /// result.assert_success();
+    /// ```
+    fn send_transaction_result(
+        &mut self,
+        transaction: Transaction,
+    ) -> Result<TransactionResult, TransactionError>;
+
+    /// Send a versioned transaction and return a wrapped result
+    ///
+    /// Accepts both legacy and v0 messages, so transactions built by production
+    /// code paths can be replayed with full assertion support.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TransactionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::instruction::Instruction;
+    /// # use solana_sdk::message::{v0, VersionedMessage};
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # use solana_sdk::transaction::VersionedTransaction;
+    /// # let mut svm = LiteSVM::new();
+    /// # let ix = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
+    /// # let signer = Keypair::new();
+    /// let message = v0::Message::try_compile(&signer.pubkey(), &[ix], &[], svm.latest_blockhash()).unwrap();
+    /// let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), &[&signer]).unwrap();
+    /// let result = svm.send_versioned_transaction_result(tx).unwrap();
+    /// result.assert_success();
+    /// ```
+    fn send_versioned_transaction_result(
+        &mut self,
+        transaction: VersionedTransaction,
+    ) -> Result<TransactionResult, TransactionError>;
+
+    /// Send instructions in a transaction that uses a durable nonce instead of a recent blockhash
+    ///
+    /// An `AdvanceNonceAccount` instruction is prepended, and the nonce account's
+    /// current durable nonce is used as the transaction's blockhash. The nonce is
+    /// advanced when the transaction is processed. The first signer pays the fee;
+    /// the nonce authority is added to the signers if it isn't already present.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::{TestHelpers, TransactionHelpers};
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::instruction::Instruction;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let ix = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
+    /// # let authority = Keypair::new();
+    /// let (nonce_account, _) = svm.create_nonce_account(&authority).unwrap();
+    /// svm.expire_blockhash();
+    /// let result = svm
+    ///     .send_with_nonce(&[ix], &nonce_account.pubkey(), &authority, &[&authority])
+    ///     .unwrap();
+    /// result.assert_success();
+    /// ```
+    fn send_with_nonce(
+        &mut self,
+        instructions: &[Instruction],
+        nonce_account: &Pubkey,
+        nonce_authority: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<TransactionResult, TransactionError>;
+
+    /// Send a single instruction in a transaction that uses a durable nonce
+    ///
+    /// Convenience wrapper around [`send_with_nonce`](Self::send_with_nonce).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::{TestHelpers, TransactionHelpers};
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::instruction::Instruction;
+    /// # use solana_sdk::signature::{Keypair, Signer};
+    /// # let mut svm = LiteSVM::new();
+    /// # let ix = Instruction::new_with_bytes(solana_program::pubkey::Pubkey::new_unique(), &[], vec![]);
+    /// # let authority = Keypair::new();
+    /// let (nonce_account, _) = svm.create_nonce_account(&authority).unwrap();
+    /// svm.expire_blockhash();
+    /// let result = svm
+    ///     .send_instruction_with_nonce(ix, &[&authority], &nonce_account.pubkey(), &authority)
+    ///     .unwrap();
+    /// result.assert_success();
+    /// ```
+    fn send_instruction_with_nonce(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+        nonce_account: &Pubkey,
+        nonce_authority: &Keypair,
+    ) -> Result<TransactionResult, TransactionError> {
+        self.send_with_nonce(&[instruction], nonce_account, nonce_authority, signers)
+    }
+
+    /// Send a single instruction in a v0 transaction that resolves accounts through lookup tables
+    ///
+    /// Accounts found in `lookup_tables` are referenced by index instead of being
+    /// included in the message, which allows transactions with more accounts than
+    /// fit in a legacy transaction. The first signer pays the fee.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::{TestHelpers, TransactionHelpers};
+    /// # use litesvm::LiteSVM;
+    /// # use solana_program::instruction::Instruction;
+    /// # use solana_program::pubkey::Pubkey;
+    /// # use solana_sdk::signature::Keypair;
+    /// # let mut svm = LiteSVM::new();
+    /// # let payer = Keypair::new();
+    /// # let ix = Instruction::new_with_bytes(Pubkey::new_unique(), &[], vec![]);
+    /// # let accounts: Vec<Pubkey> = vec![];
+    /// let table = svm.create_lookup_table(&payer, &accounts).unwrap();
+    /// let result = svm.send_v0_instruction(ix, &[&payer], &[table]).unwrap();
+    /// result.assert_success();
+    /// ```
+    fn send_v0_instruction(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+        lookup_tables: &[Pubkey],
+    ) -> Result<TransactionResult, TransactionError> {
+        self.send_v0_instructions(&[instruction], signers, lookup_tables)
+    }
+
+    /// Send multiple instructions in a v0 transaction that resolves accounts through lookup tables
+    fn send_v0_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+        lookup_tables: &[Pubkey],
+    ) -> Result<TransactionResult, TransactionError>;
+
+    /// Deserialize and send a pre-signed transaction
+    ///
+    /// The bytes are bincode-decoded as a `VersionedTransaction`, which also accepts
+    /// the legacy transaction wire format. Useful for running transactions produced
+    /// by external tools such as wallets or TypeScript clients.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use litesvm_utils::TransactionHelpers;
+    /// # use litesvm::LiteSVM;
+    /// # let mut svm = LiteSVM::new();
+    /// # let bytes: Vec<u8> = vec![];
+    /// let result = svm.send_serialized_transaction(&bytes).unwrap();
+    /// result.assert_success();
+    /// ```
+    fn send_serialized_transaction(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<TransactionResult, TransactionError>;
+}
+
+impl TransactionHelpers for LiteSVM {
+    fn send_with_nonce(
+        &mut self,
+        instructions: &[Instruction],
+        nonce_account: &Pubkey,
+        nonce_authority: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<TransactionResult, TransactionError> {
+        if signers.is_empty() {
+            return Err(TransactionError::BuildError("No signers provided".to_string()));
+        }
+
+        let nonce = durable_nonce(self, nonce_account).ok_or_else(|| {
+            TransactionError::BuildError(format!("{} is not an initialized nonce account", nonce_account))
+        })?;
+
+        // The advance instruction must come first for the runtime to treat this as a nonce transaction
+        let mut all_instructions = vec![solana_system_interface::instruction::advance_nonce_account(
+            nonce_account,
+            &nonce_authority.pubkey(),
+        )];
+        all_instructions.extend_from_slice(instructions);
+
+        let mut all_signers = signers.to_vec();
+        if !all_signers.iter().any(|s| s.pubkey() == nonce_authority.pubkey()) {
+            all_signers.push(nonce_authority);
+        }
+
+        let tx = Transaction::new_signed_with_payer(
+            &all_instructions,
+            Some(&signers[0].pubkey()),
+            &all_signers,
+            nonce,
+        );
+
+        self.send_transaction_result(tx)
+    }
+
+    fn send_instruction(
+        &mut self,
+        instruction: Instruction,
+        signers: &[&Keypair],
+    ) -> Result<TransactionResult, TransactionError> {
+        if signers.is_empty() {
+            return Err(TransactionError::BuildError("No signers provided".to_string()));
+        }
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&signers[0].pubkey()),
+            signers,
+            self.latest_blockhash(),
+        );
+
+        self.send_transaction_result(tx)
+    }
+
+    fn send_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<TransactionResult, TransactionError> {
+        if signers.is_empty() {
+            return Err(TransactionError::BuildError("No signers provided".to_string()));
+        }
+
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&signers[0].pubkey()),
+            signers,
+            self.latest_blockhash(),
+        );
+
+        self.send_transaction_result(tx)
+    }
+
+    fn send_with_blockhash(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+        blockhash: Hash,
+    ) -> Result<TransactionResult, TransactionError> {
+        if signers.is_empty() {
+            return Err(TransactionError::BuildError("No signers provided".to_string()));
+        }
+
+        let tx = Transaction::new_signed_with_payer(instructions, Some(&signers[0].pubkey()), signers, blockhash);
+
+        self.send_transaction_result(tx)
+    }
+
+    fn send_transaction_result(
+        &mut self,
+        transaction: Transaction,
+    ) -> Result<TransactionResult, TransactionError> {
+        check_transaction_size(&transaction)?;
+        let fee = transaction_fee(&transaction);
+        let account_keys = transaction.message.account_keys.clone();
+        let result = match self.send_transaction(transaction) {
+            Ok(result) => TransactionResult::new(result, None),
+            Err(failed) => {
+                // Return a failed transaction result with metadata
+                TransactionResult::new_failed(format!("{:?}", failed.err), failed.meta, None)
+            }
+        };
+        Ok(result.with_fee(fee).with_account_keys(account_keys))
+    }
+
+    fn send_versioned_transaction_result(
+        &mut self,
+        transaction: VersionedTransaction,
+    ) -> Result<TransactionResult, TransactionError> {
+        let fee = signature_fee(
+            transaction.message.header().num_required_signatures,
+            transaction.message.static_account_keys(),
+            transaction.message.instructions(),
+        );
+        let account_keys = versioned_account_keys(self, &transaction.message);
+        let size = bincode::serialized_size(&transaction).expect("Failed to measure transaction") as usize;
+        size_within_limit(size, account_keys.len())?;
+        let result = match self.send_transaction(transaction) {
+            Ok(result) => TransactionResult::new(result, None),
+            Err(failed) => TransactionResult::new_failed(format!("{:?}", failed.err), failed.meta, None),
+        };
+        Ok(result.with_fee(fee).with_account_keys(account_keys))
+    }
+
+    fn send_serialized_transaction(
+        &mut self,
+        bytes: &[u8],
+    ) -> Result<TransactionResult, TransactionError> {
+        let transaction: VersionedTransaction = bincode::deserialize(bytes).map_err(|e| {
+            TransactionError::BuildError(format!("Failed to deserialize transaction: {}", e))
+        })?;
+
+        self.send_versioned_transaction_result(transaction)
+    }
+
+    fn send_v0_instructions(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+        lookup_tables: &[Pubkey],
+    ) -> Result<TransactionResult, TransactionError> {
+        if signers.is_empty() {
+            return Err(TransactionError::BuildError("No signers provided".to_string()));
+        }
+
+        let lookup_table_accounts = lookup_tables
+            .iter()
+            .map(|table| load_lookup_table(self, table))
+            .collect::<Result<Vec<_>, _>>()?;
+        let message = v0::Message::try_compile(
+            &signers[0].pubkey(),
+            instructions,
+            &lookup_table_accounts,
+            self.latest_blockhash(),
+        )
+        .map_err(|e| TransactionError::BuildError(format!("Failed to compile v0 message: {}", e)))?;
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), signers)
+            .map_err(|e| TransactionError::BuildError(format!("Failed to sign v0 transaction: {}", e)))?;
+
+        self.send_versioned_transaction_result(transaction)
+    }
+}
+
+/// Collect a message's account keys in runtime order, resolving lookup table addresses
+///
+/// Addresses from tables that can't be read are skipped.
+fn versioned_account_keys(svm: &LiteSVM, message: &VersionedMessage) -> Vec<Pubkey> {
+    let mut keys = message.static_account_keys().to_vec();
+    if let Some(lookups) = message.address_table_lookups() {
+        let tables: Vec<_> = lookups
+            .iter()
+            .filter_map(|lookup| load_lookup_table(svm, &lookup.account_key).ok().map(|table| (lookup, table)))
+            .collect();
+        let resolve = |table: &AddressLookupTableAccount, indexes: &[u8]| {
+            indexes
+                .iter()
+                .filter_map(|i| table.addresses.get(*i as usize).copied())
+                .collect::<Vec<_>>()
+        };
+        for (lookup, table) in &tables {
+            keys.extend(resolve(table, &lookup.writable_indexes));
+        }
+        for (lookup, table) in &tables {
+            keys.extend(resolve(table, &lookup.readonly_indexes));
+        }
+    }
+    keys
+}
+
+/// Read the addresses stored in a lookup table account
+fn load_lookup_table(svm: &LiteSVM, table: &Pubkey) -> Result<AddressLookupTableAccount, TransactionError> {
+    let account = svm
+        .get_account(table)
+        .ok_or_else(|| TransactionError::BuildError(format!("Lookup table {} not found", table)))?;
+    let lookup_table = AddressLookupTable::deserialize(&account.data)
+        .map_err(|e| TransactionError::BuildError(format!("Invalid lookup table {}: {}", table, e)))?;
+    Ok(AddressLookupTableAccount {
+        key: *table,
+        addresses: lookup_table.addresses.to_vec(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::TestHelpers;
+    use solana_system_interface::instruction as system_instruction;
+
+    #[test]
+    fn test_transaction_result_success() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient = Keypair::new();
+
+        // Create a simple transfer instruction
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        assert!(result.is_success());
+        assert_eq!(result.error(), None);
+        result.assert_success();
+    }
+
+    #[test]
+    fn test_transaction_result_has_log() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient = Keypair::new();
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        // System program logs typically contain "invoke" messages
+        assert!(result.has_log("invoke"));
+    }
+
+    #[test]
+    fn test_transaction_result_find_log() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient = Keypair::new();
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        // Should find a log containing "invoke"
+        let log = result.find_log("invoke");
+        assert!(log.is_some());
+    }
+
+    #[test]
+    fn test_transaction_result_compute_units() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient = Keypair::new();
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        // Simple transfer should consume some compute units
+        let cu = result.compute_units();
+        assert!(cu > 0);
+        assert!(cu < 1_000_000); // Should be reasonable
+    }
+
+    #[test]
+    fn test_transaction_result_logs() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient = Keypair::new();
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        let logs = result.logs();
+        assert!(!logs.is_empty());
+    }
+
+    #[test]
+    fn test_transaction_result_inner() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient = Keypair::new();
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        // Should be able to access inner metadata
+        let _inner = result.inner();
+        assert!(_inner.compute_units_consumed > 0);
+    }
+
+    #[test]
+    fn test_transaction_result_failure() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new(); // Unfunded account
+
+        // This should fail due to insufficient funds
+        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        assert!(!result.is_success());
+        assert!(result.error().is_some());
+    }
+
+    #[test]
+    fn test_transaction_result_assert_failure() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new(); // Unfunded account
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        // Should not panic when asserting failure on a failed transaction
+        result.assert_failure();
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected transaction to fail")]
+    fn test_transaction_result_assert_failure_on_success() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        // Should panic when asserting failure on a successful transaction
+        result.assert_failure();
+    }
+
+    #[test]
+    fn test_transaction_result_assert_error() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new(); // Unfunded account
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        // Should contain "AccountNotFound" in the error (account doesn't exist)
+        result.assert_error("AccountNotFound");
+    }
+
+    #[test]
+    #[should_panic(expected = "Transaction failed with unexpected error")]
+    fn test_transaction_result_assert_error_wrong_message() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new(); // Unfunded account
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        // Should panic when expecting wrong error message
+        result.assert_error("this error does not exist");
+    }
+
+    #[test]
+    fn test_anchor_error_code() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&authority, 9).unwrap();
+        let ata = svm.create_associated_token_account(&mint.pubkey(), &authority).unwrap();
+
+        // Minting with the wrong authority fails with TokenError::OwnerMismatch (4)
+        let wrong_authority = svm.create_funded_account(10_000_000_000).unwrap();
+        let ix = spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &ata,
+            &wrong_authority.pubkey(),
+            &[],
+            100,
+        )
+        .unwrap();
+        let result = svm.send_instruction(ix, &[&wrong_authority]).unwrap();
+
+        assert_eq!(result.anchor_error_code(), Some(4));
+    }
+
+    #[test]
+    fn test_anchor_error_code_parses_anchor_range() {
+        let result = TransactionResult::new_failed(
+            "InstructionError(0, Custom(6001))".to_string(),
+            TransactionMetadata::default(),
+            None,
+        );
+        assert_eq!(result.anchor_error_code(), Some(6001));
+    }
+
+    #[test]
+    fn test_account_change_deltas() {
+        let address = Pubkey::new_unique();
+        let change = AccountChange {
+            address,
+            lamports_before: 1_000,
+            lamports_after: 400,
+            data_len_before: 0,
+            data_len_after: 165,
+            owner_before: Pubkey::default(),
+            owner_after: spl_token::id(),
+            data_changed: true,
+        };
+        assert_eq!(change.lamport_delta(), -600);
+        assert_eq!(change.data_len_delta(), 165);
+        assert!(change.owner_changed());
+
+        let result = TransactionResult::new(TransactionMetadata::default(), None)
+            .with_account_changes(vec![change.clone()]);
+        assert_eq!(result.account_changes(), &[change]);
+        assert!(result.account_change(&address).is_some());
+        assert!(result.account_change(&Pubkey::new_unique()).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "Account changes were not recorded")]
+    fn test_account_changes_not_recorded() {
+        TransactionResult::new(TransactionMetadata::default(), None).account_changes();
+    }
+
+    #[test]
+    fn test_with_error_names() {
+        let names = HashMap::from([(6001, "InsufficientFunds".to_string())]);
+        let result = TransactionResult::new_failed(
+            "InstructionError(0, Custom(6001))".to_string(),
+            TransactionMetadata::default(),
+            None,
+        )
+        .with_error_names(&names);
+
+        assert_eq!(result.error_name(), Some("InsufficientFunds"));
+        result.assert_error("InsufficientFunds");
+        result.assert_error("Custom(6001)");
+
+        let unknown = TransactionResult::new_failed(
+            "InstructionError(0, Custom(6002))".to_string(),
+            TransactionMetadata::default(),
+            None,
+        )
+        .with_error_names(&names);
+        assert_eq!(unknown.error_name(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Custom(6001)) (InsufficientFunds)")]
+    fn test_assert_success_shows_error_name() {
+        let names = HashMap::from([(6001, "InsufficientFunds".to_string())]);
+        TransactionResult::new_failed(
+            "InstructionError(0, Custom(6001))".to_string(),
+            TransactionMetadata::default(),
+            None,
+        )
+        .with_error_names(&names)
+        .assert_success();
+    }
+
+    #[test]
+    fn test_anchor_error_code_none() {
+        let mut svm = LiteSVM::new();
+        let payer = Keypair::new(); // Unfunded account
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        assert_eq!(result.anchor_error_code(), None);
+    }
+
+    /// Build a successful transaction result with the given log lines
+    fn result_with_logs(logs: &[&str]) -> TransactionResult {
+        TransactionResult::new(
+            TransactionMetadata {
+                logs: logs.iter().map(|l| l.to_string()).collect(),
+                ..Default::default()
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn test_assert_processed_accounts() {
+        let result = result_with_logs(&[
+            "Program 11111111111111111111111111111111 invoke [1]",
+            "Program log: Instruction: Settle",
+            "Program log: processed 3 accounts",
+        ]);
+        result.assert_processed_accounts(3);
+
+        let result = result_with_logs(&["Program log: Accounts: 5"]);
+        result.assert_processed_accounts(5);
+    }
+
+    #[test]
+    fn test_assert_processed_accounts_patterns() {
+        result_with_logs(&["Program log: Processed 1 account"]).assert_processed_accounts(1);
+        result_with_logs(&["Program log: settle: processed 2 accounts."]).assert_processed_accounts(2);
+
+        // Numbers that aren't an account count are skipped
+        result_with_logs(&[
+            "Program log: transferring 500 lamports between accounts",
+            "Program log: processed 7 instructions",
+            "Program log: processed 4 accounts",
+        ])
+        .assert_processed_accounts(4);
+    }
+
+    #[test]
+    fn test_assert_processed_accounts_ignores_cpi_logs() {
+        let caller = Pubkey::new_unique();
+        let callee = Pubkey::new_unique();
+        let logs = [
+            format!("Program {} invoke [1]", caller),
+            format!("Program {} invoke [2]", callee),
+            "Program log: processed 9 accounts".to_string(),
+            format!("Program {} success", callee),
+            "Program log: processed 2 accounts".to_string(),
+            format!("Program {} success", caller),
+        ];
+        let result = result_with_logs(&logs.iter().map(String::as_str).collect::<Vec<_>>());
+        result
+            .assert_processed_accounts(2)
+            .assert_processed_accounts_for(&callee, 9)
+            .assert_processed_accounts_for(&caller, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "No processed account count found")]
+    fn test_assert_processed_accounts_unrelated_number() {
+        result_with_logs(&["Program log: transferring 500 lamports between accounts"]).assert_processed_accounts(500);
+    }
+
+    #[test]
+    #[should_panic(expected = "Processed account count mismatch")]
+    fn test_assert_processed_accounts_mismatch() {
+        let result = result_with_logs(&["Program log: processed 2 accounts"]);
+        result.assert_processed_accounts(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "No processed account count found")]
+    fn test_assert_processed_accounts_missing() {
+        let result = result_with_logs(&["Program log: Instruction: Settle"]);
+        result.assert_processed_accounts(3);
+    }
+
+    #[test]
+    fn test_assert_compute_under() {
+        let result = TransactionResult::new(
+            TransactionMetadata {
+                compute_units_consumed: 1_500,
+                ..Default::default()
+            },
+            None,
+        );
+        result.assert_compute_under(1_501);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected under: 1500, Actual: 1500")]
+    fn test_assert_compute_under_exceeded() {
+        let result = TransactionResult::new(
+            TransactionMetadata {
+                compute_units_consumed: 1_500,
+                ..Default::default()
+            },
+            None,
+        );
+        result.assert_compute_under(1_500);
+    }
+
+    #[test]
+    fn test_fee_paid() {
+        let mut svm = LiteSVM::new();
+        let relayer = svm.create_funded_account(10_000_000_000).unwrap();
+        let user = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let ix = system_instruction::transfer(&user.pubkey(), &Pubkey::new_unique(), 1_000_000);
+        let result = svm.send_instruction(ix, &[&relayer, &user]).unwrap();
+        result.assert_success().assert_fee_paid(2 * LAMPORTS_PER_SIGNATURE);
+        assert_eq!(result.fee_paid(), Some(2 * LAMPORTS_PER_SIGNATURE));
+        assert_eq!(result.fee_payer(), Some(&relayer.pubkey()));
+        assert_eq!(
+            svm.get_balance(&relayer.pubkey()).unwrap(),
+            10_000_000_000 - 2 * LAMPORTS_PER_SIGNATURE
+        );
+
+        // Priority fees aren't charged
+        let ixs = [
+            // SetComputeUnitPrice(1_000_000)
+            Instruction::new_with_bytes(
+                solana_sdk_ids::compute_budget::id(),
+                &[&[3u8][..], &1_000_000u64.to_le_bytes()].concat(),
+                vec![],
+            ),
+            system_instruction::transfer(&relayer.pubkey(), &Pubkey::new_unique(), 1_000_000),
+        ];
+        let balance = svm.get_balance(&relayer.pubkey()).unwrap();
+        let result = svm.send_instructions(&ixs, &[&relayer]).unwrap();
+        result.assert_success().assert_fee_paid(LAMPORTS_PER_SIGNATURE);
+        assert_eq!(svm.get_balance(&relayer.pubkey()).unwrap(), balance - 1_000_000 - LAMPORTS_PER_SIGNATURE);
+
+        let unrecorded = TransactionResult::new(TransactionMetadata::default(), None);
+        assert_eq!(unrecorded.fee_paid(), None);
+        assert_eq!(unrecorded.fee_payer(), None);
+    }
+
+    /// Build an ed25519 precompile instruction verifying one signature by `signer`
+    fn ed25519_verify_instruction(signer: &Keypair, message: &[u8]) -> Instruction {
+        // Header: signature count and padding, then 7 u16 offsets; the data follows
+        let (public_key_offset, signature_offset, message_offset) = (16u16, 48u16, 112u16);
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset,
+            u16::MAX,
+            public_key_offset,
+            u16::MAX,
+            message_offset,
+            message.len() as u16,
+            u16::MAX,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(signer.pubkey().as_ref());
+        data.extend_from_slice(signer.sign_message(message).as_ref());
+        data.extend_from_slice(message);
+        Instruction::new_with_bytes(solana_sdk_ids::ed25519_program::ID, &data, vec![])
+    }
+
+    #[test]
+    fn test_transaction_fee_counts_precompile_signatures() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let ixs = [
+            ed25519_verify_instruction(&Keypair::new(), b"attestation"),
+            system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000),
+        ];
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&payer.pubkey()), &[&payer], svm.latest_blockhash());
+        assert_eq!(transaction_fee(&tx), 2 * LAMPORTS_PER_SIGNATURE);
+
+        // litesvm is built without its `precompiles` feature, so stand in a program at the
+        // ed25519 address; the fee depends only on the program id and signature count
+        let program_bytes = svm.get_account(&spl_token::id()).unwrap().data;
+        svm.add_program(solana_sdk_ids::ed25519_program::ID, &program_bytes).unwrap();
+        svm.send_transaction_result(tx).unwrap().assert_failure();
+        assert_eq!(
+            svm.get_balance(&payer.pubkey()).unwrap(),
+            10_000_000_000 - 2 * LAMPORTS_PER_SIGNATURE
+        );
+    }
+
+    #[test]
+    fn test_fee_paid_with_precompile() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let program_bytes = svm.get_account(&spl_token::id()).unwrap().data;
+        svm.add_program(solana_sdk_ids::ed25519_program::ID, &program_bytes).unwrap();
+
+        let ix = ed25519_verify_instruction(&Keypair::new(), b"attestation");
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+        result.assert_fee_paid(2 * LAMPORTS_PER_SIGNATURE);
+        assert_eq!(
+            10_000_000_000 - svm.get_balance(&payer.pubkey()).unwrap(),
+            result.fee_paid().unwrap()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Fee mismatch")]
+    fn test_assert_fee_paid_mismatch() {
+        let result = TransactionResult::new(TransactionMetadata::default(), None).with_fee(LAMPORTS_PER_SIGNATURE);
+        result.assert_fee_paid(2 * LAMPORTS_PER_SIGNATURE);
+    }
+
+    #[test]
+    fn test_send_multiple_instructions() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient1 = Keypair::new();
+        let recipient2 = Keypair::new();
+
+        // Send two transfers in one transaction
+        let ix1 = system_instruction::transfer(&payer.pubkey(), &recipient1.pubkey(), 1_000_000);
+        let ix2 = system_instruction::transfer(&payer.pubkey(), &recipient2.pubkey(), 2_000_000);
+
+        let result = svm.send_instructions(&[ix1, ix2], &[&payer]).unwrap();
+        result.assert_success();
+
+        // Verify both transfers succeeded
+        let balance1 = svm.get_balance(&recipient1.pubkey()).unwrap();
+        let balance2 = svm.get_balance(&recipient2.pubkey()).unwrap();
+        assert_eq!(balance1, 1_000_000);
+        assert_eq!(balance2, 2_000_000);
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::test_helpers::TestHelpers;
-    use solana_system_interface::instruction as system_instruction;
 
     #[test]
-    fn test_transaction_result_success() {
+    fn test_send_instruction_no_signers() {
         let mut svm = LiteSVM::new();
-        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let payer = Keypair::new();
         let recipient = Keypair::new();
 
-        // Create a simple transfer instruction
         let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
 
-        let result = svm.send_instruction(ix, &[&payer]).unwrap();
-
-        assert!(result.is_success());
-        assert_eq!(result.error(), None);
-        result.assert_success();
+        // Should error when no signers provided
+        let result = svm.send_instruction(ix, &[]);
+        assert!(result.is_err());
+        match result {
+            Err(TransactionError::BuildError(msg)) => {
+                assert!(msg.contains("No signers"));
+            }
+            _ => panic!("Expected BuildError"),
+        }
     }
 
     #[test]
-    fn test_transaction_result_has_log() {
+    fn test_send_instructions_no_signers() {
         let mut svm = LiteSVM::new();
-        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let payer = Keypair::new();
         let recipient = Keypair::new();
 
         let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
-        let result = svm.send_instruction(ix, &[&payer]).unwrap();
 
-        // System program logs typically contain "invoke" messages
-        assert!(result.has_log("invoke"));
+        // Should error when no signers provided
+        let result = svm.send_instructions(&[ix], &[]);
+        assert!(result.is_err());
     }
 
     #[test]
-    fn test_transaction_result_find_log() {
+    fn test_transaction_result_debug() {
         let mut svm = LiteSVM::new();
         let payer = svm.create_funded_account(10_000_000_000).unwrap();
         let recipient = Keypair::new();
@@ -527,13 +2536,13 @@ mod tests {
         let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
         let result = svm.send_instruction(ix, &[&payer]).unwrap();
 
-        // Should find a log containing "invoke"
-        let log = result.find_log("invoke");
-        assert!(log.is_some());
+        // Should be able to format as debug
+        let debug_str = format!("{:?}", result);
+        assert!(debug_str.contains("TransactionResult"));
     }
 
     #[test]
-    fn test_transaction_result_compute_units() {
+    fn test_transaction_result_print_logs() {
         let mut svm = LiteSVM::new();
         let payer = svm.create_funded_account(10_000_000_000).unwrap();
         let recipient = Keypair::new();
@@ -541,27 +2550,32 @@ mod tests {
         let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
         let result = svm.send_instruction(ix, &[&payer]).unwrap();
 
-        // Simple transfer should consume some compute units
-        let cu = result.compute_units();
-        assert!(cu > 0);
-        assert!(cu < 1_000_000); // Should be reasonable
+        // Should not panic when printing logs
+        result.print_logs();
     }
 
     #[test]
-    fn test_transaction_result_logs() {
+    fn test_transaction_fee() {
         let mut svm = LiteSVM::new();
         let payer = svm.create_funded_account(10_000_000_000).unwrap();
         let recipient = Keypair::new();
 
         let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
-        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            svm.latest_blockhash(),
+        );
+        let fee = transaction_fee(&tx);
+        assert_eq!(fee, LAMPORTS_PER_SIGNATURE);
 
-        let logs = result.logs();
-        assert!(!logs.is_empty());
+        svm.send_transaction_result(tx).unwrap().assert_success();
+        assert_eq!(svm.get_balance(&payer.pubkey()).unwrap(), 10_000_000_000 - 1_000_000 - fee);
     }
 
     #[test]
-    fn test_transaction_result_inner() {
+    fn test_transaction_result_to_json() {
         let mut svm = LiteSVM::new();
         let payer = svm.create_funded_account(10_000_000_000).unwrap();
         let recipient = Keypair::new();
@@ -569,152 +2583,649 @@ mod tests {
         let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
         let result = svm.send_instruction(ix, &[&payer]).unwrap();
 
-        // Should be able to access inner metadata
-        let _inner = result.inner();
-        assert!(_inner.compute_units_consumed > 0);
+        let json = result.to_json();
+        assert_eq!(json["success"], true);
+        assert!(json["error"].is_null());
+        assert_eq!(json["compute_units"], result.compute_units());
+        assert_eq!(json["fee"], LAMPORTS_PER_SIGNATURE);
+        assert_eq!(json["logs"].as_array().unwrap().len(), result.logs().len());
+        assert_eq!(json["inner_instructions"], serde_json::json!([]));
+        assert!(json["account_changes"].is_null());
     }
 
     #[test]
-    fn test_transaction_result_failure() {
+    fn test_transaction_result_to_json_inner_instructions_and_changes() {
         let mut svm = LiteSVM::new();
-        let payer = Keypair::new(); // Unfunded account
-
-        // This should fail due to insufficient funds
-        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
-        let result = svm.send_instruction(ix, &[&payer]).unwrap();
-
-        assert!(!result.is_success());
-        assert!(result.error().is_some());
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&payer, 6).unwrap();
+
+        let owner = Pubkey::new_unique();
+        let ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            &owner,
+            &mint.pubkey(),
+            &spl_token::id(),
+        );
+        let change = AccountChange {
+            address: payer.pubkey(),
+            lamports_before: 10,
+            lamports_after: 4,
+            data_len_before: 0,
+            data_len_after: 0,
+            owner_before: Pubkey::default(),
+            owner_after: Pubkey::default(),
+            data_changed: false,
+        };
+        let result = svm
+            .send_instruction(ix, &[&payer])
+            .unwrap()
+            .with_account_changes(vec![change]);
+
+        let json = result.to_json();
+        let inner = json["inner_instructions"].as_array().unwrap();
+        assert_eq!(inner.len(), result.cpi_count());
+        let cpis = result.cpis();
+        assert_eq!(inner[0]["program_id"], cpis[0].program_id.to_string());
+        assert_eq!(inner[0]["stack_height"], 2);
+        assert_eq!(inner[0]["data"].as_str().unwrap().len(), cpis[0].data.len() * 2);
+
+        let changes = json["account_changes"].as_array().unwrap();
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0]["address"], payer.pubkey().to_string());
+        assert_eq!(changes[0]["lamports_after"], 4);
+
+        // The report round-trips through a string for CI artifacts
+        let parsed: serde_json::Value = serde_json::from_str(&json.to_string()).unwrap();
+        assert_eq!(parsed, json);
     }
 
     #[test]
-    fn test_transaction_result_assert_failure() {
+    fn test_transaction_result_to_json_failure() {
         let mut svm = LiteSVM::new();
         let payer = Keypair::new(); // Unfunded account
 
         let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
         let result = svm.send_instruction(ix, &[&payer]).unwrap();
 
-        // Should not panic when asserting failure on a failed transaction
-        result.assert_failure();
+        let json = result.to_json();
+        assert_eq!(json["success"], false);
+        assert!(json["error"].as_str().unwrap().contains("AccountNotFound"));
     }
 
     #[test]
-    #[should_panic(expected = "Expected transaction to fail")]
-    fn test_transaction_result_assert_failure_on_success() {
-        let mut svm = LiteSVM::new();
-        let payer = svm.create_funded_account(10_000_000_000).unwrap();
-
-        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
-        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+    fn test_normalize_compute_units() {
+        assert_eq!(
+            normalize_compute_units("Program 11111111111111111111111111111111 consumed 150 of 200000 compute units"),
+            "Program 11111111111111111111111111111111 consumed <N> of <N> compute units"
+        );
+        assert_eq!(normalize_compute_units("Program log: amount 42"), "Program log: amount 42");
+    }
 
-        // Should panic when asserting failure on a successful transaction
-        result.assert_failure();
+    #[test]
+    fn test_normalize_addresses() {
+        let address = Pubkey::new_unique();
+        assert_eq!(
+            normalize_addresses(&format!("Program {} invoke [1]", address)),
+            "Program <address> invoke [1]"
+        );
+        assert_eq!(
+            normalize_addresses(&format!("Program log: owner: {},", address)),
+            "Program log: owner: <address>,"
+        );
     }
 
     #[test]
-    fn test_transaction_result_assert_error() {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new(); // Unfunded account
+    fn test_assert_logs_match_golden() {
+        let path = std::env::temp_dir()
+            .join(format!("litesvm-utils-golden-{}", Pubkey::new_unique()))
+            .join("transfer.log");
+        let path = path.to_str().unwrap();
 
-        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000);
         let result = svm.send_instruction(ix, &[&payer]).unwrap();
 
-        // Should contain "AccountNotFound" in the error (account doesn't exist)
-        result.assert_error("AccountNotFound");
+        result.check_golden(path, normalize_compute_units, true);
+        result.assert_logs_match_golden(path);
+
+        std::fs::remove_dir_all(std::path::Path::new(path).parent().unwrap()).unwrap();
     }
 
     #[test]
-    #[should_panic(expected = "Transaction failed with unexpected error")]
-    fn test_transaction_result_assert_error_wrong_message() {
-        let mut svm = LiteSVM::new();
-        let payer = Keypair::new(); // Unfunded account
+    #[should_panic(expected = "don't match golden file")]
+    fn test_assert_logs_match_golden_mismatch() {
+        let path = std::env::temp_dir().join(format!("litesvm-utils-golden-{}.log", Pubkey::new_unique()));
+        let path = path.to_str().unwrap();
+
+        let result = result_with_logs(&["Program log: first"]);
+        result.check_golden(path, normalize_compute_units, true);
+
+        let changed = result_with_logs(&["Program log: second"]);
+        let outcome = std::panic::catch_unwind(|| changed.check_golden(path, normalize_compute_units, false));
+        std::fs::remove_file(path).unwrap();
+        std::panic::resume_unwind(outcome.unwrap_err());
+    }
 
-        let ix = system_instruction::transfer(&payer.pubkey(), &Keypair::new().pubkey(), 1_000_000);
-        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+    #[test]
+    fn test_assert_compute_within_baseline() {
+        let path = std::env::temp_dir().join(format!("litesvm-utils-cu-{}.json", Pubkey::new_unique()));
+        let path = path.to_str().unwrap();
 
-        // Should panic when expecting wrong error message
-        result.assert_error("this error does not exist");
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&payer, 6).unwrap();
+        let ata = svm.create_associated_token_account(&mint.pubkey(), &payer).unwrap();
+        let mint_to = |amount| {
+            spl_token::instruction::mint_to(&spl_token::id(), &mint.pubkey(), &ata, &payer.pubkey(), &[], amount)
+                .unwrap()
+        };
+
+        let result = svm.send_instruction(mint_to(1), &[&payer]).unwrap();
+        result.assert_compute_within_baseline_at(path, "mint_to", 0.0);
+        let recorded = crate::cu_baseline::CuBaseline::load(path).unwrap().get("mint_to");
+        assert_eq!(recorded, Some(result.compute_units()));
+
+        let result = svm.send_instruction(mint_to(2), &[&payer]).unwrap();
+        result.assert_compute_within_baseline_at(path, "mint_to", 0.0);
+        std::fs::remove_file(path).unwrap();
     }
 
     #[test]
-    fn test_send_multiple_instructions() {
+    fn test_send_serialized_transaction() {
         let mut svm = LiteSVM::new();
         let payer = svm.create_funded_account(10_000_000_000).unwrap();
-        let recipient1 = Keypair::new();
-        let recipient2 = Keypair::new();
+        let recipient = Keypair::new();
 
-        // Send two transfers in one transaction
-        let ix1 = system_instruction::transfer(&payer.pubkey(), &recipient1.pubkey(), 1_000_000);
-        let ix2 = system_instruction::transfer(&payer.pubkey(), &recipient2.pubkey(), 2_000_000);
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&payer.pubkey()),
+            &[&payer],
+            svm.latest_blockhash(),
+        );
+        let bytes = bincode::serialize(&tx).unwrap();
 
-        let result = svm.send_instructions(&[ix1, ix2], &[&payer]).unwrap();
+        let result = svm.send_serialized_transaction(&bytes).unwrap();
         result.assert_success();
+        assert_eq!(result.fee, Some(LAMPORTS_PER_SIGNATURE));
+        assert_eq!(svm.get_balance(&recipient.pubkey()).unwrap(), 1_000_000);
+    }
 
-        // Verify both transfers succeeded
-        let balance1 = svm.get_balance(&recipient1.pubkey()).unwrap();
-        let balance2 = svm.get_balance(&recipient2.pubkey()).unwrap();
-        assert_eq!(balance1, 1_000_000);
-        assert_eq!(balance2, 2_000_000);
+    #[test]
+    fn test_send_serialized_transaction_invalid_bytes() {
+        let mut svm = LiteSVM::new();
+        let result = svm.send_serialized_transaction(&[1, 2, 3]);
+        assert!(matches!(result, Err(TransactionError::BuildError(_))));
     }
 
     #[test]
-    fn test_send_instruction_no_signers() {
+    fn test_send_with_nonce() {
         let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
         let recipient = Keypair::new();
+        let (nonce_account, nonce) = svm.create_nonce_account(&authority).unwrap();
 
-        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        // The nonce can only be advanced once the blockhash it was derived from has moved on
+        svm.expire_blockhash();
 
-        // Should error when no signers provided
-        let result = svm.send_instruction(ix, &[]);
-        assert!(result.is_err());
-        match result {
-            Err(TransactionError::BuildError(msg)) => {
-                assert!(msg.contains("No signers"));
+        let ix = system_instruction::transfer(&authority.pubkey(), &recipient.pubkey(), 1_000_000);
+        let result = svm
+            .send_with_nonce(&[ix], &nonce_account.pubkey(), &authority, &[&authority])
+            .unwrap();
+        result.assert_success();
+
+        assert_eq!(svm.get_balance(&recipient.pubkey()).unwrap(), 1_000_000);
+        let advanced = durable_nonce(&svm, &nonce_account.pubkey()).unwrap();
+        assert_ne!(advanced, nonce);
+    }
+
+    #[test]
+    fn test_send_with_blockhash() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let transfer = || system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000);
+
+        let stale = svm.latest_blockhash();
+        svm.expire_blockhash();
+        svm.send_with_blockhash(&[transfer()], &[&payer], stale)
+            .unwrap()
+            .assert_error("BlockhashNotFound");
+
+        // Resending the exact same transaction is rejected as a duplicate
+        let ix = transfer();
+        let blockhash = svm.latest_blockhash();
+        svm.send_with_blockhash(std::slice::from_ref(&ix), &[&payer], blockhash)
+            .unwrap()
+            .assert_success();
+        svm.send_with_blockhash(&[ix], &[&payer], blockhash)
+            .unwrap()
+            .assert_error("AlreadyProcessed");
+
+        let result = svm.send_with_blockhash(&[transfer()], &[], blockhash);
+        assert!(matches!(result, Err(TransactionError::BuildError(_))));
+    }
+
+    #[test]
+    fn test_transaction_size_limit() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let transfers = |count: usize| -> Vec<Instruction> {
+            (0..count)
+                .map(|_| system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000))
+                .collect()
+        };
+
+        let ixs = transfers(1);
+        let tx = Transaction::new_signed_with_payer(&ixs, Some(&payer.pubkey()), &[&payer], svm.latest_blockhash());
+        assert_eq!(tx_size(&ixs, &payer.pubkey()), bincode::serialized_size(&tx).unwrap() as usize);
+        assert!(tx_size(&ixs, &payer.pubkey()) + 64 <= MAX_TRANSACTION_SIZE);
+
+        let ixs = transfers(30);
+        assert!(tx_size(&ixs, &payer.pubkey()) > MAX_TRANSACTION_SIZE);
+        match svm.send_instructions(&ixs, &[&payer]) {
+            Err(TransactionError::TooLarge { size, limit, account_count }) => {
+                assert_eq!(size, tx_size(&ixs, &payer.pubkey()));
+                assert_eq!(limit, MAX_TRANSACTION_SIZE);
+                assert_eq!(account_count, 32);
             }
-            _ => panic!("Expected BuildError"),
+            other => panic!("Expected TooLarge, got {:?}", other.map(|r| r.is_success())),
         }
+        assert!(svm.get_balance(&payer.pubkey()).unwrap() == 10_000_000_000);
     }
 
     #[test]
-    fn test_send_instructions_no_signers() {
+    fn test_send_instruction_with_nonce() {
         let mut svm = LiteSVM::new();
-        let payer = Keypair::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
         let recipient = Keypair::new();
+        let (nonce_account, nonce) = svm.create_nonce_account(&authority).unwrap();
+        let authority_balance = svm.get_balance(&authority.pubkey()).unwrap();
+        svm.expire_blockhash();
 
+        // The nonce authority is not a signer of the instruction and doesn't pay the fee
         let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        let result = svm
+            .send_instruction_with_nonce(ix, &[&payer], &nonce_account.pubkey(), &authority)
+            .unwrap();
+        result.assert_success();
 
-        // Should error when no signers provided
-        let result = svm.send_instructions(&[ix], &[]);
-        assert!(result.is_err());
+        assert_eq!(svm.get_balance(&recipient.pubkey()).unwrap(), 1_000_000);
+        assert_eq!(svm.get_balance(&authority.pubkey()).unwrap(), authority_balance);
+        assert_ne!(durable_nonce(&svm, &nonce_account.pubkey()).unwrap(), nonce);
     }
 
     #[test]
-    fn test_transaction_result_debug() {
+    fn test_send_with_nonce_not_a_nonce_account() {
+        let mut svm = LiteSVM::new();
+        let authority = svm.create_funded_account(10_000_000_000).unwrap();
+
+        let ix = system_instruction::transfer(&authority.pubkey(), &Keypair::new().pubkey(), 1);
+        let result = svm.send_with_nonce(&[ix], &authority.pubkey(), &authority, &[&authority]);
+        assert!(matches!(result, Err(TransactionError::BuildError(_))));
+    }
+
+    #[test]
+    fn test_send_v0_instructions_with_lookup_table() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(100_000_000_000).unwrap();
+        let recipients: Vec<Pubkey> = (0..40).map(|_| Pubkey::new_unique()).collect();
+
+        let table = svm.create_lookup_table(&payer, &recipients).unwrap();
+
+        // 40 recipients plus payer and program don't fit in a legacy transaction
+        let instructions: Vec<Instruction> = recipients
+            .iter()
+            .map(|recipient| system_instruction::transfer(&payer.pubkey(), recipient, 1_000_000))
+            .collect();
+        let result = svm.send_v0_instructions(&instructions, &[&payer], &[table]).unwrap();
+        result.assert_success();
+
+        for recipient in &recipients {
+            assert_eq!(svm.get_balance(recipient).unwrap(), 1_000_000);
+        }
+    }
+
+    #[test]
+    fn test_send_v0_instruction_extended_table() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let recipient = Pubkey::new_unique();
+
+        let table = svm.create_lookup_table(&payer, &[]).unwrap();
+        svm.extend_lookup_table(&table, &payer, &[recipient]).unwrap();
+        // A second table from the same authority gets its own address
+        let other = svm.create_lookup_table(&payer, &[]).unwrap();
+        assert_ne!(table, other);
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient, 1_000_000);
+        svm.send_v0_instruction(ix, &[&payer], &[table]).unwrap().assert_success();
+        assert_eq!(svm.get_balance(&recipient).unwrap(), 1_000_000);
+
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient, 1_000_000);
+        let result = svm.send_v0_instruction(ix, &[&payer], &[Pubkey::new_unique()]);
+        assert!(matches!(result, Err(TransactionError::BuildError(_))));
+    }
+
+    #[test]
+    fn test_send_versioned_transaction_result() {
         let mut svm = LiteSVM::new();
         let payer = svm.create_funded_account(10_000_000_000).unwrap();
         let recipient = Keypair::new();
 
         let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        let message = v0::Message::try_compile(&payer.pubkey(), &[ix], &[], svm.latest_blockhash()).unwrap();
+        let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), &[&payer]).unwrap();
+        let result = svm.send_versioned_transaction_result(tx).unwrap();
+        result.assert_success();
+        assert_eq!(svm.get_balance(&recipient.pubkey()).unwrap(), 1_000_000);
+
+        // Legacy messages go through the same path, and failures are wrapped rather than returned as errors
+        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 100_000_000_000);
+        let legacy = Transaction::new_signed_with_payer(&[ix], Some(&payer.pubkey()), &[&payer], svm.latest_blockhash());
+        let result = svm.send_versioned_transaction_result(legacy.into()).unwrap();
+        result.assert_failure();
+    }
+
+    #[test]
+    fn test_return_data() {
+        let result = TransactionResult::new(TransactionMetadata::default(), None);
+        assert_eq!(result.return_data(), None);
+        assert_eq!(result.return_data_program(), None);
+
+        let program_id = Pubkey::new_unique();
+        let mut meta = TransactionMetadata::default();
+        meta.return_data.program_id = program_id;
+        meta.return_data.data = vec![42, 0, 0, 0];
+        let result = TransactionResult::new(meta, None);
+        assert_eq!(result.return_data(), Some(&[42, 0, 0, 0][..]));
+        assert_eq!(result.return_data_program(), Some(&program_id));
+    }
+
+    #[test]
+    fn test_cpi_inspection() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&payer, 6).unwrap();
+        let owner = Pubkey::new_unique();
+
+        // Creating an ATA invokes the system and token programs
+        let ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            &owner,
+            &mint.pubkey(),
+            &spl_token::id(),
+        );
         let result = svm.send_instruction(ix, &[&payer]).unwrap();
+        result.assert_success();
 
-        // Should be able to format as debug
-        let debug_str = format!("{:?}", result);
-        assert!(debug_str.contains("TransactionResult"));
+        result
+            .assert_cpi_to(&spl_token::id())
+            .assert_cpi_to(&solana_system_interface::program::id());
+        assert_eq!(result.cpi_count(), result.inner_instructions()[0].len());
+        assert_eq!(
+            result.cpi_count(),
+            result.cpi_count_to(&spl_token::id()) + result.cpi_count_to(&solana_system_interface::program::id())
+        );
+        assert_eq!(result.cpi_count_to(&Pubkey::new_unique()), 0);
+
+        let cpis = result.cpis();
+        assert!(cpis.iter().all(|cpi| cpi.instruction_index == 0 && cpi.stack_height == 2));
+        let ata = spl_associated_token_account::get_associated_token_address(&owner, &mint.pubkey());
+        assert!(cpis
+            .iter()
+            .any(|cpi| cpi.program_id == spl_token::id() && cpi.accounts.contains(&ata)));
     }
 
     #[test]
-    fn test_transaction_result_print_logs() {
+    #[should_panic(expected = "Expected a CPI to")]
+    fn test_assert_cpi_to_fails() {
         let mut svm = LiteSVM::new();
         let payer = svm.create_funded_account(10_000_000_000).unwrap();
-        let recipient = Keypair::new();
 
-        let ix = system_instruction::transfer(&payer.pubkey(), &recipient.pubkey(), 1_000_000);
+        let ix = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000);
         let result = svm.send_instruction(ix, &[&payer]).unwrap();
+        assert_eq!(result.cpi_count(), 0);
+        result.assert_cpi_to(&spl_token::id());
+    }
 
-        // Should not panic when printing logs
-        result.print_logs();
+    #[test]
+    fn test_parse_call_graph() {
+        let outer = Pubkey::new_unique();
+        let inner = Pubkey::new_unique();
+        let system = solana_system_interface::program::id();
+        let logs: Vec<String> = vec![
+            format!("Program {} invoke [1]", outer),
+            "Program log: Instruction: Swap".to_string(),
+            format!("Program {} invoke [2]", system),
+            format!("Program {} success", system),
+            format!("Program {} invoke [2]", inner),
+            "Program log: Transfer".to_string(),
+            format!("Program {} consumed 4000 of 190000 compute units", inner),
+            format!("Program {} success", inner),
+            format!("Program {} consumed 12000 of 200000 compute units", outer),
+            format!("Program {} success", outer),
+            format!("Program {} invoke [1]", inner),
+            format!("Program {} consumed 500 of 200000 compute units", inner),
+            format!("Program {} failed: custom program error: 0x1", inner),
+        ];
+
+        let graph = parse_call_graph(&logs);
+        assert_eq!(graph.len(), 2);
+
+        let swap = &graph[0];
+        assert_eq!(swap.program_id, outer);
+        assert_eq!(swap.depth, 1);
+        assert_eq!(swap.compute_units, Some(12000));
+        assert!(swap.success);
+        assert_eq!(swap.logs, vec!["Instruction: Swap"]);
+        assert_eq!(swap.children.len(), 2);
+        assert_eq!(swap.children[0].program_id, system);
+        assert_eq!(swap.children[0].compute_units, None);
+        assert_eq!(swap.children[1].depth, 2);
+        assert_eq!(swap.children[1].compute_units, Some(4000));
+        assert_eq!(swap.children[1].logs, vec!["Transfer"]);
+        assert_eq!(swap.iter().count(), 3);
+
+        let failed = &graph[1];
+        assert!(!failed.success);
+        assert_eq!(failed.error.as_deref(), Some("custom program error: 0x1"));
+    }
+
+    #[test]
+    fn test_parse_call_graph_truncated() {
+        let program = Pubkey::new_unique();
+        let logs = vec![
+            format!("Program {} invoke [1]", program),
+            format!("Program {} invoke [2]", program),
+            "Log truncated".to_string(),
+        ];
+
+        let graph = parse_call_graph(&logs);
+        assert_eq!(graph.len(), 1);
+        assert!(!graph[0].success);
+        assert_eq!(graph[0].error, None);
+        assert_eq!(graph[0].children.len(), 1);
+    }
+
+    #[test]
+    fn test_assert_logs_in_order() {
+        let result = result_with_logs(&[
+            "Program log: deposit recorded",
+            "Program log: fee charged",
+            "Program log: unrelated",
+            "Program log: event emitted",
+        ]);
+        result
+            .assert_logs_in_order(&["deposit recorded", "fee charged", "event emitted"])
+            .assert_logs_in_order(&["deposit", "event"])
+            .assert_logs_in_order(&[]);
+
+        let out_of_order = std::panic::catch_unwind(|| {
+            result.assert_logs_in_order(&["deposit recorded", "event emitted", "fee charged"]);
+        });
+        let message = *out_of_order.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.starts_with("Log 'fee charged' appears before 'event emitted'"), "{}", message);
+
+        // A repeated message needs a second matching line
+        let repeated = std::panic::catch_unwind(|| {
+            result.assert_logs_in_order(&["fee charged", "fee charged"]);
+        });
+        assert!(repeated.is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected log 'withdrawal' not found")]
+    fn test_assert_logs_in_order_missing() {
+        let result = result_with_logs(&["Program log: deposit recorded"]);
+        result.assert_logs_in_order(&["deposit recorded", "withdrawal"]);
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn test_log_regex_matching() {
+        let result = result_with_logs(&[
+            "Program log: Deposited 1500 lamports",
+            "Program log: fee: 25",
+            "Program log: Deposited 300 lamports",
+        ]);
+        let deposits = regex::Regex::new(r"Deposited \d+ lamports").unwrap();
+        assert!(result.has_log_matching(&deposits));
+        assert_eq!(
+            result.find_logs_matching(&deposits),
+            vec!["Program log: Deposited 1500 lamports", "Program log: Deposited 300 lamports"]
+        );
+
+        let withdrawals = regex::Regex::new(r"Withdrew \d+").unwrap();
+        assert!(!result.has_log_matching(&withdrawals));
+        assert!(result.find_logs_matching(&withdrawals).is_empty());
+    }
+
+    #[test]
+    fn test_label_addresses() {
+        let vault = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let labels = HashMap::from([(vault, "vault".to_string())]);
+
+        assert_eq!(
+            label_addresses(&format!("Program log: {} -> ({}), {}:", other, vault, vault), &labels),
+            format!("Program log: {} -> (vault), vault:", other)
+        );
+        assert_eq!(label_addresses("Program log: 42", &labels), "Program log: 42");
+    }
+
+    #[test]
+    fn test_labels_in_output() {
+        let vault = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let labels = HashMap::from([(vault, "vault".to_string()), (user, "user".to_string())]);
+        let result = TransactionResult::new_failed(
+            "InstructionError(0, Custom(1))".to_string(),
+            TransactionMetadata {
+                logs: vec![format!("Program log: Insufficient funds in {}", vault)],
+                ..Default::default()
+            },
+            None,
+        )
+        .with_account_changes(vec![AccountChange {
+            address: user,
+            lamports_before: 100,
+            lamports_after: 40,
+            data_len_before: 0,
+            data_len_after: 0,
+            owner_before: Pubkey::default(),
+            owner_after: Pubkey::default(),
+            data_changed: false,
+        }])
+        .with_labels(&labels);
+
+        assert_eq!(result.logs_text(), "Program log: Insufficient funds in vault");
+        assert_eq!(result.account_changes_text(), vec!["user: lamports 100 -> 40 (-60)"]);
+
+        let panic = std::panic::catch_unwind(|| {
+            result.assert_success();
+        });
+        let message = *panic.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("Insufficient funds in vault"), "{}", message);
+        // Raw logs keep the addresses
+        assert!(result.logs()[0].contains(&vault.to_string()));
+    }
+
+    #[test]
+    fn test_logs_for() {
+        let outer = Pubkey::new_unique();
+        let token = spl_token::id();
+        let logs: Vec<String> = vec![
+            format!("Program {} invoke [1]", outer),
+            "Program log: Instruction: Deposit".to_string(),
+            format!("Program {} invoke [2]", token),
+            "Program log: Instruction: Transfer".to_string(),
+            format!("Program {} consumed 4000 of 190000 compute units", token),
+            format!("Program {} success", token),
+            "Program log: Transfer complete".to_string(),
+            format!("Program {} consumed 12000 of 200000 compute units", outer),
+            format!("Program {} success", outer),
+        ];
+        let result = result_with_logs(&logs.iter().map(String::as_str).collect::<Vec<_>>());
+
+        assert_eq!(
+            result.logs_for(&outer),
+            vec!["Program log: Instruction: Deposit", "Program log: Transfer complete"]
+        );
+        assert_eq!(result.logs_for(&token), vec!["Program log: Instruction: Transfer"]);
+        assert!(result.logs_for(&Pubkey::new_unique()).is_empty());
+    }
+
+    #[test]
+    fn test_call_graph() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&payer, 6).unwrap();
+
+        let ix = spl_associated_token_account::instruction::create_associated_token_account(
+            &payer.pubkey(),
+            &Pubkey::new_unique(),
+            &mint.pubkey(),
+            &spl_token::id(),
+        );
+        let result = svm.send_instruction(ix, &[&payer]).unwrap();
+
+        let graph = result.call_graph();
+        assert_eq!(graph.len(), 1);
+        assert_eq!(graph[0].program_id, spl_associated_token_account::id());
+        assert_eq!(graph[0].compute_units, Some(result.compute_units()));
+        assert!(graph[0].iter().all(|invocation| invocation.success));
+        let token_cpis = graph[0].children.iter().filter(|child| child.program_id == spl_token::id()).count();
+        assert_eq!(token_cpis, result.cpi_count_to(&spl_token::id()));
+    }
+
+    #[test]
+    fn test_compute_units_per_instruction() {
+        let mut svm = LiteSVM::new();
+        let payer = svm.create_funded_account(10_000_000_000).unwrap();
+        let mint = svm.create_token_mint(&payer, 6).unwrap();
+        let create_ata = || {
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &payer.pubkey(),
+                &Pubkey::new_unique(),
+                &mint.pubkey(),
+                &spl_token::id(),
+            )
+        };
+        let transfer = system_instruction::transfer(&payer.pubkey(), &Pubkey::new_unique(), 1_000_000);
+
+        let result = svm
+            .send_instructions(&[create_ata(), transfer, create_ata()], &[&payer])
+            .unwrap();
+        result.assert_success();
+
+        let per_ix = result.compute_units_per_instruction();
+        assert_eq!(per_ix.len(), 3);
+        assert!(per_ix[0] > 0);
+        assert_eq!(per_ix[1], 0);
+        assert!(per_ix[2] > 0);
+        assert!(per_ix.iter().sum::<u64>() <= result.compute_units());
     }
 
     #[test]